@@ -1 +1,2 @@
-pub mod redis;
\ No newline at end of file
+pub mod mem_db;
+pub mod redis;
@@ -1,16 +1,25 @@
-use redis::{db::MemoryDatabase, server::Redis};
-use std::{io::Error, sync::Arc};
+use redis_starter_rust::{
+    mem_db,
+    redis::{
+        client::Client, config::Config, db::MemoryDatabase, expire::spawn_active_expire,
+        persistence::spawn_save_scheduler, rdb, respv2::RESPv2Decoder, server::Redis,
+    },
+};
+use std::{
+    io::{Error, ErrorKind},
+    sync::Arc,
+};
 use tokio::{
-    io::{AsyncReadExt, AsyncWriteExt, BufReader},
+    io::{AsyncReadExt, AsyncWriteExt},
     net::TcpStream,
-    sync::Mutex,
 };
 
-mod mem_db;
-mod redis;
-
 #[tokio::main]
 async fn main() {
+    let config = Config::from_args(std::env::args().skip(1)).unwrap_or_else(|err| {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    });
     let listener = tokio::net::TcpListener::bind("127.0.0.1:6379")
         .await
         .unwrap();
@@ -19,25 +28,49 @@ async fn main() {
         .enable_all()
         .build()
         .unwrap();
-    let db = mem_db::MemDB::new();
+    let mut db = mem_db::MemDB::new();
+    let aof_path = config.aof_path();
+    let load_aof = config.appendonly && aof_path.exists();
+
+    if !load_aof {
+        if let Err(err) = rdb::load(&config.rdb_path(), &mut db) {
+            eprintln!("Error loading {}: {}", config.rdb_path().display(), err);
+            std::process::exit(1);
+        }
+    }
+
+    let redis = Arc::new(Redis::with_config(db, config));
+
+    if load_aof {
+        if let Err(err) = redis.load_aof().await {
+            eprintln!("Error loading {}: {}", aof_path.display(), err);
+            std::process::exit(1);
+        }
+    }
 
-    let db = Arc::new(Mutex::new(db));
+    if let Err(err) = redis.persistence.open_aof(&mut *redis.db.lock().await) {
+        eprintln!("Error opening {}: {}", aof_path.display(), err);
+        std::process::exit(1);
+    }
+
+    spawn_active_expire(Arc::clone(&redis.db));
+    spawn_save_scheduler(Arc::clone(&redis.db), redis.persistence.clone());
 
     loop {
         let (mut stream, ip) = listener.accept().await.unwrap();
 
         println!("Connection with: {}", ip);
-        let db = Arc::clone(&db);
+        let redis = Arc::clone(&redis);
 
         thread_pool.spawn(async move {
-            let result = handler(&mut stream, db).await;
+            let result = handler(&mut stream, redis).await;
 
             if let Err(ref e) = result {
                 if e.kind() != std::io::ErrorKind::BrokenPipe {
                     println!("Connection closed with: {}", ip);
                 } else {
                     stream
-                        .write_all(format!("-ERR {}\r\n", e.to_string()).as_bytes())
+                        .write_all(format!("-ERR {}\r\n", e).as_bytes())
                         .await
                         .unwrap();
                 }
@@ -46,31 +79,91 @@ async fn main() {
     }
 }
 
-async fn handler<'a>(
+async fn handler(
+    stream: &mut TcpStream,
+    redis: Arc<Redis<impl MemoryDatabase>>,
+) -> Result<(), Error> {
+    let mut client = Client::new();
+    let result = serve_client(stream, &redis, &mut client).await;
+
+    redis.disconnect(&mut client).await;
+
+    result
+}
+
+async fn serve_client(
     stream: &mut TcpStream,
-    db: Arc<Mutex<impl MemoryDatabase>>,
+    redis: &Redis<impl MemoryDatabase>,
+    client: &mut Client,
 ) -> Result<(), Error> {
+    let mut decoder = RESPv2Decoder::new();
+
     loop {
-        let db = Arc::clone(&db);
-        let (read, mut write) = stream.split();
-        let string = read_to_string(read).await?;
+        let mut frames = vec![];
+
+        let decoded = loop {
+            match decoder.next_frame() {
+                Ok(Some(frame)) => frames.push(frame),
+                Ok(None) => break Ok(()),
+                Err(e) => break Err(e),
+            }
+        };
+
+        if !frames.is_empty() {
+            let disconnected = Arc::clone(&client.disconnected);
+            let pipeline = redis.handle_pipeline(frames, client);
+            let mut closed = None;
 
-        let response = Redis::handle(string, db).await?;
+            tokio::pin!(pipeline);
 
-        write.write_all(response.as_bytes()).await?;
+            let response = loop {
+                tokio::select! {
+                    response = &mut pipeline => break response,
+                    read = read_to_decoder(stream, &mut decoder), if closed.is_none() => {
+                        if let Err(e) = read {
+                            disconnected.notify_one();
+                            closed = Some(e);
+                        }
+                    }
+                }
+            };
+
+            let written = stream.write_all(&response).await;
+
+            if let Some(e) = closed {
+                return Err(e);
+            }
+
+            written?;
+        }
+
+        if let Err(e) = decoded {
+            stream
+                .write_all(format!("-ERR Protocol error: {}\r\n", e).as_bytes())
+                .await?;
+
+            return Err(Error::new(ErrorKind::InvalidData, e.to_string()));
+        }
+
+        tokio::select! {
+            read = read_to_decoder(stream, &mut decoder) => read?,
+            Some(message) = client.next_message() => {
+                stream.write_all(&message.serialize(client.protocol)).await?;
+            }
+        }
     }
 }
 
-async fn read_to_string(read: tokio::net::tcp::ReadHalf<'_>) -> Result<String, Error> {
-    let mut reader = BufReader::new(read);
-    let mut buffer = [0; 1024];
+async fn read_to_decoder(stream: &mut TcpStream, decoder: &mut RESPv2Decoder) -> Result<(), Error> {
+    let mut buffer = [0; 4096];
+
+    let length = stream.read(&mut buffer).await?;
 
-    let length = reader.read(&mut buffer).await?;
+    if length == 0 {
+        return Err(Error::new(ErrorKind::UnexpectedEof, "Connection closed."));
+    }
 
-    let string = String::from_utf8(buffer[..length].to_vec())
-        .unwrap()
-        .trim()
-        .to_string();
+    decoder.feed(&buffer[..length]);
 
-    Ok(string)
+    Ok(())
 }
@@ -1,30 +1,252 @@
-use std::{collections::HashMap, io::Error};
+use std::{collections::HashMap, io::Error, sync::Arc};
 
-use crate::redis::db::MemoryDatabase;
+use crate::redis::{
+    db::{unix_time_ms, MemoryDatabase, Snapshot},
+    expire::ExpireStats,
+    types::Value,
+};
 
+#[derive(Default)]
+struct WatchedKey {
+    version: u64,
+    watchers: usize,
+}
+
+#[derive(Default)]
 pub struct MemDB {
-    data: HashMap<String, String>,
+    data: HashMap<Vec<u8>, Arc<Value>>,
+    expires: HashMap<Vec<u8>, (u64, usize)>,
+    expire_keys: Vec<Vec<u8>>,
+    expire_stats: ExpireStats,
+    watched: HashMap<Vec<u8>, WatchedKey>,
+    dirty: u64,
+    rng: u64,
 }
 
 impl MemDB {
     pub fn new() -> Self {
         Self {
             data: HashMap::new(),
+            expires: HashMap::new(),
+            expire_keys: vec![],
+            expire_stats: ExpireStats::default(),
+            watched: HashMap::new(),
+            dirty: 0,
+            rng: unix_time_ms() | 1,
+        }
+    }
+
+    fn expire_if_needed(&mut self, key: &[u8]) {
+        if let Some((expires_at, _)) = self.expires.get(key) {
+            if *expires_at <= unix_time_ms() {
+                self.remove_expiry(key);
+                self.data.remove(key);
+                self.touch(key);
+                self.expire_stats.expired_keys += 1;
+            }
         }
     }
+
+    fn insert_expiry(&mut self, key: &[u8], expires_at: u64) {
+        match self.expires.get_mut(key) {
+            Some((current, _)) => *current = expires_at,
+            None => {
+                self.expires
+                    .insert(key.to_vec(), (expires_at, self.expire_keys.len()));
+                self.expire_keys.push(key.to_vec());
+            }
+        }
+    }
+
+    fn remove_expiry(&mut self, key: &[u8]) -> Option<u64> {
+        let (expires_at, position) = self.expires.remove(key)?;
+
+        self.expire_keys.swap_remove(position);
+
+        if let Some(moved) = self.expire_keys.get(position) {
+            if let Some((_, moved_position)) = self.expires.get_mut(moved) {
+                *moved_position = position;
+            }
+        }
+
+        Some(expires_at)
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
 }
 
 impl MemoryDatabase for MemDB {
-    fn set(&mut self, key: &str, value: &str) -> Result<(), Error> {
-        self.data.insert(key.to_string(), value.to_string());
+    fn set(&mut self, key: &[u8], value: Value, expires_at: Option<u64>) -> Result<(), Error> {
+        self.data.insert(key.to_vec(), Arc::new(value));
+        self.touch(key);
+
+        match expires_at {
+            Some(expires_at) => self.insert_expiry(key, expires_at),
+            None => {
+                self.remove_expiry(key);
+            }
+        };
+
         Ok(())
     }
 
-    fn get(&self, key: &str) -> Option<String> {
-        self.data.get(key).map(|v| v.to_string())
+    fn get(&mut self, key: &[u8]) -> Option<&Value> {
+        self.expire_if_needed(key);
+        self.data.get(key).map(|value| value.as_ref())
+    }
+
+    fn get_mut(&mut self, key: &[u8]) -> Option<&mut Value> {
+        self.expire_if_needed(key);
+        self.data.get_mut(key).map(Arc::make_mut)
+    }
+
+    fn touch(&mut self, key: &[u8]) {
+        self.dirty += 1;
+
+        if let Some(watched) = self.watched.get_mut(key) {
+            watched.version += 1;
+        }
+    }
+
+    fn del(&mut self, key: &[u8]) -> Option<Value> {
+        self.expire_if_needed(key);
+        self.remove_expiry(key);
+
+        let value = self.data.remove(key)?;
+
+        self.touch(key);
+
+        Some(Arc::unwrap_or_clone(value))
+    }
+
+    fn exists(&mut self, key: &[u8]) -> bool {
+        self.expire_if_needed(key);
+        self.data.contains_key(key)
+    }
+
+    fn get_expiry(&mut self, key: &[u8]) -> Option<u64> {
+        self.expire_if_needed(key);
+        self.expires.get(key).map(|(expires_at, _)| *expires_at)
+    }
+
+    fn set_expiry(&mut self, key: &[u8], expires_at: Option<u64>) -> bool {
+        if !self.exists(key) {
+            return false;
+        }
+
+        match expires_at {
+            Some(expires_at) => self.insert_expiry(key, expires_at),
+            None => {
+                self.remove_expiry(key);
+            }
+        };
+
+        self.touch(key);
+
+        true
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
     }
 
-    fn del(&mut self, key: &str) -> Option<String> {
-        self.data.remove(key)
+    fn expires_len(&self) -> usize {
+        self.expire_keys.len()
     }
-}
\ No newline at end of file
+
+    fn sample_expired(&mut self, count: usize) -> (usize, usize) {
+        let sampled = count.min(self.expire_keys.len());
+        let now = unix_time_ms();
+        let mut expired = 0;
+
+        for _ in 0..sampled {
+            if self.expire_keys.is_empty() {
+                break;
+            }
+
+            let position = (self.next_random() % self.expire_keys.len() as u64) as usize;
+            let key = self.expire_keys[position].clone();
+
+            if matches!(self.expires.get(&key), Some((expires_at, _)) if *expires_at <= now) {
+                self.remove_expiry(&key);
+                self.data.remove(&key);
+                self.touch(&key);
+                expired += 1;
+            }
+        }
+
+        self.expire_stats.expired_keys += expired as u64;
+
+        (sampled, expired)
+    }
+
+    fn expire_stats(&mut self) -> &mut ExpireStats {
+        &mut self.expire_stats
+    }
+
+    fn random(&mut self) -> u64 {
+        self.next_random()
+    }
+
+    fn flush(&mut self) {
+        for (key, watched) in self.watched.iter_mut() {
+            if self.data.contains_key(key) {
+                watched.version += 1;
+            }
+        }
+
+        self.dirty += self.data.len() as u64;
+        self.data.clear();
+        self.expires.clear();
+        self.expire_keys.clear();
+    }
+
+    fn watch(&mut self, key: &[u8]) -> u64 {
+        self.expire_if_needed(key);
+
+        let watched = self.watched.entry(key.to_vec()).or_default();
+
+        watched.watchers += 1;
+        watched.version
+    }
+
+    fn unwatch(&mut self, key: &[u8]) {
+        if let Some(watched) = self.watched.get_mut(key) {
+            watched.watchers -= 1;
+
+            if watched.watchers == 0 {
+                self.watched.remove(key);
+            }
+        }
+    }
+
+    fn version(&mut self, key: &[u8]) -> u64 {
+        self.expire_if_needed(key);
+        self.watched.get(key).map_or(0, |watched| watched.version)
+    }
+
+    fn dirty(&self) -> u64 {
+        self.dirty
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        let now = unix_time_ms();
+
+        self.data
+            .iter()
+            .filter_map(|(key, value)| {
+                let expires_at = self.expires.get(key).map(|(expires_at, _)| *expires_at);
+
+                match expires_at {
+                    Some(expires_at) if expires_at <= now => None,
+                    _ => Some((key.clone(), Arc::clone(value), expires_at)),
+                }
+            })
+            .collect()
+    }
+}
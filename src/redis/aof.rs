@@ -0,0 +1,302 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{Error, ErrorKind, Write},
+    path::Path,
+};
+
+use super::{
+    config::AppendFsync,
+    db::Snapshot,
+    resp3::format_double,
+    respv2::{RESPv2Parser, RESPv2Type},
+    types::{Stream, StreamId, Value},
+};
+
+pub const AOF_REWRITE_ITEMS_PER_CMD: usize = 64;
+
+pub type Commands = Vec<Vec<Vec<u8>>>;
+
+pub struct AppendOnlyFile {
+    file: File,
+    fsync: AppendFsync,
+    fsync_pending: bool,
+    rewrite_buffer: Option<Vec<u8>>,
+}
+
+impl AppendOnlyFile {
+    pub fn open(path: &Path, fsync: AppendFsync) -> Result<Self, Error> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self {
+            file,
+            fsync,
+            fsync_pending: false,
+            rewrite_buffer: None,
+        })
+    }
+
+    pub fn append(&mut self, commands: &[Vec<Vec<u8>>]) -> Result<(), Error> {
+        let mut buffer = vec![];
+
+        for command in commands {
+            encode_command(&mut buffer, command);
+        }
+
+        if let Some(rewrite_buffer) = self.rewrite_buffer.as_mut() {
+            rewrite_buffer.extend_from_slice(&buffer);
+        }
+
+        self.file.write_all(&buffer)?;
+
+        match self.fsync {
+            AppendFsync::Always => self.file.sync_data(),
+            AppendFsync::EverySec => {
+                self.fsync_pending = true;
+                Ok(())
+            }
+            AppendFsync::No => Ok(()),
+        }
+    }
+
+    pub fn take_pending_fsync(&mut self) -> Result<Option<File>, Error> {
+        if !self.fsync_pending {
+            return Ok(None);
+        }
+
+        self.fsync_pending = false;
+        self.file.try_clone().map(Some)
+    }
+
+    pub fn start_rewrite(&mut self) {
+        self.rewrite_buffer = Some(vec![]);
+    }
+
+    pub fn cancel_rewrite(&mut self) {
+        self.rewrite_buffer = None;
+    }
+
+    pub fn finish_rewrite(&mut self, temp: &Path, path: &Path) -> Result<(), Error> {
+        if let Some(buffer) = self.rewrite_buffer.take() {
+            let mut file = OpenOptions::new().append(true).open(temp)?;
+
+            file.write_all(&buffer)?;
+            file.sync_data()?;
+        }
+
+        fs::rename(temp, path)?;
+        self.file = OpenOptions::new().append(true).open(path)?;
+
+        Ok(())
+    }
+}
+
+pub fn encode_command(buffer: &mut Vec<u8>, args: &[Vec<u8>]) {
+    buffer.extend_from_slice(format!("*{}\r\n", args.len()).as_bytes());
+
+    for arg in args {
+        buffer.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+        buffer.extend_from_slice(arg);
+        buffer.extend_from_slice(b"\r\n");
+    }
+}
+
+pub fn parse(data: &[u8]) -> Result<(Commands, usize), Error> {
+    let bad_format = |offset: usize| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Bad file format reading the append only file at offset {}",
+                offset
+            ),
+        )
+    };
+
+    let mut commands = vec![];
+    let mut offset = 0;
+    let mut in_multi = false;
+    let mut valid = (0, 0);
+
+    while offset < data.len() {
+        if data[offset] != b'*' {
+            return Err(bad_format(offset));
+        }
+
+        let (frame, consumed) = match RESPv2Parser::parse_frame(&data[offset..]) {
+            Ok(Some(frame)) => frame,
+            Ok(None) => break,
+            Err(_) => return Err(bad_format(offset)),
+        };
+
+        let args = match frame {
+            RESPv2Type::Array(args) if !args.is_empty() => args
+                .into_iter()
+                .map(|arg| match *arg {
+                    RESPv2Type::String(arg) | RESPv2Type::Bulk(arg) => Ok(arg),
+                    _ => Err(bad_format(offset)),
+                })
+                .collect::<Result<Vec<_>, Error>>()?,
+            _ => return Err(bad_format(offset)),
+        };
+
+        match args[0].to_ascii_lowercase().as_slice() {
+            b"multi" => in_multi = true,
+            b"exec" => in_multi = false,
+            _ => {}
+        }
+
+        offset += consumed;
+        commands.push(args);
+
+        if !in_multi {
+            valid = (commands.len(), offset);
+        }
+    }
+
+    commands.truncate(valid.0);
+
+    Ok((commands, valid.1))
+}
+
+pub fn write_rewrite(path: &Path, snapshot: &Snapshot) -> Result<(), Error> {
+    let mut file = File::create(path)?;
+
+    file.write_all(&rewrite(snapshot))?;
+    file.sync_all()
+}
+
+pub fn rewrite(snapshot: &Snapshot) -> Vec<u8> {
+    let mut buffer = vec![];
+
+    for (key, value, expires_at) in snapshot {
+        for command in rewrite_value(key, value) {
+            encode_command(&mut buffer, &command);
+        }
+
+        if let Some(expires_at) = expires_at {
+            encode_command(
+                &mut buffer,
+                &command(&[b"PEXPIREAT", key, expires_at.to_string().as_bytes()]),
+            );
+        }
+    }
+
+    buffer
+}
+
+fn rewrite_value(key: &[u8], value: &Value) -> Commands {
+    match value {
+        Value::String(string) => vec![command(&[b"SET", key, string])],
+        Value::List(list) => batched(b"RPUSH", key, list.iter().map(|item| vec![item.clone()])),
+        Value::Set(set) => batched(
+            b"SADD",
+            key,
+            set.members().into_iter().map(|member| vec![member]),
+        ),
+        Value::ZSet(zset) => batched(
+            b"ZADD",
+            key,
+            zset.iter()
+                .map(|(member, score)| vec![format_double(score).into_bytes(), member.to_vec()]),
+        ),
+        Value::Hash(hash) => batched(
+            b"HSET",
+            key,
+            hash.iter()
+                .map(|(field, value)| vec![field.clone(), value.clone()]),
+        ),
+        Value::Stream(stream) => rewrite_stream(key, stream),
+    }
+}
+
+fn rewrite_stream(key: &[u8], stream: &Stream) -> Commands {
+    let mut commands = vec![];
+
+    if stream.is_empty() {
+        commands.push(command(&[
+            b"XADD", key, b"MAXLEN", b"0", b"0-1", b"x", b"y",
+        ]));
+    }
+
+    for (id, fields) in stream.range(StreamId::MIN..=StreamId::MAX) {
+        let mut xadd = command(&[b"XADD", key, id.to_string().as_bytes()]);
+
+        for (field, value) in fields {
+            xadd.extend([field.clone(), value.clone()]);
+        }
+
+        commands.push(xadd);
+    }
+
+    commands.push(command(&[
+        b"XSETID",
+        key,
+        stream.last_id().to_string().as_bytes(),
+        b"ENTRIESADDED",
+        stream.entries_added().to_string().as_bytes(),
+        b"MAXDELETEDID",
+        stream.max_deleted_id().to_string().as_bytes(),
+    ]));
+
+    for (name, group) in stream.groups() {
+        let entries_read = group
+            .entries_read
+            .map_or(String::from("-1"), |read| read.to_string());
+
+        commands.push(command(&[
+            b"XGROUP",
+            b"CREATE",
+            key,
+            name,
+            group.last_id.to_string().as_bytes(),
+            b"ENTRIESREAD",
+            entries_read.as_bytes(),
+        ]));
+
+        for consumer in group.consumers.keys() {
+            commands.push(command(&[
+                b"XGROUP",
+                b"CREATECONSUMER",
+                key,
+                name,
+                consumer,
+            ]));
+        }
+
+        for (id, entry) in &group.pending {
+            commands.push(command(&[
+                b"XCLAIM",
+                key,
+                name,
+                &entry.consumer,
+                b"0",
+                id.to_string().as_bytes(),
+                b"TIME",
+                entry.delivery_time.to_string().as_bytes(),
+                b"RETRYCOUNT",
+                entry.delivery_count.to_string().as_bytes(),
+                b"JUSTID",
+                b"FORCE",
+            ]));
+        }
+    }
+
+    commands
+}
+
+fn batched(name: &[u8], key: &[u8], items: impl Iterator<Item = Vec<Vec<u8>>>) -> Commands {
+    let items: Vec<Vec<Vec<u8>>> = items.collect();
+
+    items
+        .chunks(AOF_REWRITE_ITEMS_PER_CMD)
+        .map(|chunk| {
+            let mut batch = command(&[name, key]);
+
+            batch.extend(chunk.iter().flatten().cloned());
+            batch
+        })
+        .collect()
+}
+
+fn command(args: &[&[u8]]) -> Vec<Vec<u8>> {
+    args.iter().map(|arg| arg.to_vec()).collect()
+}
@@ -0,0 +1,295 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    io::Error,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::{oneshot, Notify};
+
+use super::{
+    cmd::{
+        list::{list_move, list_pop, ListEnd},
+        stream::{read_entries, read_group_entries, read_reply},
+    },
+    db::MemoryDatabase,
+    reply::{Protocol, Reply},
+    types::StreamId,
+};
+
+#[derive(Clone, Debug)]
+pub enum BlockedOperation {
+    Pop {
+        end: ListEnd,
+    },
+    MultiPop {
+        end: ListEnd,
+        count: usize,
+    },
+    Move {
+        destination: Vec<u8>,
+        from: ListEnd,
+        to: ListEnd,
+    },
+    StreamRead {
+        cursors: Vec<(Vec<u8>, StreamId)>,
+        count: Option<usize>,
+        protocol: Protocol,
+    },
+    StreamReadGroup {
+        group: Vec<u8>,
+        consumer: Vec<u8>,
+        count: Option<usize>,
+        noack: bool,
+        protocol: Protocol,
+    },
+}
+
+pub struct BlockRequest {
+    pub keys: Vec<Vec<u8>>,
+    pub operation: BlockedOperation,
+    pub timeout: Option<Duration>,
+}
+
+struct Waiter {
+    keys: Vec<Vec<u8>>,
+    operation: BlockedOperation,
+    sender: oneshot::Sender<Reply>,
+}
+
+#[derive(Default)]
+struct Waiters {
+    next_id: u64,
+    by_key: HashMap<Vec<u8>, VecDeque<u64>>,
+    waiters: HashMap<u64, Waiter>,
+}
+
+impl Waiters {
+    fn remove(&mut self, id: u64) -> Option<Waiter> {
+        let waiter = self.waiters.remove(&id)?;
+
+        for key in &waiter.keys {
+            if let Some(queue) = self.by_key.get_mut(key) {
+                queue.retain(|queued| *queued != id);
+
+                if queue.is_empty() {
+                    self.by_key.remove(key);
+                }
+            }
+        }
+
+        Some(waiter)
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct BlockingRegistry {
+    inner: Arc<Mutex<Waiters>>,
+}
+
+impl BlockingRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn blocked_clients(&self) -> usize {
+        self.inner.lock().unwrap().waiters.len()
+    }
+
+    pub fn register(&self, request: &BlockRequest) -> (u64, oneshot::Receiver<Reply>) {
+        let (sender, receiver) = oneshot::channel();
+        let mut inner = self.inner.lock().unwrap();
+
+        inner.next_id += 1;
+        let id = inner.next_id;
+
+        for key in &request.keys {
+            inner.by_key.entry(key.clone()).or_default().push_back(id);
+        }
+
+        inner.waiters.insert(
+            id,
+            Waiter {
+                keys: request.keys.clone(),
+                operation: request.operation.clone(),
+                sender,
+            },
+        );
+
+        (id, receiver)
+    }
+
+    pub fn unregister(&self, id: u64) -> bool {
+        self.inner.lock().unwrap().remove(id).is_some()
+    }
+
+    pub fn serve(
+        &self,
+        ready_keys: Vec<Vec<u8>>,
+        db: &mut dyn MemoryDatabase,
+    ) -> Vec<Vec<Vec<u8>>> {
+        let mut inner = self.inner.lock().unwrap();
+        let mut ready_keys = VecDeque::from(ready_keys);
+        let mut propagated = vec![];
+
+        while let Some(key) = ready_keys.pop_front() {
+            let queued = match inner.by_key.get(&key) {
+                Some(queue) => queue.iter().copied().collect::<Vec<_>>(),
+                None => continue,
+            };
+
+            for id in queued {
+                let Some(waiter) = inner.waiters.get(&id) else {
+                    continue;
+                };
+
+                let reply = match serve_operation(&waiter.operation, &key, db) {
+                    Ok(None) => continue,
+                    Ok(Some(served)) => {
+                        ready_keys.extend(served.ready_keys);
+                        propagated.extend(served.propagate);
+                        served.reply
+                    }
+                    Err(e) => Reply::from_error(e),
+                };
+
+                if let Some(waiter) = inner.remove(id) {
+                    let _ = waiter.sender.send(reply);
+                }
+            }
+        }
+
+        propagated
+    }
+
+    pub async fn wait(
+        &self,
+        id: u64,
+        receiver: oneshot::Receiver<Reply>,
+        timeout: Option<Duration>,
+        disconnected: &Notify,
+    ) -> Reply {
+        let mut guard = WaitGuard {
+            registry: self.clone(),
+            id,
+            receiver,
+        };
+        let received = async {
+            match timeout {
+                Some(timeout) => tokio::time::timeout(timeout, &mut guard.receiver)
+                    .await
+                    .ok(),
+                None => Some((&mut guard.receiver).await),
+            }
+        };
+
+        let reply = tokio::select! {
+            reply = received => reply,
+            _ = disconnected.notified() => None,
+        };
+
+        self.unregister(id);
+
+        match reply {
+            Some(Ok(reply)) => reply,
+            _ => guard.receiver.try_recv().unwrap_or(Reply::NullArray),
+        }
+    }
+}
+
+struct WaitGuard {
+    registry: BlockingRegistry,
+    id: u64,
+    receiver: oneshot::Receiver<Reply>,
+}
+
+impl Drop for WaitGuard {
+    fn drop(&mut self) {
+        self.registry.unregister(self.id);
+    }
+}
+
+struct Served {
+    reply: Reply,
+    ready_keys: Vec<Vec<u8>>,
+    propagate: Vec<Vec<Vec<u8>>>,
+}
+
+fn serve_operation(
+    operation: &BlockedOperation,
+    key: &[u8],
+    db: &mut dyn MemoryDatabase,
+) -> Result<Option<Served>, Error> {
+    match operation {
+        BlockedOperation::Pop { end } => Ok(list_pop(db, key, *end, 1)?
+            .and_then(|mut items| items.pop())
+            .map(|item| Served {
+                reply: Reply::Array(vec![Reply::bulk(key), Reply::Bulk(item)]),
+                ready_keys: vec![],
+                propagate: vec![vec![end.pop_command().to_vec(), key.to_vec()]],
+            })),
+        BlockedOperation::MultiPop { end, count } => Ok(list_pop(db, key, *end, *count)?
+            .filter(|items| !items.is_empty())
+            .map(|items| Served {
+                propagate: vec![vec![
+                    end.pop_command().to_vec(),
+                    key.to_vec(),
+                    items.len().to_string().into_bytes(),
+                ]],
+                reply: Reply::Array(vec![
+                    Reply::bulk(key),
+                    Reply::Array(items.into_iter().map(Reply::Bulk).collect()),
+                ]),
+                ready_keys: vec![],
+            })),
+        BlockedOperation::Move {
+            destination,
+            from,
+            to,
+        } => Ok(
+            list_move(db, key, destination, *from, *to)?.map(|item| Served {
+                reply: Reply::Bulk(item),
+                ready_keys: vec![destination.clone()],
+                propagate: vec![vec![
+                    b"LMOVE".to_vec(),
+                    key.to_vec(),
+                    destination.clone(),
+                    from.name().to_vec(),
+                    to.name().to_vec(),
+                ]],
+            }),
+        ),
+        BlockedOperation::StreamRead {
+            cursors,
+            count,
+            protocol,
+        } => {
+            let Some((_, after)) = cursors.iter().find(|(cursor, _)| cursor == key) else {
+                return Ok(None);
+            };
+
+            Ok(
+                read_entries(db, key, *after, *count)?.map(|entries| Served {
+                    reply: read_reply(vec![(key.to_vec(), entries)], *protocol),
+                    ready_keys: vec![],
+                    propagate: vec![],
+                }),
+            )
+        }
+        BlockedOperation::StreamReadGroup {
+            group,
+            consumer,
+            count,
+            noack,
+            protocol,
+        } => {
+            let (entries, propagate) =
+                read_group_entries(db, key, group, consumer, *count, *noack)?;
+
+            Ok(entries.map(|entries| Served {
+                reply: read_reply(vec![(key.to_vec(), entries)], *protocol),
+                ready_keys: vec![],
+                propagate,
+            }))
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::{
+    collections::BTreeSet,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::{
+    mpsc::{self, UnboundedReceiver, UnboundedSender},
+    Notify,
+};
+
+use super::{
+    pubsub::SubscriptionKind,
+    reply::{Protocol, Reply},
+};
+
+static NEXT_CLIENT_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Default)]
+pub struct Transaction {
+    pub commands: Vec<Vec<Vec<u8>>>,
+    pub aborted: bool,
+}
+
+pub struct Client {
+    pub id: u64,
+    pub name: Option<Vec<u8>>,
+    pub protocol: Protocol,
+    pub channels: BTreeSet<Vec<u8>>,
+    pub patterns: BTreeSet<Vec<u8>>,
+    pub shard_channels: BTreeSet<Vec<u8>>,
+    pub transaction: Option<Transaction>,
+    pub watched: Vec<(Vec<u8>, u64)>,
+    pub disconnected: Arc<Notify>,
+    messages: UnboundedSender<Reply>,
+    receiver: UnboundedReceiver<Reply>,
+}
+
+impl Client {
+    pub fn new() -> Self {
+        let (messages, receiver) = mpsc::unbounded_channel();
+
+        Self {
+            id: NEXT_CLIENT_ID.fetch_add(1, Ordering::Relaxed),
+            name: None,
+            protocol: Protocol::RESP2,
+            channels: BTreeSet::new(),
+            patterns: BTreeSet::new(),
+            shard_channels: BTreeSet::new(),
+            transaction: None,
+            watched: vec![],
+            disconnected: Arc::new(Notify::new()),
+            messages,
+            receiver,
+        }
+    }
+
+    pub fn subscriptions(&self, kind: SubscriptionKind) -> &BTreeSet<Vec<u8>> {
+        match kind {
+            SubscriptionKind::Channel => &self.channels,
+            SubscriptionKind::Pattern => &self.patterns,
+            SubscriptionKind::Shard => &self.shard_channels,
+        }
+    }
+
+    pub fn subscriptions_mut(&mut self, kind: SubscriptionKind) -> &mut BTreeSet<Vec<u8>> {
+        match kind {
+            SubscriptionKind::Channel => &mut self.channels,
+            SubscriptionKind::Pattern => &mut self.patterns,
+            SubscriptionKind::Shard => &mut self.shard_channels,
+        }
+    }
+
+    pub fn subscription_count(&self) -> usize {
+        self.channels.len() + self.patterns.len() + self.shard_channels.len()
+    }
+
+    pub fn namespace_subscriptions(&self, kind: SubscriptionKind) -> usize {
+        match kind {
+            SubscriptionKind::Channel | SubscriptionKind::Pattern => {
+                self.channels.len() + self.patterns.len()
+            }
+            SubscriptionKind::Shard => self.shard_channels.len(),
+        }
+    }
+
+    pub fn abort_transaction(&mut self) {
+        if let Some(transaction) = self.transaction.as_mut() {
+            transaction.aborted = true;
+        }
+    }
+
+    pub fn sender(&self) -> UnboundedSender<Reply> {
+        self.messages.clone()
+    }
+
+    pub fn push(&self, reply: Reply) {
+        let _ = self.messages.send(reply);
+    }
+
+    pub fn try_next_message(&mut self) -> Option<Reply> {
+        self.receiver.try_recv().ok()
+    }
+
+    pub async fn next_message(&mut self) -> Option<Reply> {
+        self.receiver.recv().await
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,69 @@
+use std::{
+    io::{Error, ErrorKind},
+    time::Duration,
+};
+
+pub fn parse_integer(arg: &[u8]) -> Result<i64, Error> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|arg| arg.parse::<i64>().ok())
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                "value is not an integer or out of range",
+            )
+        })
+}
+
+pub fn command_name(args: &[Vec<u8>]) -> String {
+    String::from_utf8_lossy(&args[0]).to_lowercase()
+}
+
+pub fn syntax_error() -> Error {
+    Error::new(ErrorKind::InvalidData, "syntax error")
+}
+
+pub fn parse_timeout(arg: &[u8]) -> Result<Option<Duration>, Error> {
+    let timeout = std::str::from_utf8(arg)
+        .ok()
+        .and_then(|arg| arg.parse::<f64>().ok())
+        .filter(|timeout| timeout.is_finite())
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                "timeout is not a float or out of range",
+            )
+        })?;
+
+    if timeout < 0.0 {
+        return Err(Error::new(ErrorKind::InvalidData, "timeout is negative"));
+    }
+
+    Ok((timeout > 0.0).then(|| Duration::from_secs_f64(timeout)))
+}
+
+pub fn parse_timeout_ms(arg: &[u8]) -> Result<Option<Duration>, Error> {
+    let timeout = std::str::from_utf8(arg)
+        .ok()
+        .and_then(|arg| arg.parse::<i64>().ok())
+        .ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                "timeout is not an integer or out of range",
+            )
+        })?;
+
+    if timeout < 0 {
+        return Err(Error::new(ErrorKind::InvalidData, "timeout is negative"));
+    }
+
+    Ok((timeout > 0).then(|| Duration::from_millis(timeout as u64)))
+}
+
+pub fn parse_float(arg: &[u8]) -> Result<f64, Error> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|arg| arg.parse::<f64>().ok())
+        .filter(|float| !float.is_nan())
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "value is not a valid float"))
+}
@@ -0,0 +1,89 @@
+use crate::redis::{
+    command::{lookup_command, CommandContext, CommandSpec, COMMAND_TABLE},
+    reply::Reply,
+};
+use std::io::{Error, ErrorKind};
+
+pub fn cmd_command(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let Some(subcommand) = ctx.args.get(1) else {
+        return Ok(Reply::Array(
+            COMMAND_TABLE.iter().map(command_info).collect(),
+        ));
+    };
+
+    let names = &ctx.args[2..];
+
+    match subcommand.to_ascii_lowercase().as_slice() {
+        b"count" if names.is_empty() => Ok(Reply::Integer(COMMAND_TABLE.len() as i64)),
+        b"list" if names.is_empty() => Ok(Reply::Array(
+            COMMAND_TABLE
+                .iter()
+                .map(|command| Reply::bulk(command.name))
+                .collect(),
+        )),
+        b"info" if names.is_empty() => Ok(Reply::Array(
+            COMMAND_TABLE.iter().map(command_info).collect(),
+        )),
+        b"info" => Ok(Reply::Array(
+            names
+                .iter()
+                .map(|name| lookup_command(name).map_or(Reply::NullArray, command_info))
+                .collect(),
+        )),
+        b"docs" if names.is_empty() => {
+            Ok(Reply::Map(COMMAND_TABLE.iter().map(command_docs).collect()))
+        }
+        b"docs" => Ok(Reply::Map(
+            names
+                .iter()
+                .filter_map(|name| lookup_command(name))
+                .map(command_docs)
+                .collect(),
+        )),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "unknown subcommand '{}'. Try COMMAND HELP.",
+                String::from_utf8_lossy(subcommand)
+            ),
+        )),
+    }
+}
+
+fn command_info(command: &CommandSpec) -> Reply {
+    Reply::Array(vec![
+        Reply::bulk(command.name),
+        Reply::Integer(command.arity),
+        Reply::Set(
+            command
+                .flags
+                .iter()
+                .map(|flag| Reply::Simple(flag.name().to_string()))
+                .collect(),
+        ),
+        Reply::Integer(command.first_key),
+        Reply::Integer(command.last_key),
+        Reply::Integer(command.step),
+        Reply::Set(
+            command
+                .acl_categories()
+                .into_iter()
+                .map(Reply::Simple)
+                .collect(),
+        ),
+        Reply::Array(vec![]),
+        Reply::Array(vec![]),
+        Reply::Array(vec![]),
+    ])
+}
+
+fn command_docs(command: &CommandSpec) -> (Reply, Reply) {
+    (
+        Reply::bulk(command.name),
+        Reply::Map(vec![
+            (Reply::bulk("summary"), Reply::bulk(command.summary)),
+            (Reply::bulk("since"), Reply::bulk(command.since)),
+            (Reply::bulk("group"), Reply::bulk(command.group)),
+        ]),
+    )
+}
@@ -0,0 +1,166 @@
+use crate::redis::{
+    cmd::args::{command_name, parse_integer},
+    command::CommandContext,
+    db::unix_time_ms,
+    reply::Reply,
+};
+use std::io::{Error, ErrorKind};
+
+#[derive(Clone, Copy)]
+pub enum ExpireUnit {
+    Seconds,
+    Milliseconds,
+}
+
+pub fn cmd_expire(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    expire(ctx, ExpireUnit::Seconds, false)
+}
+
+pub fn cmd_pexpire(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    expire(ctx, ExpireUnit::Milliseconds, false)
+}
+
+pub fn cmd_expireat(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    expire(ctx, ExpireUnit::Seconds, true)
+}
+
+pub fn cmd_pexpireat(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    expire(ctx, ExpireUnit::Milliseconds, true)
+}
+
+pub fn cmd_ttl(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    ttl(ctx, ExpireUnit::Seconds, false)
+}
+
+pub fn cmd_pttl(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    ttl(ctx, ExpireUnit::Milliseconds, false)
+}
+
+pub fn cmd_expiretime(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    ttl(ctx, ExpireUnit::Seconds, true)
+}
+
+pub fn cmd_pexpiretime(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    ttl(ctx, ExpireUnit::Milliseconds, true)
+}
+
+pub fn cmd_persist(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let key = &ctx.args[1];
+
+    if ctx.db.get_expiry(key).is_none() {
+        return Ok(Reply::Integer(0));
+    }
+
+    ctx.db.set_expiry(key, None);
+
+    Ok(Reply::Integer(1))
+}
+
+fn expire(ctx: &mut CommandContext, unit: ExpireUnit, absolute: bool) -> Result<Reply, Error> {
+    let key = &ctx.args[1];
+    let time = parse_integer(&ctx.args[2])?;
+    let (mut nx, mut xx, mut gt, mut lt) = (false, false, false, false);
+
+    for option in &ctx.args[3..] {
+        match option.to_ascii_lowercase().as_slice() {
+            b"nx" => nx = true,
+            b"xx" => xx = true,
+            b"gt" => gt = true,
+            b"lt" => lt = true,
+            option => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!("Unsupported option {}", String::from_utf8_lossy(option)),
+                ))
+            }
+        }
+    }
+
+    if nx && (xx || gt || lt) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "NX and XX, GT or LT options at the same time are not compatible",
+        ));
+    }
+
+    if gt && lt {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "GT and LT options at the same time are not compatible",
+        ));
+    }
+
+    let time = match unit {
+        ExpireUnit::Seconds => time.checked_mul(1000),
+        ExpireUnit::Milliseconds => Some(time),
+    };
+    let expires_at = match absolute {
+        true => time,
+        false => time.and_then(|time| time.checked_add(unix_time_ms() as i64)),
+    }
+    .ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "invalid expire time in '{}' command",
+                command_name(ctx.args)
+            ),
+        )
+    })?;
+
+    ctx.propagate = Some(vec![]);
+
+    if !ctx.db.exists(key) {
+        return Ok(Reply::Integer(0));
+    }
+
+    let current = ctx.db.get_expiry(key);
+    let allowed = match current {
+        _ if nx => current.is_none(),
+        None => !(xx || gt),
+        Some(current) if gt => expires_at > current as i64,
+        Some(current) if lt => expires_at < current as i64,
+        Some(_) => true,
+    };
+
+    if !allowed {
+        return Ok(Reply::Integer(0));
+    }
+
+    if expires_at <= unix_time_ms() as i64 {
+        ctx.db.del(key);
+        ctx.propagate = Some(vec![vec![b"DEL".to_vec(), key.clone()]]);
+    } else {
+        ctx.db.set_expiry(key, Some(expires_at as u64));
+        ctx.propagate = Some(vec![vec![
+            b"PEXPIREAT".to_vec(),
+            key.clone(),
+            expires_at.to_string().into_bytes(),
+        ]]);
+    }
+
+    Ok(Reply::Integer(1))
+}
+
+fn ttl(ctx: &mut CommandContext, unit: ExpireUnit, absolute: bool) -> Result<Reply, Error> {
+    let key = &ctx.args[1];
+
+    if !ctx.db.exists(key) {
+        return Ok(Reply::Integer(-2));
+    }
+
+    let Some(expires_at) = ctx.db.get_expiry(key) else {
+        return Ok(Reply::Integer(-1));
+    };
+
+    let time = match absolute {
+        true => expires_at,
+        false => expires_at.saturating_sub(unix_time_ms()),
+    };
+
+    Ok(Reply::Integer(match unit {
+        ExpireUnit::Seconds if absolute => time / 1000,
+        ExpireUnit::Seconds => (time + 500) / 1000,
+        ExpireUnit::Milliseconds => time,
+    } as i64))
+}
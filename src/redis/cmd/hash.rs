@@ -0,0 +1,293 @@
+use crate::redis::{
+    cmd::args::{command_name, parse_float, parse_integer, syntax_error},
+    command::CommandContext,
+    db::{pick_random, MemoryDatabase},
+    reply::{Protocol, Reply},
+    resp3::format_double,
+};
+use std::io::{Error, ErrorKind};
+
+pub fn cmd_hset(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let added = set_fields(ctx)?;
+
+    Ok(Reply::Integer(added as i64))
+}
+
+pub fn cmd_hmset(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    set_fields(ctx)?;
+
+    Ok(Reply::ok())
+}
+
+pub fn cmd_hsetnx(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let hash = ctx
+        .db
+        .get_hash_mut(&ctx.args[1], true)?
+        .expect("hash was just created");
+
+    if hash.contains_key(&ctx.args[2]) {
+        return Ok(Reply::Integer(0));
+    }
+
+    hash.insert(ctx.args[2].clone(), ctx.args[3].clone());
+    ctx.db.touch(&ctx.args[1]);
+
+    Ok(Reply::Integer(1))
+}
+
+pub fn cmd_hget(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let value = ctx
+        .db
+        .get_hash(&ctx.args[1])?
+        .and_then(|hash| hash.get(&ctx.args[2]));
+
+    match value {
+        Some(value) => Ok(Reply::bulk(value)),
+        None => Ok(Reply::Null),
+    }
+}
+
+pub fn cmd_hmget(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let hash = ctx.db.get_hash(&ctx.args[1])?;
+    let values = ctx.args[2..]
+        .iter()
+        .map(|field| match hash.and_then(|hash| hash.get(field)) {
+            Some(value) => Reply::bulk(value),
+            None => Reply::Null,
+        })
+        .collect();
+
+    Ok(Reply::Array(values))
+}
+
+pub fn cmd_hdel(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let Some(hash) = ctx.db.get_hash_mut(&ctx.args[1], false)? else {
+        return Ok(Reply::Integer(0));
+    };
+
+    let deleted = ctx.args[2..]
+        .iter()
+        .filter(|field| hash.remove(*field).is_some())
+        .count();
+
+    if deleted > 0 {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    remove_if_empty(ctx.db, &ctx.args[1]);
+
+    Ok(Reply::Integer(deleted as i64))
+}
+
+pub fn cmd_hgetall(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let pairs = ctx
+        .db
+        .get_hash(&ctx.args[1])?
+        .into_iter()
+        .flatten()
+        .map(|(field, value)| (Reply::bulk(field), Reply::bulk(value)))
+        .collect();
+
+    Ok(Reply::Map(pairs))
+}
+
+pub fn cmd_hkeys(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let fields = ctx
+        .db
+        .get_hash(&ctx.args[1])?
+        .into_iter()
+        .flat_map(|hash| hash.keys())
+        .map(Reply::bulk)
+        .collect();
+
+    Ok(Reply::Array(fields))
+}
+
+pub fn cmd_hvals(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let values = ctx
+        .db
+        .get_hash(&ctx.args[1])?
+        .into_iter()
+        .flat_map(|hash| hash.values())
+        .map(Reply::bulk)
+        .collect();
+
+    Ok(Reply::Array(values))
+}
+
+pub fn cmd_hlen(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let length = ctx.db.get_hash(&ctx.args[1])?.map_or(0, |hash| hash.len());
+
+    Ok(Reply::Integer(length as i64))
+}
+
+pub fn cmd_hexists(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let exists = ctx
+        .db
+        .get_hash(&ctx.args[1])?
+        .is_some_and(|hash| hash.contains_key(&ctx.args[2]));
+
+    Ok(Reply::Integer(exists as i64))
+}
+
+pub fn cmd_hstrlen(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let length = ctx
+        .db
+        .get_hash(&ctx.args[1])?
+        .and_then(|hash| hash.get(&ctx.args[2]))
+        .map_or(0, |value| value.len());
+
+    Ok(Reply::Integer(length as i64))
+}
+
+pub fn cmd_hincrby(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let increment = parse_integer(&ctx.args[3])?;
+    let current = match ctx
+        .db
+        .get_hash(&ctx.args[1])?
+        .and_then(|hash| hash.get(&ctx.args[2]))
+    {
+        Some(value) => parse_integer(value)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "hash value is not an integer"))?,
+        None => 0,
+    };
+
+    let value = current.checked_add(increment).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            "increment or decrement would overflow",
+        )
+    })?;
+
+    ctx.db
+        .get_hash_mut(&ctx.args[1], true)?
+        .expect("hash was just created")
+        .insert(ctx.args[2].clone(), value.to_string().into_bytes());
+    ctx.db.touch(&ctx.args[1]);
+
+    Ok(Reply::Integer(value))
+}
+
+pub fn cmd_hincrbyfloat(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let increment = parse_float(&ctx.args[3])?;
+    let current = match ctx
+        .db
+        .get_hash(&ctx.args[1])?
+        .and_then(|hash| hash.get(&ctx.args[2]))
+    {
+        Some(value) => parse_float(value)
+            .map_err(|_| Error::new(ErrorKind::InvalidData, "hash value is not a float"))?,
+        None => 0.0,
+    };
+
+    let value = current + increment;
+
+    if !value.is_finite() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "increment would produce NaN or Infinity",
+        ));
+    }
+
+    let value = format_double(value).into_bytes();
+
+    ctx.db
+        .get_hash_mut(&ctx.args[1], true)?
+        .expect("hash was just created")
+        .insert(ctx.args[2].clone(), value.clone());
+    ctx.db.touch(&ctx.args[1]);
+
+    Ok(Reply::Bulk(value))
+}
+
+pub fn cmd_hrandfield(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let count = ctx
+        .args
+        .get(2)
+        .map(|count| parse_integer(count))
+        .transpose()?;
+    let with_values = match ctx.args.get(3) {
+        Some(option) if option.eq_ignore_ascii_case(b"withvalues") => true,
+        Some(_) => return Err(syntax_error()),
+        None => false,
+    };
+
+    if ctx.args.len() > 4 {
+        return Err(syntax_error());
+    }
+
+    let limit = match with_values {
+        true => i64::MAX / 2,
+        false => i64::MAX,
+    };
+
+    if count.is_some_and(|count| count < -limit || count > limit) {
+        return Err(Error::new(ErrorKind::InvalidData, "value is out of range"));
+    }
+
+    let pairs: Vec<(Vec<u8>, Vec<u8>)> = match ctx.db.get_hash(&ctx.args[1])? {
+        Some(hash) => hash.iter().map(|(f, v)| (f.clone(), v.clone())).collect(),
+        None => vec![],
+    };
+
+    let Some(count) = count else {
+        return match pick_random(ctx.db, pairs, 1, false).pop() {
+            Some((field, _)) => Ok(Reply::Bulk(field)),
+            None => Ok(Reply::Null),
+        };
+    };
+
+    let picked = pick_random(ctx.db, pairs, count.unsigned_abs() as usize, count < 0);
+    let protocol = ctx.client.protocol;
+
+    let reply = match (with_values, protocol) {
+        (false, _) => picked
+            .into_iter()
+            .map(|(field, _)| Reply::Bulk(field))
+            .collect(),
+        (true, Protocol::RESP3) => picked
+            .into_iter()
+            .map(|(field, value)| Reply::Array(vec![Reply::Bulk(field), Reply::Bulk(value)]))
+            .collect(),
+        (true, Protocol::RESP2) => picked
+            .into_iter()
+            .flat_map(|(field, value)| [Reply::Bulk(field), Reply::Bulk(value)])
+            .collect(),
+    };
+
+    Ok(Reply::Array(reply))
+}
+
+fn set_fields(ctx: &mut CommandContext) -> Result<usize, Error> {
+    if !ctx.args.len().is_multiple_of(2) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "wrong number of arguments for '{}' command",
+                command_name(ctx.args)
+            ),
+        ));
+    }
+
+    let hash = ctx
+        .db
+        .get_hash_mut(&ctx.args[1], true)?
+        .expect("hash was just created");
+
+    let added = ctx.args[2..]
+        .chunks(2)
+        .filter(|pair| hash.insert(pair[0].clone(), pair[1].clone()).is_none())
+        .count();
+
+    ctx.db.touch(&ctx.args[1]);
+
+    Ok(added)
+}
+
+fn remove_if_empty(db: &mut dyn MemoryDatabase, key: &[u8]) {
+    if let Ok(Some(hash)) = db.get_hash(key) {
+        if hash.is_empty() {
+            db.del(key);
+        }
+    }
+}
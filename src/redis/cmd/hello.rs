@@ -0,0 +1,74 @@
+use crate::redis::{
+    command::CommandContext,
+    reply::{Protocol, Reply},
+};
+use std::io::{Error, ErrorKind};
+
+pub const SERVER_VERSION: &str = "7.2.0";
+
+pub fn cmd_hello(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let mut args = ctx.args[1..].iter().map(Vec::as_slice);
+    let client = &mut *ctx.client;
+
+    let mut protocol = client.protocol;
+    let mut name = None;
+
+    if let Some(version) = args.next() {
+        protocol = match std::str::from_utf8(version).map(str::parse::<i64>) {
+            Ok(Ok(2)) => Protocol::RESP2,
+            Ok(Ok(3)) => Protocol::RESP3,
+            Ok(Ok(_)) => {
+                return Ok(Reply::Error(String::from(
+                    "NOPROTO unsupported protocol version",
+                )))
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "Protocol version is not an integer or out of range",
+                ))
+            }
+        };
+    }
+
+    while let Some(option) = args.next() {
+        match option.to_ascii_lowercase().as_slice() {
+            b"auth" => {
+                if args.next().zip(args.next()).is_none() {
+                    return Err(syntax_error(option));
+                }
+            }
+            b"setname" => match args.next() {
+                Some(value) => name = Some(value.to_vec()),
+                None => return Err(syntax_error(option)),
+            },
+            _ => return Err(syntax_error(option)),
+        }
+    }
+
+    client.protocol = protocol;
+
+    if name.is_some() {
+        client.name = name;
+    }
+
+    Ok(Reply::Map(vec![
+        (Reply::bulk("server"), Reply::bulk("redis")),
+        (Reply::bulk("version"), Reply::bulk(SERVER_VERSION)),
+        (Reply::bulk("proto"), Reply::Integer(protocol.version())),
+        (Reply::bulk("id"), Reply::Integer(client.id as i64)),
+        (Reply::bulk("mode"), Reply::bulk("standalone")),
+        (Reply::bulk("role"), Reply::bulk("master")),
+        (Reply::bulk("modules"), Reply::Array(vec![])),
+    ]))
+}
+
+fn syntax_error(option: &[u8]) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "Syntax error in HELLO option '{}'",
+            String::from_utf8_lossy(option)
+        ),
+    )
+}
@@ -0,0 +1,88 @@
+use crate::redis::{
+    cmd::hello::SERVER_VERSION, command::CommandContext, expire::ACTIVE_EXPIRE_CYCLE_HZ,
+    reply::Reply,
+};
+use std::{fmt::Write, io::Error};
+
+const SECTIONS: [&str; 5] = ["server", "persistence", "replication", "stats", "keyspace"];
+
+pub fn cmd_info(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let requested = ctx.args[1..]
+        .iter()
+        .map(|arg| String::from_utf8_lossy(arg).to_lowercase())
+        .collect::<Vec<_>>();
+
+    let everything = requested.is_empty()
+        || requested
+            .iter()
+            .any(|section| section == "all" || section == "default" || section == "everything");
+
+    let db = &mut ctx.db;
+    let mut info = String::new();
+
+    for section in SECTIONS {
+        if !everything && !requested.iter().any(|requested| requested == section) {
+            continue;
+        }
+
+        if !info.is_empty() {
+            info.push_str("\r\n");
+        }
+
+        match section {
+            "server" => {
+                let _ = write!(
+                    info,
+                    "# Server\r\nredis_version:{}\r\nredis_mode:standalone\r\nprocess_id:{}\r\nhz:{}\r\n",
+                    SERVER_VERSION,
+                    std::process::id(),
+                    ACTIVE_EXPIRE_CYCLE_HZ,
+                );
+            }
+            "persistence" => {
+                let dirty = db.dirty();
+                let state = ctx.persistence.state();
+                let _ = write!(
+                    info,
+                    "# Persistence\r\nloading:0\r\nrdb_changes_since_last_save:{}\r\nrdb_bgsave_in_progress:{}\r\nrdb_last_save_time:{}\r\nrdb_last_bgsave_status:{}\r\naof_enabled:{}\r\naof_rewrite_in_progress:{}\r\naof_last_bgrewrite_status:{}\r\n",
+                    dirty.saturating_sub(state.dirty_at_save),
+                    state.bgsave_in_progress as u8,
+                    state.last_save,
+                    if state.last_bgsave_ok { "ok" } else { "err" },
+                    ctx.persistence.aof().is_some() as u8,
+                    state.aof_rewrite_in_progress as u8,
+                    if state.aof_last_rewrite_ok { "ok" } else { "err" },
+                );
+            }
+            "replication" => {
+                info.push_str("# Replication\r\nrole:master\r\nconnected_slaves:0\r\n");
+            }
+            "stats" => {
+                let stats = db.expire_stats().clone();
+                let _ = write!(
+                    info,
+                    "# Stats\r\nexpired_keys:{}\r\nexpired_stale_perc:{:.2}\r\nexpired_time_cap_reached_count:{}\r\nexpire_cycle_cpu_milliseconds:{}\r\nactive_expire_cycles:{}\r\n",
+                    stats.expired_keys,
+                    stats.expired_stale_perc * 100.0,
+                    stats.expired_time_cap_reached_count,
+                    stats.expire_cycle_cpu_milliseconds,
+                    stats.active_expire_cycles,
+                );
+            }
+            _ => {
+                info.push_str("# Keyspace\r\n");
+
+                if !db.is_empty() {
+                    let _ = write!(
+                        info,
+                        "db0:keys={},expires={},avg_ttl=0\r\n",
+                        db.len(),
+                        db.expires_len()
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(Reply::Verbatim(String::from("txt"), info.into_bytes()))
+}
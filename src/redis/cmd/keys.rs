@@ -0,0 +1,42 @@
+use crate::redis::{cmd::args::syntax_error, command::CommandContext, reply::Reply};
+use std::io::Error;
+
+pub fn cmd_del(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let deleted = ctx.args[1..]
+        .iter()
+        .filter(|key| ctx.db.del(key).is_some())
+        .count();
+
+    Ok(Reply::Integer(deleted as i64))
+}
+
+pub fn cmd_exists(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let existing = ctx.args[1..]
+        .iter()
+        .filter(|key| ctx.db.exists(key))
+        .count();
+
+    Ok(Reply::Integer(existing as i64))
+}
+
+pub fn cmd_type(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let type_name = ctx
+        .db
+        .get(&ctx.args[1])
+        .map(|value| value.type_name())
+        .unwrap_or("none");
+
+    Ok(Reply::Simple(type_name.to_string()))
+}
+
+pub fn cmd_flushdb(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    match ctx.args.get(1).map(|mode| mode.to_ascii_uppercase()) {
+        None => {}
+        Some(mode) if ctx.args.len() == 2 && (mode == b"SYNC" || mode == b"ASYNC") => {}
+        Some(_) => return Err(syntax_error()),
+    }
+
+    ctx.db.flush();
+
+    Ok(Reply::Simple("OK".to_string()))
+}
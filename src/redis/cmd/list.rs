@@ -0,0 +1,631 @@
+use crate::redis::{
+    blocking::{BlockRequest, BlockedOperation},
+    cmd::args::{parse_integer, parse_timeout, syntax_error},
+    command::CommandContext,
+    db::MemoryDatabase,
+    reply::Reply,
+};
+use std::{
+    io::{Error, ErrorKind},
+    time::Duration,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ListEnd {
+    Left,
+    Right,
+}
+
+impl ListEnd {
+    pub fn parse(arg: &[u8]) -> Result<Self, Error> {
+        match arg.to_ascii_lowercase().as_slice() {
+            b"left" => Ok(ListEnd::Left),
+            b"right" => Ok(ListEnd::Right),
+            _ => Err(syntax_error()),
+        }
+    }
+
+    pub fn name(&self) -> &'static [u8] {
+        match self {
+            ListEnd::Left => b"LEFT",
+            ListEnd::Right => b"RIGHT",
+        }
+    }
+
+    pub fn pop_command(&self) -> &'static [u8] {
+        match self {
+            ListEnd::Left => b"LPOP",
+            ListEnd::Right => b"RPOP",
+        }
+    }
+}
+
+pub fn cmd_lpush(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    push(ctx, ListEnd::Left, true)
+}
+
+pub fn cmd_rpush(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    push(ctx, ListEnd::Right, true)
+}
+
+pub fn cmd_lpushx(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    push(ctx, ListEnd::Left, false)
+}
+
+pub fn cmd_rpushx(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    push(ctx, ListEnd::Right, false)
+}
+
+pub fn cmd_lpop(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    pop(ctx, ListEnd::Left)
+}
+
+pub fn cmd_rpop(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    pop(ctx, ListEnd::Right)
+}
+
+pub fn cmd_llen(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let length = ctx.db.get_list(&ctx.args[1])?.map_or(0, |list| list.len());
+
+    Ok(Reply::Integer(length as i64))
+}
+
+pub fn cmd_lrange(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let start = parse_integer(&ctx.args[2])?;
+    let stop = parse_integer(&ctx.args[3])?;
+
+    let Some(list) = ctx.db.get_list(&ctx.args[1])? else {
+        return Ok(Reply::Array(vec![]));
+    };
+
+    let items = match normalize_range(start, stop, list.len()) {
+        Some((start, stop)) => list.range(start..=stop).map(Reply::bulk).collect(),
+        None => vec![],
+    };
+
+    Ok(Reply::Array(items))
+}
+
+pub fn cmd_lindex(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let index = parse_integer(&ctx.args[2])?;
+
+    let Some(list) = ctx.db.get_list(&ctx.args[1])? else {
+        return Ok(Reply::Null);
+    };
+
+    match normalize_index(index, list.len()).and_then(|index| list.get(index)) {
+        Some(item) => Ok(Reply::bulk(item)),
+        None => Ok(Reply::Null),
+    }
+}
+
+pub fn cmd_lset(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let index = parse_integer(&ctx.args[2])?;
+
+    let Some(list) = ctx.db.get_list(&ctx.args[1])? else {
+        return Err(Error::new(ErrorKind::InvalidData, "no such key"));
+    };
+
+    let Some(index) = normalize_index(index, list.len()) else {
+        return Err(Error::new(ErrorKind::InvalidData, "index out of range"));
+    };
+
+    ctx.db
+        .get_list_mut(&ctx.args[1], false)?
+        .expect("list was checked")[index] = ctx.args[3].clone();
+    ctx.db.touch(&ctx.args[1]);
+
+    Ok(Reply::ok())
+}
+
+pub fn cmd_lrem(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let count = parse_integer(&ctx.args[2])?;
+    let element = &ctx.args[3];
+
+    let Some(list) = ctx.db.get_list_mut(&ctx.args[1], false)? else {
+        return Ok(Reply::Integer(0));
+    };
+
+    let limit = match count {
+        0 => usize::MAX,
+        count => count.unsigned_abs() as usize,
+    };
+    let mut removed = 0;
+
+    if count >= 0 {
+        let mut index = 0;
+
+        while index < list.len() && removed < limit {
+            if list[index] == *element {
+                list.remove(index);
+                removed += 1;
+            } else {
+                index += 1;
+            }
+        }
+    } else {
+        let mut index = list.len();
+
+        while index > 0 && removed < limit {
+            index -= 1;
+
+            if list[index] == *element {
+                list.remove(index);
+                removed += 1;
+            }
+        }
+    }
+
+    if removed > 0 {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    remove_if_empty(ctx.db, &ctx.args[1]);
+
+    Ok(Reply::Integer(removed as i64))
+}
+
+pub fn cmd_ltrim(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let start = parse_integer(&ctx.args[2])?;
+    let stop = parse_integer(&ctx.args[3])?;
+
+    let Some(list) = ctx.db.get_list_mut(&ctx.args[1], false)? else {
+        return Ok(Reply::ok());
+    };
+
+    let length = list.len();
+
+    match normalize_range(start, stop, length) {
+        Some((start, stop)) => {
+            list.truncate(stop + 1);
+            list.drain(..start);
+        }
+        None => list.clear(),
+    }
+
+    if list.len() != length {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    remove_if_empty(ctx.db, &ctx.args[1]);
+
+    Ok(Reply::ok())
+}
+
+pub fn cmd_linsert(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let after = match ctx.args[2].to_ascii_lowercase().as_slice() {
+        b"before" => false,
+        b"after" => true,
+        _ => return Err(syntax_error()),
+    };
+    let pivot = &ctx.args[3];
+
+    let Some(list) = ctx.db.get_list_mut(&ctx.args[1], false)? else {
+        return Ok(Reply::Integer(0));
+    };
+
+    match list.iter().position(|item| item == pivot) {
+        Some(index) => {
+            list.insert(index + after as usize, ctx.args[4].clone());
+
+            let length = list.len();
+
+            ctx.db.touch(&ctx.args[1]);
+            Ok(Reply::Integer(length as i64))
+        }
+        None => Ok(Reply::Integer(-1)),
+    }
+}
+
+pub fn cmd_lpos(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let element = &ctx.args[2];
+    let mut options = ctx.args[3..].iter();
+    let (mut rank, mut count, mut max_len) = (1, None, 0);
+
+    while let Some(option) = options.next() {
+        let value = parse_integer(options.next().ok_or_else(syntax_error)?)?;
+
+        match option.to_ascii_lowercase().as_slice() {
+            b"rank" if value == 0 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "RANK can't be zero: use 1 to start from the first match, 2 from the second ... or use negative to start from the end of the list",
+                ))
+            }
+            b"rank" if value == i64::MIN => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "value is out of range",
+                ))
+            }
+            b"rank" => rank = value,
+            b"count" if value < 0 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "COUNT can't be negative",
+                ))
+            }
+            b"count" => count = Some(value as usize),
+            b"maxlen" if value < 0 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "MAXLEN can't be negative",
+                ))
+            }
+            b"maxlen" => max_len = value as usize,
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    let list = ctx.db.get_list(&ctx.args[1])?;
+    let limit = match count {
+        Some(0) => usize::MAX,
+        Some(count) => count,
+        None => 1,
+    };
+    let max_len = match max_len {
+        0 => usize::MAX,
+        max_len => max_len,
+    };
+
+    let mut matches = vec![];
+
+    if let Some(list) = list {
+        let indexes: Box<dyn Iterator<Item = usize>> = match rank > 0 {
+            true => Box::new(0..list.len()),
+            false => Box::new((0..list.len()).rev()),
+        };
+        let mut skip = rank.unsigned_abs() - 1;
+
+        for index in indexes.take(max_len) {
+            if list[index] != *element {
+                continue;
+            }
+
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+
+            matches.push(Reply::Integer(index as i64));
+
+            if matches.len() >= limit {
+                break;
+            }
+        }
+    }
+
+    match count {
+        Some(_) => Ok(Reply::Array(matches)),
+        None => Ok(matches.pop().unwrap_or(Reply::Null)),
+    }
+}
+
+pub fn cmd_lmove(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let from = ListEnd::parse(&ctx.args[3])?;
+    let to = ListEnd::parse(&ctx.args[4])?;
+
+    match list_move(ctx.db, &ctx.args[1], &ctx.args[2], from, to)? {
+        Some(item) => {
+            ctx.ready_keys.push(ctx.args[2].clone());
+            Ok(Reply::Bulk(item))
+        }
+        None => Ok(Reply::Null),
+    }
+}
+
+pub fn cmd_rpoplpush(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    match list_move(
+        ctx.db,
+        &ctx.args[1],
+        &ctx.args[2],
+        ListEnd::Right,
+        ListEnd::Left,
+    )? {
+        Some(item) => {
+            ctx.ready_keys.push(ctx.args[2].clone());
+            Ok(Reply::Bulk(item))
+        }
+        None => Ok(Reply::Null),
+    }
+}
+
+pub fn cmd_lmpop(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let (keys, end, count) = parse_mpop(&ctx.args[1..])?;
+
+    match mpop(ctx.db, keys, end, count)? {
+        Some(reply) => Ok(reply),
+        None => Ok(Reply::NullArray),
+    }
+}
+
+pub fn cmd_blpop(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    blocking_pop(ctx, ListEnd::Left)
+}
+
+pub fn cmd_brpop(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    blocking_pop(ctx, ListEnd::Right)
+}
+
+pub fn cmd_blmpop(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let timeout = parse_timeout(&ctx.args[1])?;
+    let (keys, end, count) = parse_mpop(&ctx.args[2..])?;
+
+    if let Some(reply) = mpop(ctx.db, keys, end, count)? {
+        return Ok(reply);
+    }
+
+    let keys = keys.to_vec();
+
+    block(
+        ctx,
+        keys,
+        BlockedOperation::MultiPop { end, count },
+        timeout,
+    )
+}
+
+pub fn cmd_blmove(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let from = ListEnd::parse(&ctx.args[3])?;
+    let to = ListEnd::parse(&ctx.args[4])?;
+    let timeout = parse_timeout(&ctx.args[5])?;
+
+    blocking_move(ctx, from, to, timeout)
+}
+
+pub fn cmd_brpoplpush(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let timeout = parse_timeout(&ctx.args[3])?;
+
+    blocking_move(ctx, ListEnd::Right, ListEnd::Left, timeout)
+}
+
+pub fn list_pop(
+    db: &mut dyn MemoryDatabase,
+    key: &[u8],
+    end: ListEnd,
+    count: usize,
+) -> Result<Option<Vec<Vec<u8>>>, Error> {
+    let Some(list) = db.get_list_mut(key, false)? else {
+        return Ok(None);
+    };
+
+    let count = count.min(list.len());
+    let items = match end {
+        ListEnd::Left => list.drain(..count).collect(),
+        ListEnd::Right => (0..count).filter_map(|_| list.pop_back()).collect(),
+    };
+
+    if count > 0 {
+        db.touch(key);
+    }
+
+    remove_if_empty(db, key);
+
+    Ok(Some(items))
+}
+
+pub fn list_move(
+    db: &mut dyn MemoryDatabase,
+    source: &[u8],
+    destination: &[u8],
+    from: ListEnd,
+    to: ListEnd,
+) -> Result<Option<Vec<u8>>, Error> {
+    if db.get_list(source)?.is_none() {
+        return Ok(None);
+    }
+
+    db.get_list(destination)?;
+
+    let Some(mut items) = list_pop(db, source, from, 1)? else {
+        return Ok(None);
+    };
+    let item = items.remove(0);
+
+    let list = db
+        .get_list_mut(destination, true)?
+        .expect("destination list was just created");
+
+    match to {
+        ListEnd::Left => list.push_front(item.clone()),
+        ListEnd::Right => list.push_back(item.clone()),
+    }
+
+    db.touch(destination);
+
+    Ok(Some(item))
+}
+
+fn push(ctx: &mut CommandContext, end: ListEnd, create: bool) -> Result<Reply, Error> {
+    let Some(list) = ctx.db.get_list_mut(&ctx.args[1], create)? else {
+        return Ok(Reply::Integer(0));
+    };
+
+    for item in &ctx.args[2..] {
+        match end {
+            ListEnd::Left => list.push_front(item.clone()),
+            ListEnd::Right => list.push_back(item.clone()),
+        }
+    }
+
+    let length = list.len();
+
+    ctx.db.touch(&ctx.args[1]);
+    ctx.ready_keys.push(ctx.args[1].clone());
+
+    Ok(Reply::Integer(length as i64))
+}
+
+fn pop(ctx: &mut CommandContext, end: ListEnd) -> Result<Reply, Error> {
+    let count = match ctx.args.get(2) {
+        Some(count) => match parse_integer(count)? {
+            count if count < 0 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "value is out of range, must be positive",
+                ))
+            }
+            count => Some(count as usize),
+        },
+        None => None,
+    };
+
+    if ctx.args.len() > 3 {
+        return Err(syntax_error());
+    }
+
+    let items = list_pop(ctx.db, &ctx.args[1], end, count.unwrap_or(1))?;
+
+    match (items, count) {
+        (Some(items), Some(_)) => Ok(Reply::Array(items.into_iter().map(Reply::Bulk).collect())),
+        (Some(mut items), None) if !items.is_empty() => Ok(Reply::Bulk(items.remove(0))),
+        (_, Some(_)) => Ok(Reply::NullArray),
+        (_, None) => Ok(Reply::Null),
+    }
+}
+
+fn blocking_pop(ctx: &mut CommandContext, end: ListEnd) -> Result<Reply, Error> {
+    let (timeout, keys) = ctx.args[1..].split_last().expect("arity is checked");
+    let timeout = parse_timeout(timeout)?;
+
+    for key in keys {
+        if let Some(item) = list_pop(ctx.db, key, end, 1)?.and_then(|mut items| items.pop()) {
+            return Ok(Reply::Array(vec![Reply::bulk(key), Reply::Bulk(item)]));
+        }
+    }
+
+    let keys = keys.to_vec();
+
+    block(ctx, keys, BlockedOperation::Pop { end }, timeout)
+}
+
+fn blocking_move(
+    ctx: &mut CommandContext,
+    from: ListEnd,
+    to: ListEnd,
+    timeout: Option<Duration>,
+) -> Result<Reply, Error> {
+    let (source, destination) = (ctx.args[1].clone(), ctx.args[2].clone());
+
+    if let Some(item) = list_move(ctx.db, &source, &destination, from, to)? {
+        ctx.ready_keys.push(destination);
+        return Ok(Reply::Bulk(item));
+    }
+
+    let operation = BlockedOperation::Move {
+        destination,
+        from,
+        to,
+    };
+
+    block(ctx, vec![source], operation, timeout)
+}
+
+fn block(
+    ctx: &mut CommandContext,
+    keys: Vec<Vec<u8>>,
+    operation: BlockedOperation,
+    timeout: Option<Duration>,
+) -> Result<Reply, Error> {
+    ctx.block = Some(BlockRequest {
+        keys,
+        operation,
+        timeout,
+    });
+
+    Ok(Reply::NullArray)
+}
+
+fn parse_mpop(args: &[Vec<u8>]) -> Result<(&[Vec<u8>], ListEnd, usize), Error> {
+    let numkeys = parse_integer(&args[0])?;
+
+    if numkeys <= 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "numkeys should be greater than 0",
+        ));
+    }
+
+    if numkeys as usize >= args.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Number of keys can't be greater than number of args",
+        ));
+    }
+
+    let keys = &args[1..=numkeys as usize];
+    let mut rest = args[numkeys as usize + 1..].iter();
+    let end = ListEnd::parse(rest.next().ok_or_else(syntax_error)?)?;
+    let mut count = None;
+
+    while let Some(option) = rest.next() {
+        match option.to_ascii_lowercase().as_slice() {
+            b"count" if count.is_none() => {
+                let value = parse_integer(rest.next().ok_or_else(syntax_error)?)?;
+
+                if value <= 0 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "count should be greater than 0",
+                    ));
+                }
+
+                count = Some(value as usize);
+            }
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    Ok((keys, end, count.unwrap_or(1)))
+}
+
+fn mpop(
+    db: &mut dyn MemoryDatabase,
+    keys: &[Vec<u8>],
+    end: ListEnd,
+    count: usize,
+) -> Result<Option<Reply>, Error> {
+    for key in keys {
+        if let Some(items) = list_pop(db, key, end, count)?.filter(|items| !items.is_empty()) {
+            return Ok(Some(Reply::Array(vec![
+                Reply::bulk(key),
+                Reply::Array(items.into_iter().map(Reply::Bulk).collect()),
+            ])));
+        }
+    }
+
+    Ok(None)
+}
+
+fn remove_if_empty(db: &mut dyn MemoryDatabase, key: &[u8]) {
+    if let Ok(Some(list)) = db.get_list(key) {
+        if list.is_empty() {
+            db.del(key);
+        }
+    }
+}
+
+fn normalize_index(index: i64, length: usize) -> Option<usize> {
+    let index = match index < 0 {
+        true => length as i64 + index,
+        false => index,
+    };
+
+    (0..length as i64)
+        .contains(&index)
+        .then_some(index as usize)
+}
+
+pub fn normalize_range(start: i64, stop: i64, length: usize) -> Option<(usize, usize)> {
+    let length = length as i64;
+    let start = match start < 0 {
+        true => (length + start).max(0),
+        false => start,
+    };
+    let stop = match stop < 0 {
+        true => length + stop,
+        false => stop.min(length - 1),
+    };
+
+    (start <= stop && start < length).then_some((start as usize, stop as usize))
+}
@@ -0,0 +1,28 @@
+use crate::redis::{
+    command::CommandContext,
+    reply::{Protocol, Reply},
+};
+use std::io::{Error, ErrorKind};
+
+pub fn cmd_ping(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    if ctx.client.protocol == Protocol::RESP2 && ctx.client.subscription_count() > 0 {
+        let message = ctx.args.get(1).map_or(Reply::bulk(""), Reply::bulk);
+
+        return match ctx.args.len() {
+            1 | 2 => Ok(Reply::Array(vec![Reply::bulk("pong"), message])),
+            _ => Err(Error::new(
+                ErrorKind::InvalidData,
+                "wrong number of arguments for 'ping' command",
+            )),
+        };
+    }
+
+    match ctx.args.len() {
+        1 => Ok(Reply::Simple(String::from("PONG"))),
+        2 => Ok(Reply::bulk(&ctx.args[1])),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            "wrong number of arguments for 'ping' command",
+        )),
+    }
+}
@@ -0,0 +1,157 @@
+use crate::redis::{
+    cmd::args::command_name, command::CommandContext, pubsub::SubscriptionKind, reply::Reply,
+};
+use std::io::{Error, ErrorKind};
+
+pub fn cmd_subscribe(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    subscribe(ctx, SubscriptionKind::Channel)
+}
+
+pub fn cmd_unsubscribe(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    unsubscribe(ctx, SubscriptionKind::Channel)
+}
+
+pub fn cmd_psubscribe(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    subscribe(ctx, SubscriptionKind::Pattern)
+}
+
+pub fn cmd_punsubscribe(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    unsubscribe(ctx, SubscriptionKind::Pattern)
+}
+
+pub fn cmd_ssubscribe(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    subscribe(ctx, SubscriptionKind::Shard)
+}
+
+pub fn cmd_sunsubscribe(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    unsubscribe(ctx, SubscriptionKind::Shard)
+}
+
+pub fn cmd_spublish(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let receivers = ctx.pubsub.lock().publish_shard(&ctx.args[1], &ctx.args[2]);
+
+    Ok(Reply::Integer(receivers as i64))
+}
+
+pub fn cmd_publish(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let receivers = ctx.pubsub.lock().publish(&ctx.args[1], &ctx.args[2]);
+
+    Ok(Reply::Integer(receivers as i64))
+}
+
+pub fn cmd_pubsub(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let subcommand = ctx.args[1].to_ascii_lowercase();
+    let subscriptions = ctx.pubsub.lock();
+    let kind = match subcommand.as_slice() {
+        b"shardchannels" | b"shardnumsub" => SubscriptionKind::Shard,
+        _ => SubscriptionKind::Channel,
+    };
+
+    match (subcommand.as_slice(), &ctx.args[2..]) {
+        (b"channels" | b"shardchannels", []) => {
+            Ok(channels_reply(subscriptions.channels(kind, None)))
+        }
+        (b"channels" | b"shardchannels", [pattern]) => {
+            Ok(channels_reply(subscriptions.channels(kind, Some(pattern))))
+        }
+        (b"numsub" | b"shardnumsub", channels) => Ok(Reply::Array(
+            channels
+                .iter()
+                .flat_map(|channel| {
+                    [
+                        Reply::bulk(channel),
+                        Reply::Integer(subscriptions.subscribers(kind, channel) as i64),
+                    ]
+                })
+                .collect(),
+        )),
+        (b"numpat", []) => Ok(Reply::Integer(subscriptions.patterns() as i64)),
+        (b"channels" | b"shardchannels" | b"numpat", _) => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "wrong number of arguments for '{}|{}' command",
+                command_name(ctx.args),
+                String::from_utf8_lossy(&subcommand)
+            ),
+        )),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "unknown subcommand '{}'. Try PUBSUB HELP.",
+                String::from_utf8_lossy(&ctx.args[1])
+            ),
+        )),
+    }
+}
+
+fn subscribe(ctx: &mut CommandContext, kind: SubscriptionKind) -> Result<Reply, Error> {
+    let mut subscriptions = ctx.pubsub.lock();
+    let mut confirmations = vec![];
+
+    for name in &ctx.args[1..] {
+        if ctx.client.subscriptions_mut(kind).insert(name.clone()) {
+            subscriptions.subscribe(kind, name, ctx.client);
+        }
+
+        confirmations.push(confirmation(
+            kind.subscribe_verb(),
+            Reply::bulk(name),
+            ctx.client.namespace_subscriptions(kind),
+        ));
+    }
+
+    Ok(confirm(ctx, confirmations))
+}
+
+fn unsubscribe(ctx: &mut CommandContext, kind: SubscriptionKind) -> Result<Reply, Error> {
+    let mut subscriptions = ctx.pubsub.lock();
+    let names = match ctx.args.len() {
+        1 => ctx.client.subscriptions(kind).iter().cloned().collect(),
+        _ => ctx.args[1..].to_vec(),
+    };
+
+    if names.is_empty() {
+        return Ok(confirmation(
+            kind.unsubscribe_verb(),
+            Reply::Null,
+            ctx.client.namespace_subscriptions(kind),
+        ));
+    }
+
+    let mut confirmations = vec![];
+
+    for name in names {
+        if ctx.client.subscriptions_mut(kind).remove(&name) {
+            subscriptions.unsubscribe(kind, &name, ctx.client);
+        }
+
+        confirmations.push(confirmation(
+            kind.unsubscribe_verb(),
+            Reply::Bulk(name),
+            ctx.client.namespace_subscriptions(kind),
+        ));
+    }
+
+    Ok(confirm(ctx, confirmations))
+}
+
+fn confirmation(verb: &str, name: Reply, count: usize) -> Reply {
+    Reply::Push(vec![Reply::bulk(verb), name, Reply::Integer(count as i64)])
+}
+
+fn confirm(ctx: &mut CommandContext, confirmations: Vec<Reply>) -> Reply {
+    let mut confirmations = confirmations.into_iter();
+    let first = confirmations.next().expect("arity is checked");
+
+    for confirmation in confirmations {
+        ctx.client.push(confirmation);
+    }
+
+    first
+}
+
+fn channels_reply(mut channels: Vec<Vec<u8>>) -> Reply {
+    channels.sort();
+
+    Reply::Array(channels.into_iter().map(Reply::Bulk).collect())
+}
@@ -0,0 +1,49 @@
+use crate::redis::{cmd::args::syntax_error, command::CommandContext, reply::Reply};
+use std::io::{Error, ErrorKind};
+
+pub fn cmd_save(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    if ctx.persistence.state().bgsave_in_progress {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Background save already in progress",
+        ));
+    }
+
+    ctx.persistence.save(ctx.db)?;
+
+    Ok(Reply::Simple("OK".to_string()))
+}
+
+pub fn cmd_bgsave(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    match ctx.args.get(1).map(|arg| arg.to_ascii_uppercase()) {
+        Some(arg) if ctx.args.len() == 2 && arg == b"SCHEDULE" => {}
+        Some(_) => return Err(syntax_error()),
+        None => {}
+    }
+
+    if ctx.persistence.background_save(ctx.db).is_none() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Background save already in progress",
+        ));
+    }
+
+    Ok(Reply::Simple("Background saving started".to_string()))
+}
+
+pub fn cmd_lastsave(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    Ok(Reply::Integer(ctx.persistence.state().last_save as i64))
+}
+
+pub fn cmd_bgrewriteaof(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    if ctx.persistence.background_rewrite_aof(ctx.db).is_none() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Background append only file rewriting already in progress",
+        ));
+    }
+
+    Ok(Reply::Simple(
+        "Background append only file rewriting started".to_string(),
+    ))
+}
@@ -1,38 +1,105 @@
 use crate::redis::{
-    db::MemoryDatabase,
-    respv2::{RESPv2Type, Serialize},
+    cmd::args::{parse_integer, syntax_error},
+    command::CommandContext,
+    db::unix_time_ms,
+    reply::Reply,
+    types::Value,
 };
-use std::{
-    io::{Error, ErrorKind},
-    sync::Arc,
-};
-use tokio::sync::Mutex;
-
-pub async fn cmd_set(
-    key: Option<&Box<RESPv2Type>>,
-    value: Option<&Box<RESPv2Type>>,
-    db: &Arc<Mutex<impl MemoryDatabase>>,
-) -> Result<String, Error> {
-    if let (Some(key), Some(value)) = (key, value) {
-        if let (RESPv2Type::String(key), RESPv2Type::String(value)) =
-            (key.as_ref(), value.as_ref())
-        {
-            let mut db = db.lock().await;
-            let db = db.set(key, value);
-
-            if db.is_err() {
-                return Err(Error::new(
-                    ErrorKind::InvalidData,
-                    db.unwrap_err().to_string(),
-                ));
+use std::io::{Error, ErrorKind};
+
+#[derive(PartialEq)]
+enum Condition {
+    Always,
+    IfNotExists,
+    IfExists,
+}
+
+pub fn cmd_set(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let key = &ctx.args[1];
+    let value = &ctx.args[2];
+    let mut options = ctx.args[3..].iter();
+    let mut condition = Condition::Always;
+    let mut expires_at = None;
+    let mut keep_ttl = false;
+    let mut get = false;
+
+    while let Some(option) = options.next() {
+        let option = option.to_ascii_lowercase();
+
+        match option.as_slice() {
+            b"nx" if condition != Condition::IfExists => condition = Condition::IfNotExists,
+            b"xx" if condition != Condition::IfNotExists => condition = Condition::IfExists,
+            b"get" => get = true,
+            b"keepttl" if expires_at.is_none() => keep_ttl = true,
+            b"ex" | b"px" | b"exat" | b"pxat" if expires_at.is_none() && !keep_ttl => {
+                let time = parse_integer(options.next().ok_or_else(syntax_error)?)?;
+
+                expires_at = Some(expiry_from_option(&option, time)?);
             }
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    let db = &mut ctx.db;
+    let old_value = match get {
+        true => db.get_string(key)?.cloned(),
+        false => None,
+    };
+    let exists = db.exists(key);
+
+    let allowed = match condition {
+        Condition::Always => true,
+        Condition::IfNotExists => !exists,
+        Condition::IfExists => exists,
+    };
 
-            return Ok("OK".serialize_to_respv2());
+    if allowed {
+        if keep_ttl {
+            expires_at = db.get_expiry(key);
+        }
+
+        db.set(key, Value::String(value.to_vec()), expires_at)?;
+
+        if let (Some(expires_at), false) = (expires_at, keep_ttl) {
+            ctx.propagate = Some(vec![vec![
+                b"SET".to_vec(),
+                key.clone(),
+                value.clone(),
+                b"PXAT".to_vec(),
+                expires_at.to_string().into_bytes(),
+            ]]);
         }
     }
 
-    return Err(Error::new(
-        ErrorKind::InvalidData,
-        "SET command needs two arguments: SET [key] [value]",
-    ));
+    match (get, old_value) {
+        (true, Some(old_value)) => Ok(Reply::Bulk(old_value)),
+        (true, None) => Ok(Reply::Null),
+        (false, _) if allowed => Ok(Reply::ok()),
+        (false, _) => Ok(Reply::Null),
+    }
+}
+
+fn expiry_from_option(option: &[u8], time: i64) -> Result<u64, Error> {
+    let invalid = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            "invalid expire time in 'set' command",
+        )
+    };
+
+    if time <= 0 {
+        return Err(invalid());
+    }
+
+    let time = time as u64;
+    let now = unix_time_ms();
+
+    match option {
+        b"ex" => time.checked_mul(1000).and_then(|ms| ms.checked_add(now)),
+        b"px" => time.checked_add(now),
+        b"exat" => time.checked_mul(1000),
+        _ => Some(time),
+    }
+    .filter(|expires_at| *expires_at <= i64::MAX as u64)
+    .ok_or_else(invalid)
 }
@@ -0,0 +1,418 @@
+use crate::redis::{
+    cmd::args::{parse_integer, syntax_error},
+    command::CommandContext,
+    db::MemoryDatabase,
+    reply::Reply,
+    types::{Set, Value},
+};
+use std::{
+    collections::HashSet,
+    io::{Error, ErrorKind},
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum SetOperation {
+    Inter,
+    Union,
+    Diff,
+}
+
+pub fn cmd_sadd(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let set = ctx
+        .db
+        .get_set_mut(&ctx.args[1], true)?
+        .expect("set was just created");
+
+    let added = ctx.args[2..]
+        .iter()
+        .filter(|member| set.insert(member))
+        .count();
+
+    if added > 0 {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    Ok(Reply::Integer(added as i64))
+}
+
+pub fn cmd_srem(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let Some(set) = ctx.db.get_set_mut(&ctx.args[1], false)? else {
+        return Ok(Reply::Integer(0));
+    };
+
+    let removed = ctx.args[2..]
+        .iter()
+        .filter(|member| set.remove(member))
+        .count();
+
+    if removed > 0 {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    remove_if_empty(ctx.db, &ctx.args[1]);
+
+    Ok(Reply::Integer(removed as i64))
+}
+
+pub fn cmd_smove(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let (source, destination, member) = (&ctx.args[1], &ctx.args[2], &ctx.args[3]);
+
+    ctx.db.get_set(destination)?;
+
+    let Some(set) = ctx.db.get_set_mut(source, false)? else {
+        return Ok(Reply::Integer(0));
+    };
+
+    if source == destination {
+        return Ok(Reply::Integer(set.contains(member) as i64));
+    }
+
+    if !set.remove(member) {
+        return Ok(Reply::Integer(0));
+    }
+
+    ctx.db.touch(source);
+    remove_if_empty(ctx.db, source);
+
+    ctx.db
+        .get_set_mut(destination, true)?
+        .expect("set was just created")
+        .insert(member);
+    ctx.db.touch(destination);
+
+    Ok(Reply::Integer(1))
+}
+
+pub fn cmd_smembers(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let members = ctx
+        .db
+        .get_set(&ctx.args[1])?
+        .map(|set| set.members())
+        .unwrap_or_default();
+
+    Ok(set_reply(members))
+}
+
+pub fn cmd_sismember(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let exists = ctx
+        .db
+        .get_set(&ctx.args[1])?
+        .is_some_and(|set| set.contains(&ctx.args[2]));
+
+    Ok(Reply::Integer(exists as i64))
+}
+
+pub fn cmd_smismember(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let set = ctx.db.get_set(&ctx.args[1])?;
+    let exists = ctx.args[2..]
+        .iter()
+        .map(|member| Reply::Integer(set.is_some_and(|set| set.contains(member)) as i64))
+        .collect();
+
+    Ok(Reply::Array(exists))
+}
+
+pub fn cmd_scard(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let length = ctx.db.get_set(&ctx.args[1])?.map_or(0, |set| set.len());
+
+    Ok(Reply::Integer(length as i64))
+}
+
+pub fn cmd_spop(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let count = parse_count(ctx.args)?;
+    let length = ctx.db.get_set(&ctx.args[1])?.map_or(0, |set| set.len());
+    let randoms: Vec<u64> = (0..count.unwrap_or(1).min(length))
+        .map(|_| ctx.db.random())
+        .collect();
+    let mut popped = Vec::with_capacity(randoms.len());
+
+    if !randoms.is_empty() {
+        let set = ctx
+            .db
+            .get_set_mut(&ctx.args[1], false)?
+            .expect("set was checked");
+
+        for random in randoms {
+            let member = set
+                .member_at(random as usize % set.len())
+                .expect("index is within the set");
+
+            set.remove(&member);
+            popped.push(member);
+        }
+
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    remove_if_empty(ctx.db, &ctx.args[1]);
+
+    let mut srem = vec![b"SREM".to_vec(), ctx.args[1].clone()];
+
+    srem.extend(popped.iter().cloned());
+    ctx.propagate = Some(match popped.is_empty() {
+        true => vec![],
+        false => vec![srem],
+    });
+
+    match count {
+        Some(_) => Ok(set_reply(popped)),
+        None => Ok(popped.into_iter().next().map_or(Reply::Null, Reply::Bulk)),
+    }
+}
+
+pub fn cmd_srandmember(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let count = ctx
+        .args
+        .get(2)
+        .map(|count| parse_integer(count))
+        .transpose()?;
+
+    if ctx.args.len() > 3 {
+        return Err(syntax_error());
+    }
+
+    if count == Some(i64::MIN) {
+        return Err(Error::new(ErrorKind::InvalidData, "value is out of range"));
+    }
+
+    let length = ctx.db.get_set(&ctx.args[1])?.map_or(0, |set| set.len());
+
+    let Some(count) = count else {
+        return match length {
+            0 => Ok(Reply::Null),
+            _ => random_member(ctx.db, &ctx.args[1], length).map(Reply::Bulk),
+        };
+    };
+
+    if length == 0 || count == 0 {
+        return Ok(Reply::Array(vec![]));
+    }
+
+    if count < 0 {
+        let count = count.unsigned_abs() as usize;
+        let mut picked = Vec::with_capacity(count.min(length));
+
+        for _ in 0..count {
+            picked.push(Reply::Bulk(random_member(ctx.db, &ctx.args[1], length)?));
+        }
+
+        return Ok(Reply::Array(picked));
+    }
+
+    let count = count as usize;
+    let mut indexes = HashSet::with_capacity(count.min(length));
+
+    if count < length {
+        for upper in length - count..length {
+            let index = ctx.db.random() as usize % (upper + 1);
+
+            if !indexes.insert(index) {
+                indexes.insert(upper);
+            }
+        }
+    }
+
+    let set = ctx.db.get_set(&ctx.args[1])?.expect("set was checked");
+    let picked = match count < length {
+        true => indexes
+            .into_iter()
+            .filter_map(|index| set.member_at(index))
+            .collect(),
+        false => set.members(),
+    };
+
+    Ok(Reply::Array(picked.into_iter().map(Reply::Bulk).collect()))
+}
+
+pub fn cmd_sinter(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let members = combine(ctx.db, &ctx.args[1..], SetOperation::Inter)?;
+
+    Ok(set_reply(members))
+}
+
+pub fn cmd_sunion(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let members = combine(ctx.db, &ctx.args[1..], SetOperation::Union)?;
+
+    Ok(set_reply(members))
+}
+
+pub fn cmd_sdiff(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let members = combine(ctx.db, &ctx.args[1..], SetOperation::Diff)?;
+
+    Ok(set_reply(members))
+}
+
+pub fn cmd_sinterstore(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    store(ctx, SetOperation::Inter)
+}
+
+pub fn cmd_sunionstore(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    store(ctx, SetOperation::Union)
+}
+
+pub fn cmd_sdiffstore(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    store(ctx, SetOperation::Diff)
+}
+
+pub fn cmd_sintercard(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let numkeys = parse_integer(&ctx.args[1])?;
+
+    if numkeys <= 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "numkeys should be greater than 0",
+        ));
+    }
+
+    if numkeys as usize > ctx.args.len() - 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Number of keys can't be greater than number of args",
+        ));
+    }
+
+    let keys = &ctx.args[2..2 + numkeys as usize];
+    let mut limit = 0;
+    let mut rest = ctx.args[2 + numkeys as usize..].iter();
+
+    while let Some(option) = rest.next() {
+        match option.to_ascii_lowercase().as_slice() {
+            b"limit" => {
+                limit = parse_integer(rest.next().ok_or_else(syntax_error)?)?;
+
+                if limit < 0 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "LIMIT can't be negative",
+                    ));
+                }
+            }
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    let sets = load_sets(ctx.db, keys)?;
+    let Some((first, others)) = sets.split_first() else {
+        return Ok(Reply::Integer(0));
+    };
+
+    let mut count = 0;
+
+    for member in first.members() {
+        if others.iter().all(|set| set.contains(&member)) {
+            count += 1;
+
+            if count == limit {
+                break;
+            }
+        }
+    }
+
+    Ok(Reply::Integer(count))
+}
+
+fn store(ctx: &mut CommandContext, operation: SetOperation) -> Result<Reply, Error> {
+    let members = combine(ctx.db, &ctx.args[2..], operation)?;
+    let length = members.len();
+
+    match length {
+        0 => {
+            ctx.db.del(&ctx.args[1]);
+        }
+        _ => ctx.db.set(
+            &ctx.args[1],
+            Value::Set(members.into_iter().collect()),
+            None,
+        )?,
+    }
+
+    Ok(Reply::Integer(length as i64))
+}
+
+fn combine(
+    db: &mut dyn MemoryDatabase,
+    keys: &[Vec<u8>],
+    operation: SetOperation,
+) -> Result<Vec<Vec<u8>>, Error> {
+    let mut sets = load_sets(db, keys)?;
+
+    if operation == SetOperation::Inter {
+        sets.sort_by_key(|set| set.len());
+    }
+
+    let Some((first, others)) = sets.split_first() else {
+        return Ok(vec![]);
+    };
+
+    let members = match operation {
+        SetOperation::Inter => first
+            .members()
+            .into_iter()
+            .filter(|member| others.iter().all(|set| set.contains(member)))
+            .collect(),
+        SetOperation::Diff => first
+            .members()
+            .into_iter()
+            .filter(|member| !others.iter().any(|set| set.contains(member)))
+            .collect(),
+        SetOperation::Union => {
+            let mut union = first.clone();
+
+            for member in others.iter().flat_map(|set| set.members()) {
+                union.insert(&member);
+            }
+
+            union.members()
+        }
+    };
+
+    Ok(members)
+}
+
+fn load_sets(db: &mut dyn MemoryDatabase, keys: &[Vec<u8>]) -> Result<Vec<Set>, Error> {
+    keys.iter()
+        .map(|key| Ok(db.get_set(key)?.cloned().unwrap_or_default()))
+        .collect()
+}
+
+fn random_member(db: &mut dyn MemoryDatabase, key: &[u8], length: usize) -> Result<Vec<u8>, Error> {
+    let index = db.random() as usize % length;
+
+    Ok(db
+        .get_set(key)?
+        .and_then(|set| set.member_at(index))
+        .expect("index is within the set"))
+}
+
+fn parse_count(args: &[Vec<u8>]) -> Result<Option<usize>, Error> {
+    let count = match args.get(2) {
+        Some(count) => match parse_integer(count)? {
+            count if count < 0 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "value is out of range, must be positive",
+                ))
+            }
+            count => Some(count as usize),
+        },
+        None => None,
+    };
+
+    if args.len() > 3 {
+        return Err(syntax_error());
+    }
+
+    Ok(count)
+}
+
+fn set_reply(members: Vec<Vec<u8>>) -> Reply {
+    Reply::Set(members.into_iter().map(Reply::Bulk).collect())
+}
+
+fn remove_if_empty(db: &mut dyn MemoryDatabase, key: &[u8]) {
+    if let Ok(Some(set)) = db.get_set(key) {
+        if set.is_empty() {
+            db.del(key);
+        }
+    }
+}
@@ -0,0 +1,1590 @@
+use crate::redis::{
+    aof::Commands,
+    blocking::{BlockRequest, BlockedOperation},
+    cmd::args::{command_name, parse_integer, parse_timeout_ms, syntax_error},
+    command::CommandContext,
+    db::{unix_time_ms, MemoryDatabase},
+    reply::{Protocol, Reply},
+    types::{
+        stream::STREAM_NODE_MAX_ENTRIES, ConsumerGroup, PendingEntry, Stream, StreamFields,
+        StreamId, StreamTrim, TrimStrategy,
+    },
+};
+use std::{
+    io::{Error, ErrorKind},
+    ops::RangeInclusive,
+    time::Duration,
+};
+
+enum IdSpec {
+    Auto,
+    AutoSeq(u64),
+    Explicit(StreamId),
+}
+
+pub fn cmd_xadd(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let mut position = 2;
+    let mut make_stream = true;
+    let mut trim = None;
+
+    while let Some(option) = ctx.args.get(position) {
+        match option.to_ascii_lowercase().as_slice() {
+            b"nomkstream" => {
+                make_stream = false;
+                position += 1;
+            }
+            b"maxlen" | b"minid" => trim = Some(parse_trim(ctx.args, &mut position)?),
+            _ => break,
+        }
+    }
+
+    let fields = ctx.args.get(position + 1..).unwrap_or_default();
+
+    if fields.is_empty() || !fields.len().is_multiple_of(2) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "wrong number of arguments for '{}' command",
+                command_name(ctx.args)
+            ),
+        ));
+    }
+
+    let spec = parse_id_spec(&ctx.args[position])?;
+
+    let empty = Stream::new();
+    let id = match ctx.db.get_stream(&ctx.args[1])? {
+        Some(stream) => next_id(stream, spec)?,
+        None if make_stream => next_id(&empty, spec)?,
+        None => return Ok(Reply::Null),
+    };
+
+    let stream = ctx
+        .db
+        .get_stream_mut(&ctx.args[1], true)?
+        .expect("stream was just created");
+
+    let entry: StreamFields = fields
+        .chunks(2)
+        .map(|pair| (pair[0].clone(), pair[1].clone()))
+        .collect();
+
+    stream.add(id, entry);
+
+    if let Some(trim) = trim {
+        stream.trim(&trim);
+    }
+
+    ctx.db.touch(&ctx.args[1]);
+    ctx.ready_keys.push(ctx.args[1].clone());
+
+    let mut propagate = ctx.args.to_vec();
+
+    propagate[position] = id.to_string().into_bytes();
+    ctx.propagate = Some(vec![propagate]);
+
+    Ok(Reply::bulk(id.to_string()))
+}
+
+pub fn cmd_xrange(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    range(ctx, false)
+}
+
+pub fn cmd_xrevrange(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    range(ctx, true)
+}
+
+pub fn cmd_xlen(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let length = ctx
+        .db
+        .get_stream(&ctx.args[1])?
+        .map_or(0, |stream| stream.len());
+
+    Ok(Reply::Integer(length as i64))
+}
+
+pub fn cmd_xtrim(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let mut position = 2;
+    let trim = parse_trim(ctx.args, &mut position)?;
+
+    if position != ctx.args.len() {
+        return Err(syntax_error());
+    }
+
+    let removed = match ctx.db.get_stream_mut(&ctx.args[1], false)? {
+        Some(stream) => stream.trim(&trim),
+        None => 0,
+    };
+
+    if removed > 0 {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    Ok(Reply::Integer(removed as i64))
+}
+
+pub fn cmd_xdel(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let ids = ctx.args[2..]
+        .iter()
+        .map(|id| parse_stream_id(id, 0))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let deleted = match ctx.db.get_stream_mut(&ctx.args[1], false)? {
+        Some(stream) => ids.iter().filter(|id| stream.delete(id)).count(),
+        None => 0,
+    };
+
+    if deleted > 0 {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    Ok(Reply::Integer(deleted as i64))
+}
+
+pub fn cmd_xsetid(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let last_id = parse_stream_id(&ctx.args[2], 0)?;
+    let mut entries_added = None;
+    let mut max_deleted_id = None;
+    let mut position = 3;
+
+    while let Some(option) = ctx.args.get(position) {
+        let value = ctx.args.get(position + 1).ok_or_else(syntax_error)?;
+
+        match option.to_ascii_lowercase().as_slice() {
+            b"entriesadded" => {
+                let value = parse_integer(value)?;
+
+                if value < 0 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "entries_added must be positive",
+                    ));
+                }
+
+                entries_added = Some(value as u64);
+            }
+            b"maxdeletedid" => {
+                let id = parse_stream_id(value, 0)?;
+
+                if last_id < id {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        "The ID specified in XSETID is smaller than the provided max_deleted_entry_id",
+                    ));
+                }
+
+                max_deleted_id = Some(id);
+            }
+            _ => return Err(syntax_error()),
+        }
+
+        position += 2;
+    }
+
+    let Some(stream) = ctx.db.get_stream(&ctx.args[1])? else {
+        return Err(Error::new(ErrorKind::InvalidData, "no such key"));
+    };
+
+    if !stream.is_empty() && last_id < stream.last_id() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The ID specified in XSETID is smaller than the target stream top item",
+        ));
+    }
+
+    if entries_added.is_some_and(|added| added < stream.len() as u64) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The entries_added specified in XSETID is smaller than the target stream length",
+        ));
+    }
+
+    ctx.db
+        .get_stream_mut(&ctx.args[1], false)?
+        .expect("stream was checked")
+        .set_id(last_id, entries_added, max_deleted_id);
+    ctx.db.touch(&ctx.args[1]);
+
+    Ok(Reply::ok())
+}
+
+pub fn cmd_xread(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let options = parse_read(ctx.args)?;
+
+    if options.group.is_some() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The GROUP option is only supported by XREADGROUP. You called XREAD instead.",
+        ));
+    }
+
+    if options.noack {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The NOACK option is only supported by XREADGROUP. You called XREAD instead.",
+        ));
+    }
+
+    let mut cursors = Vec::with_capacity(options.keys.len());
+
+    for (key, id) in options.keys.iter().zip(options.ids) {
+        let id = match id.as_slice() {
+            b"$" => ctx
+                .db
+                .get_stream(key)?
+                .map_or(StreamId::MIN, |stream| stream.last_id()),
+            _ => parse_stream_id(id, 0)?,
+        };
+
+        cursors.push((key.clone(), id));
+    }
+
+    let mut replies = vec![];
+
+    for (key, id) in &cursors {
+        if let Some(entries) = read_entries(ctx.db, key, *id, options.count)? {
+            replies.push((key.clone(), entries));
+        }
+    }
+
+    if !replies.is_empty() {
+        return Ok(read_reply(replies, ctx.client.protocol));
+    }
+
+    if options.blocking {
+        ctx.block = Some(BlockRequest {
+            keys: options.keys.to_vec(),
+            operation: BlockedOperation::StreamRead {
+                cursors,
+                count: options.count,
+                protocol: ctx.client.protocol,
+            },
+            timeout: options.timeout,
+        });
+    }
+
+    Ok(Reply::NullArray)
+}
+
+pub fn cmd_xreadgroup(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let options = parse_read(ctx.args)?;
+    let Some((group, consumer)) = options.group else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "Missing GROUP option for XREADGROUP",
+        ));
+    };
+
+    let mut cursors = Vec::with_capacity(options.keys.len());
+
+    for (key, id) in options.keys.iter().zip(options.ids) {
+        let cursor = match id.as_slice() {
+            b">" => None,
+            b"$" => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The $ ID is meaningless in the context of XREADGROUP: you want to read the history of this consumer by specifying a proper ID, or use the > ID to get new messages. The $ ID would just return an empty result set.",
+                ))
+            }
+            _ => Some(parse_stream_id(id, 0)?),
+        };
+
+        if ctx
+            .db
+            .get_stream(key)?
+            .and_then(|stream| stream.group(group))
+            .is_none()
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "-NOGROUP No such key '{}' or consumer group '{}' in XREADGROUP with GROUP option",
+                    String::from_utf8_lossy(key),
+                    String::from_utf8_lossy(group)
+                ),
+            ));
+        }
+
+        cursors.push((key, cursor));
+    }
+
+    let now = unix_time_ms();
+    let mut replies = vec![];
+    let mut propagate = vec![];
+
+    for (key, cursor) in &cursors {
+        let Some(after) = cursor else {
+            let (entries, commands) =
+                read_group_entries(ctx.db, key, group, consumer, options.count, options.noack)?;
+
+            replies.extend(entries.map(|entries| (key.to_vec(), entries)));
+            propagate.extend(commands);
+            continue;
+        };
+
+        let stream = ctx
+            .db
+            .get_stream_mut(key, false)?
+            .expect("stream was checked");
+        let created = !stream
+            .group(group)
+            .expect("group was checked")
+            .consumers
+            .contains_key(consumer);
+        let history = stream.read_history(group, consumer, *after, options.count, now);
+        let delivered: Vec<StreamId> = history
+            .iter()
+            .filter(|(_, fields)| fields.is_some())
+            .map(|(id, _)| *id)
+            .collect();
+        let state = stream.group(group).expect("group was checked");
+        let commands = claim_propagation(
+            key,
+            group,
+            consumer,
+            state,
+            created,
+            state.last_id,
+            &delivered,
+        );
+
+        if !commands.is_empty() {
+            ctx.db.touch(key);
+        }
+
+        propagate.extend(commands);
+
+        let history = history
+            .into_iter()
+            .map(|(id, fields)| match fields {
+                Some(fields) => entry_reply(&id, &fields),
+                None => Reply::Array(vec![Reply::bulk(id.to_string()), Reply::NullArray]),
+            })
+            .collect();
+
+        replies.push((key.to_vec(), Reply::Array(history)));
+    }
+
+    ctx.propagate = Some(propagate);
+
+    if !replies.is_empty() {
+        return Ok(read_reply(replies, ctx.client.protocol));
+    }
+
+    if options.blocking {
+        ctx.block = Some(BlockRequest {
+            keys: options.keys.to_vec(),
+            operation: BlockedOperation::StreamReadGroup {
+                group: group.to_vec(),
+                consumer: consumer.to_vec(),
+                count: options.count,
+                noack: options.noack,
+                protocol: ctx.client.protocol,
+            },
+            timeout: options.timeout,
+        });
+    }
+
+    Ok(Reply::NullArray)
+}
+
+pub fn cmd_xgroup(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let subcommand = ctx.args[1].to_ascii_lowercase();
+    let arity_matches = match subcommand.as_slice() {
+        b"create" => ctx.args.len() >= 5,
+        b"setid" => ctx.args.len() >= 5,
+        b"destroy" => ctx.args.len() == 4,
+        b"createconsumer" | b"delconsumer" => ctx.args.len() == 5,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "unknown subcommand '{}'. Try XGROUP HELP.",
+                    String::from_utf8_lossy(&ctx.args[1])
+                ),
+            ))
+        }
+    };
+
+    if !arity_matches {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "wrong number of arguments for 'xgroup|{}' command",
+                String::from_utf8_lossy(&subcommand)
+            ),
+        ));
+    }
+
+    let (key, name) = (&ctx.args[2], &ctx.args[3]);
+    let mut make_stream = false;
+    let mut entries_read = None;
+    let mut group_id = None;
+
+    if matches!(subcommand.as_slice(), b"create" | b"setid") {
+        let mut position = 5;
+
+        if ctx.args[4] != b"$" {
+            group_id = Some(parse_stream_id(&ctx.args[4], 0)?);
+        }
+
+        while let Some(option) = ctx.args.get(position) {
+            match option.to_ascii_lowercase().as_slice() {
+                b"mkstream" if subcommand == b"create" => {
+                    make_stream = true;
+                    position += 1;
+                }
+                b"entriesread" => {
+                    let value =
+                        parse_integer(ctx.args.get(position + 1).ok_or_else(syntax_error)?)?;
+
+                    if value < -1 {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            "value for ENTRIESREAD must be positive or -1",
+                        ));
+                    }
+
+                    entries_read = Some((value >= 0).then_some(value as u64));
+                    position += 2;
+                }
+                _ => return Err(syntax_error()),
+            }
+        }
+    }
+
+    if let Some(stream) = ctx.db.get_stream(key)? {
+        match (subcommand.as_slice(), stream.group(name).is_some()) {
+            (b"create", true) => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "-BUSYGROUP Consumer Group name already exists",
+                ))
+            }
+            (b"create" | b"destroy", _) | (_, true) => {}
+            _ => return Err(no_such_group(key, name)),
+        }
+    }
+
+    let Some(stream) = ctx.db.get_stream_mut(key, make_stream)? else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.",
+        ));
+    };
+
+    let resolve = |stream: &Stream| match group_id {
+        Some(id) => (id, None),
+        None => (stream.last_id(), Some(stream.entries_added())),
+    };
+
+    match subcommand.as_slice() {
+        b"create" => {
+            let (id, estimated) = resolve(stream);
+            let group = ConsumerGroup::new(id, entries_read.unwrap_or(estimated));
+
+            stream.create_group(name, group);
+            ctx.db.touch(key);
+
+            Ok(Reply::ok())
+        }
+        b"setid" => {
+            let (id, estimated) = resolve(stream);
+            let group = stream.group_mut(name).expect("group was checked");
+
+            group.last_id = id;
+            group.entries_read = entries_read.unwrap_or(estimated);
+            ctx.db.touch(key);
+
+            Ok(Reply::ok())
+        }
+        b"destroy" => {
+            let destroyed = stream.destroy_group(name);
+
+            if destroyed {
+                ctx.db.touch(key);
+                ctx.ready_keys.push(key.clone());
+            }
+
+            Ok(Reply::Integer(destroyed as i64))
+        }
+        b"createconsumer" => {
+            let group = stream.group_mut(name).expect("group was checked");
+            let created = group.create_consumer(&ctx.args[4], unix_time_ms());
+
+            if created {
+                ctx.db.touch(key);
+            }
+
+            Ok(Reply::Integer(created as i64))
+        }
+        _ => {
+            let group = stream.group_mut(name).expect("group was checked");
+            let deleted = group.delete_consumer(&ctx.args[4]);
+
+            if deleted.is_some() {
+                ctx.db.touch(key);
+            }
+
+            let pending = deleted.unwrap_or(0);
+
+            Ok(Reply::Integer(pending as i64))
+        }
+    }
+}
+
+pub fn cmd_xack(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let ids = ctx.args[3..]
+        .iter()
+        .map(|id| parse_stream_id(id, 0))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    let group = ctx
+        .db
+        .get_stream_mut(&ctx.args[1], false)?
+        .and_then(|stream| stream.group_mut(&ctx.args[2]));
+
+    let acked = match group {
+        Some(group) => ids.iter().filter(|id| group.ack(id)).count(),
+        None => 0,
+    };
+
+    if acked > 0 {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    Ok(Reply::Integer(acked as i64))
+}
+
+pub fn cmd_xpending(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let (key, name) = (&ctx.args[1], &ctx.args[2]);
+    let mut position = 3;
+    let mut min_idle = 0;
+
+    if ctx
+        .args
+        .get(position)
+        .is_some_and(|arg| arg.eq_ignore_ascii_case(b"idle"))
+    {
+        min_idle = parse_integer(ctx.args.get(position + 1).ok_or_else(syntax_error)?)?;
+        position += 2;
+    }
+
+    let extended = match ctx.args.len() - position {
+        0 if position == 3 => None,
+        3 | 4 => {
+            let range = parse_interval(&ctx.args[position], &ctx.args[position + 1])?;
+            let count = parse_integer(&ctx.args[position + 2])?.max(0) as usize;
+
+            Some((range, count, ctx.args.get(position + 3)))
+        }
+        _ => return Err(syntax_error()),
+    };
+
+    let group = ctx
+        .db
+        .get_stream(key)?
+        .and_then(|stream| stream.group(name))
+        .ok_or_else(|| no_group(key, name))?;
+
+    let Some((range, count, consumer)) = extended else {
+        let (Some((first, _)), Some((last, _))) = (
+            group.pending.first_key_value(),
+            group.pending.last_key_value(),
+        ) else {
+            return Ok(Reply::Array(vec![
+                Reply::Integer(0),
+                Reply::Null,
+                Reply::Null,
+                Reply::NullArray,
+            ]));
+        };
+
+        let consumers = group
+            .consumers
+            .iter()
+            .filter(|(_, consumer)| !consumer.pending.is_empty())
+            .map(|(name, consumer)| {
+                Reply::Array(vec![
+                    Reply::bulk(name),
+                    Reply::bulk(consumer.pending.len().to_string()),
+                ])
+            })
+            .collect();
+
+        return Ok(Reply::Array(vec![
+            Reply::Integer(group.pending.len() as i64),
+            Reply::bulk(first.to_string()),
+            Reply::bulk(last.to_string()),
+            Reply::Array(consumers),
+        ]));
+    };
+
+    let now = unix_time_ms();
+    let ids: Vec<&StreamId> = match (range, consumer) {
+        (None, _) => vec![],
+        (Some(range), Some(consumer)) => match group.consumers.get(consumer) {
+            Some(consumer) => consumer.pending.range(range).collect(),
+            None => vec![],
+        },
+        (Some(range), None) => group.pending.range(range).map(|(id, _)| id).collect(),
+    };
+
+    let entries = ids
+        .into_iter()
+        .filter_map(|id| group.pending.get(id).map(|entry| (id, entry)))
+        .map(|(id, entry)| (id, entry, now.saturating_sub(entry.delivery_time)))
+        .filter(|(_, _, idle)| *idle as i64 >= min_idle)
+        .take(count)
+        .map(|(id, entry, idle)| {
+            Reply::Array(vec![
+                Reply::bulk(id.to_string()),
+                Reply::bulk(&entry.consumer),
+                Reply::Integer(idle as i64),
+                Reply::Integer(entry.delivery_count as i64),
+            ])
+        })
+        .collect();
+
+    Ok(Reply::Array(entries))
+}
+
+pub fn cmd_xclaim(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let (key, name, consumer) = (&ctx.args[1], &ctx.args[2], &ctx.args[3]);
+    let min_idle = parse_min_idle(&ctx.args[4], "XCLAIM")?;
+    let now = unix_time_ms();
+    let mut ids = vec![];
+    let mut position = 5;
+
+    while let Some(Ok(id)) = ctx.args.get(position).map(|id| parse_stream_id(id, 0)) {
+        ids.push(id);
+        position += 1;
+    }
+
+    let mut delivery_time = now as i64;
+    let mut retry_count = None;
+    let mut force = false;
+    let mut justid = false;
+    let mut last_id = None;
+
+    while let Some(option) = ctx.args.get(position) {
+        let value = ctx.args.get(position + 1);
+
+        position += match (option.to_ascii_lowercase().as_slice(), value) {
+            (b"force", _) => {
+                force = true;
+                1
+            }
+            (b"justid", _) => {
+                justid = true;
+                1
+            }
+            (b"idle", Some(value)) => {
+                delivery_time = now as i64 - parse_integer(value)?;
+                2
+            }
+            (b"time", Some(value)) => {
+                delivery_time = parse_integer(value)?;
+                2
+            }
+            (b"retrycount", Some(value)) => {
+                retry_count = Some(parse_integer(value)?.max(0) as u64);
+                2
+            }
+            (b"lastid", Some(value)) => {
+                last_id = Some(parse_stream_id(value, 0)?);
+                2
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Unrecognized XCLAIM option '{}'",
+                        String::from_utf8_lossy(option)
+                    ),
+                ))
+            }
+        };
+    }
+
+    let delivery_time = match delivery_time {
+        time if time < 0 || time as u64 > now => now,
+        time => time as u64,
+    };
+
+    let stream = claim_target(ctx.db, key, name)?;
+    let group = stream.group_mut(name).expect("group was checked");
+    let previous_id = group.last_id;
+    let created = !group.consumers.contains_key(consumer);
+
+    if let Some(last_id) = last_id {
+        group.last_id = group.last_id.max(last_id);
+    }
+
+    group.touch_consumer(consumer, now);
+
+    let mut claimed = vec![];
+    let mut deleted = vec![];
+
+    for id in ids {
+        let exists = stream.get(&id).is_some();
+        let group = stream.group_mut(name).expect("group was checked");
+
+        if !exists {
+            if group.ack(&id) {
+                deleted.push(id);
+            }
+
+            continue;
+        }
+
+        let delivery_count = match group.pending.get(&id) {
+            Some(entry) if now.saturating_sub(entry.delivery_time) < min_idle => continue,
+            Some(entry) => entry.delivery_count,
+            None if force => 1,
+            None => continue,
+        };
+
+        let entry = PendingEntry {
+            consumer: consumer.clone(),
+            delivery_time,
+            delivery_count: match (retry_count, justid) {
+                (Some(retry_count), _) => retry_count,
+                (None, true) => delivery_count,
+                (None, false) => delivery_count + 1,
+            },
+        };
+
+        group.assign(id, entry, now);
+        claimed.push(id);
+    }
+
+    let group = stream.group(name).expect("group was checked");
+
+    let mut propagate =
+        claim_propagation(key, name, consumer, group, created, previous_id, &claimed);
+
+    propagate.extend(ack_propagation(key, name, &deleted));
+
+    let reply = claim_reply(stream, &claimed, justid);
+
+    if !propagate.is_empty() {
+        ctx.db.touch(key);
+    }
+
+    ctx.propagate = Some(propagate);
+
+    Ok(Reply::Array(reply))
+}
+
+pub fn cmd_xautoclaim(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let (key, name, consumer) = (&ctx.args[1], &ctx.args[2], &ctx.args[3]);
+    let min_idle = parse_min_idle(&ctx.args[4], "XAUTOCLAIM")?;
+    let start = parse_range_id(&ctx.args[5], 0)?.ok_or_else(invalid_stream_id)?;
+    let mut count = 100;
+    let mut justid = false;
+    let mut position = 6;
+
+    while let Some(option) = ctx.args.get(position) {
+        match option.to_ascii_lowercase().as_slice() {
+            b"count" => {
+                let value = parse_integer(ctx.args.get(position + 1).ok_or_else(syntax_error)?)?;
+
+                if !(1..=i64::MAX / 10).contains(&value) {
+                    return Err(Error::new(ErrorKind::InvalidData, "COUNT must be > 0"));
+                }
+
+                count = value as usize;
+                position += 2;
+            }
+            b"justid" => {
+                justid = true;
+                position += 1;
+            }
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    let now = unix_time_ms();
+    let attempts = count * 10;
+    let stream = claim_target(ctx.db, key, name)?;
+    let group = stream.group_mut(name).expect("group was checked");
+    let created = !group.consumers.contains_key(consumer);
+
+    group.touch_consumer(consumer, now);
+
+    let ids: Vec<StreamId> = group
+        .pending
+        .range(start..)
+        .map(|(id, _)| *id)
+        .take(attempts + 1)
+        .collect();
+
+    let mut examined = 0;
+    let mut claimed = vec![];
+    let mut deleted = vec![];
+
+    for id in &ids {
+        if examined == attempts || claimed.len() == count {
+            break;
+        }
+
+        examined += 1;
+
+        let exists = stream.get(id).is_some();
+        let group = stream.group_mut(name).expect("group was checked");
+
+        if !exists {
+            group.ack(id);
+            deleted.push(*id);
+            continue;
+        }
+
+        let entry = &group.pending[id];
+
+        if now.saturating_sub(entry.delivery_time) < min_idle {
+            continue;
+        }
+
+        let entry = PendingEntry {
+            consumer: consumer.clone(),
+            delivery_time: now,
+            delivery_count: entry.delivery_count + u64::from(!justid),
+        };
+
+        group.assign(*id, entry, now);
+        claimed.push(*id);
+    }
+
+    let cursor = ids.get(examined).copied().unwrap_or(StreamId::MIN);
+    let group = stream.group(name).expect("group was checked");
+
+    let mut propagate =
+        claim_propagation(key, name, consumer, group, created, group.last_id, &claimed);
+
+    propagate.extend(ack_propagation(key, name, &deleted));
+
+    let reply = claim_reply(stream, &claimed, justid);
+
+    if !propagate.is_empty() {
+        ctx.db.touch(key);
+    }
+
+    ctx.propagate = Some(propagate);
+
+    Ok(Reply::Array(vec![
+        Reply::bulk(cursor.to_string()),
+        Reply::Array(reply),
+        Reply::Array(
+            deleted
+                .iter()
+                .map(|id| Reply::bulk(id.to_string()))
+                .collect(),
+        ),
+    ]))
+}
+
+pub fn cmd_xinfo(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let subcommand = ctx.args[1].to_ascii_lowercase();
+    let arity_matches = match subcommand.as_slice() {
+        b"stream" => ctx.args.len() >= 3,
+        b"groups" => ctx.args.len() == 3,
+        b"consumers" => ctx.args.len() == 4,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "unknown subcommand '{}'. Try XINFO HELP.",
+                    String::from_utf8_lossy(&ctx.args[1])
+                ),
+            ))
+        }
+    };
+
+    if !arity_matches {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "wrong number of arguments for 'xinfo|{}' command",
+                String::from_utf8_lossy(&subcommand)
+            ),
+        ));
+    }
+
+    let full = match &ctx.args[3..] {
+        [] => None,
+        [option] if option.eq_ignore_ascii_case(b"full") => Some(10),
+        [option, count, value]
+            if option.eq_ignore_ascii_case(b"full") && count.eq_ignore_ascii_case(b"count") =>
+        {
+            Some(parse_integer(value)?.max(0) as usize)
+        }
+        _ if subcommand == b"stream" => return Err(syntax_error()),
+        _ => None,
+    };
+
+    let key = &ctx.args[2];
+    let stream = ctx
+        .db
+        .get_stream(key)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "no such key"))?;
+    let now = unix_time_ms();
+
+    match subcommand.as_slice() {
+        b"stream" => Ok(stream_info(stream, full)),
+        b"groups" => Ok(Reply::Array(
+            stream
+                .groups()
+                .map(|(name, group)| {
+                    Reply::Map(vec![
+                        field("name", Reply::bulk(name)),
+                        field("consumers", Reply::Integer(group.consumers.len() as i64)),
+                        field("pending", Reply::Integer(group.pending.len() as i64)),
+                        field("last-delivered-id", Reply::bulk(group.last_id.to_string())),
+                        field("entries-read", optional_integer(group.entries_read)),
+                        field("lag", optional_integer(stream.lag(group))),
+                    ])
+                })
+                .collect(),
+        )),
+        _ => {
+            let name = &ctx.args[3];
+            let group = stream.group(name).ok_or_else(|| no_such_group(key, name))?;
+
+            Ok(Reply::Array(
+                group
+                    .consumers
+                    .iter()
+                    .map(|(name, consumer)| {
+                        let inactive = consumer
+                            .active_time
+                            .map_or(-1, |active| now.saturating_sub(active) as i64);
+
+                        Reply::Map(vec![
+                            field("name", Reply::bulk(name)),
+                            field("pending", Reply::Integer(consumer.pending.len() as i64)),
+                            field(
+                                "idle",
+                                Reply::Integer(now.saturating_sub(consumer.seen_time) as i64),
+                            ),
+                            field("inactive", Reply::Integer(inactive)),
+                        ])
+                    })
+                    .collect(),
+            ))
+        }
+    }
+}
+
+pub fn read_entries(
+    db: &mut dyn MemoryDatabase,
+    key: &[u8],
+    after: StreamId,
+    count: Option<usize>,
+) -> Result<Option<Reply>, Error> {
+    let (Some(stream), Some(start)) = (db.get_stream(key)?, after.next()) else {
+        return Ok(None);
+    };
+
+    let entries: Vec<Reply> = stream
+        .range(start..=StreamId::MAX)
+        .take(count.unwrap_or(usize::MAX))
+        .map(|(id, fields)| entry_reply(id, fields))
+        .collect();
+
+    Ok((!entries.is_empty()).then_some(Reply::Array(entries)))
+}
+
+pub fn read_group_entries(
+    db: &mut dyn MemoryDatabase,
+    key: &[u8],
+    group: &[u8],
+    consumer: &[u8],
+    count: Option<usize>,
+    noack: bool,
+) -> Result<(Option<Reply>, Commands), Error> {
+    let Some(stream) = db.get_stream(key)? else {
+        return Ok((None, vec![]));
+    };
+
+    let Some(state) = stream.group(group) else {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "-NOGROUP the consumer group this client was blocked on no longer exists",
+        ));
+    };
+
+    let previous_id = state.last_id;
+    let created = !state.consumers.contains_key(consumer);
+    let stream = db.get_stream_mut(key, false)?.expect("stream was checked");
+    let entries = stream.read_group(group, consumer, count, noack, unix_time_ms());
+    let claimed: Vec<StreamId> = match noack {
+        true => vec![],
+        false => entries.iter().map(|(id, _)| *id).collect(),
+    };
+    let propagate = claim_propagation(
+        key,
+        group,
+        consumer,
+        stream.group(group).expect("group was checked"),
+        created,
+        previous_id,
+        &claimed,
+    );
+    let entries: Vec<Reply> = entries
+        .iter()
+        .map(|(id, fields)| entry_reply(id, fields))
+        .collect();
+
+    if !propagate.is_empty() {
+        db.touch(key);
+    }
+
+    Ok((
+        (!entries.is_empty()).then_some(Reply::Array(entries)),
+        propagate,
+    ))
+}
+
+pub fn read_reply(replies: Vec<(Vec<u8>, Reply)>, protocol: Protocol) -> Reply {
+    match protocol {
+        Protocol::RESP3 => Reply::Map(
+            replies
+                .into_iter()
+                .map(|(key, entries)| (Reply::Bulk(key), entries))
+                .collect(),
+        ),
+        Protocol::RESP2 => Reply::Array(
+            replies
+                .into_iter()
+                .map(|(key, entries)| Reply::Array(vec![Reply::Bulk(key), entries]))
+                .collect(),
+        ),
+    }
+}
+
+pub fn parse_stream_id(arg: &[u8], default_seq: u64) -> Result<StreamId, Error> {
+    let parse = |part: &[u8]| {
+        std::str::from_utf8(part)
+            .ok()
+            .filter(|part| part.bytes().all(|byte| byte.is_ascii_digit()))
+            .and_then(|part| part.parse::<u64>().ok())
+            .ok_or_else(invalid_stream_id)
+    };
+
+    match arg.iter().position(|byte| *byte == b'-') {
+        Some(separator) => Ok(StreamId::new(
+            parse(&arg[..separator])?,
+            parse(&arg[separator + 1..])?,
+        )),
+        None => Ok(StreamId::new(parse(arg)?, default_seq)),
+    }
+}
+
+pub fn entry_reply(id: &StreamId, fields: &StreamFields) -> Reply {
+    Reply::Array(vec![
+        Reply::bulk(id.to_string()),
+        Reply::Array(
+            fields
+                .iter()
+                .flat_map(|(field, value)| [Reply::bulk(field), Reply::bulk(value)])
+                .collect(),
+        ),
+    ])
+}
+
+pub fn invalid_stream_id() -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        "Invalid stream ID specified as stream command argument",
+    )
+}
+
+fn range(ctx: &mut CommandContext, reverse: bool) -> Result<Reply, Error> {
+    let (start, end) = match reverse {
+        true => (&ctx.args[3], &ctx.args[2]),
+        false => (&ctx.args[2], &ctx.args[3]),
+    };
+
+    let range = parse_interval(start, end)?;
+    let count = match ctx.args.get(4) {
+        Some(option) if option.eq_ignore_ascii_case(b"count") && ctx.args.len() == 6 => {
+            Some(parse_integer(&ctx.args[5])?.max(0) as usize)
+        }
+        Some(_) => return Err(syntax_error()),
+        None => None,
+    };
+
+    let Some(stream) = ctx.db.get_stream(&ctx.args[1])? else {
+        return Ok(Reply::Array(vec![]));
+    };
+
+    let Some(range) = range else {
+        return Ok(Reply::Array(vec![]));
+    };
+
+    let entries = stream.range(range);
+    let entries: Box<dyn Iterator<Item = _>> = match reverse {
+        true => Box::new(entries.rev()),
+        false => Box::new(entries),
+    };
+
+    Ok(Reply::Array(
+        entries
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(id, fields)| entry_reply(id, fields))
+            .collect(),
+    ))
+}
+
+fn parse_range_id(arg: &[u8], default_seq: u64) -> Result<Option<StreamId>, Error> {
+    match arg {
+        b"-" => Ok(Some(StreamId::MIN)),
+        b"+" => Ok(Some(StreamId::MAX)),
+        _ => match arg.strip_prefix(b"(") {
+            Some(id) if default_seq == 0 => Ok(parse_stream_id(id, default_seq)?.next()),
+            Some(id) => Ok(parse_stream_id(id, default_seq)?.prev()),
+            None => parse_stream_id(arg, default_seq).map(Some),
+        },
+    }
+}
+
+fn parse_id_spec(arg: &[u8]) -> Result<IdSpec, Error> {
+    if arg == b"*" {
+        return Ok(IdSpec::Auto);
+    }
+
+    match arg.strip_suffix(b"-*") {
+        Some(ms) => Ok(IdSpec::AutoSeq(parse_stream_id(ms, 0)?.ms)),
+        None => Ok(IdSpec::Explicit(parse_stream_id(arg, 0)?)),
+    }
+}
+
+fn parse_trim(args: &[Vec<u8>], position: &mut usize) -> Result<StreamTrim, Error> {
+    let strategy = args[*position].to_ascii_lowercase();
+    let mut approximate = false;
+
+    *position += 1;
+
+    match args.get(*position).map(|arg| arg.as_slice()) {
+        Some(b"~") => {
+            approximate = true;
+            *position += 1;
+        }
+        Some(b"=") => *position += 1,
+        _ => {}
+    }
+
+    let threshold = args.get(*position).ok_or_else(syntax_error)?;
+    let strategy = match strategy.as_slice() {
+        b"maxlen" => match parse_integer(threshold)? {
+            max_len if max_len < 0 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "The MAXLEN argument must be >= 0.",
+                ))
+            }
+            max_len => TrimStrategy::MaxLen(max_len as usize),
+        },
+        _ => TrimStrategy::MinId(parse_stream_id(threshold, 0)?),
+    };
+
+    *position += 1;
+
+    let mut limit = match approximate {
+        true => 100 * STREAM_NODE_MAX_ENTRIES,
+        false => 0,
+    };
+
+    if args
+        .get(*position)
+        .is_some_and(|arg| arg.eq_ignore_ascii_case(b"limit"))
+    {
+        let value = parse_integer(args.get(*position + 1).ok_or_else(syntax_error)?)?;
+
+        if value < 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The LIMIT argument must be >= 0.",
+            ));
+        }
+
+        if !approximate {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "syntax error, LIMIT cannot be used without the special ~ option",
+            ));
+        }
+
+        limit = value as usize;
+        *position += 2;
+    }
+
+    Ok(StreamTrim {
+        strategy,
+        approximate,
+        limit,
+    })
+}
+
+fn next_id(stream: &Stream, spec: IdSpec) -> Result<StreamId, Error> {
+    let too_small = || {
+        Error::new(
+            ErrorKind::InvalidData,
+            "The ID specified in XADD is equal or smaller than the target stream top item",
+        )
+    };
+
+    match spec {
+        IdSpec::Auto => stream.auto_id(unix_time_ms()).ok_or_else(|| {
+            Error::new(
+                ErrorKind::InvalidData,
+                "The stream has exhausted the last possible ID, unable to add more items",
+            )
+        }),
+        IdSpec::AutoSeq(ms) => stream.auto_seq(ms).ok_or_else(too_small),
+        IdSpec::Explicit(StreamId::MIN) => Err(Error::new(
+            ErrorKind::InvalidData,
+            "The ID specified in XADD must be greater than 0-0",
+        )),
+        IdSpec::Explicit(id) if id <= stream.last_id() => Err(too_small()),
+        IdSpec::Explicit(id) => Ok(id),
+    }
+}
+
+struct ReadOptions<'a> {
+    count: Option<usize>,
+    timeout: Option<Duration>,
+    blocking: bool,
+    noack: bool,
+    group: Option<(&'a [u8], &'a [u8])>,
+    keys: &'a [Vec<u8>],
+    ids: &'a [Vec<u8>],
+}
+
+fn parse_read(args: &[Vec<u8>]) -> Result<ReadOptions<'_>, Error> {
+    let mut position = 1;
+    let mut options = ReadOptions {
+        count: None,
+        timeout: None,
+        blocking: false,
+        noack: false,
+        group: None,
+        keys: &[],
+        ids: &[],
+    };
+
+    loop {
+        let option = args.get(position).ok_or_else(syntax_error)?;
+        let value = args.get(position + 1);
+
+        match (option.to_ascii_lowercase().as_slice(), value) {
+            (b"streams", _) => break,
+            (b"noack", _) => {
+                options.noack = true;
+                position += 1;
+                continue;
+            }
+            (b"count", Some(value)) => {
+                options.count = match parse_integer(value)? {
+                    count if count < 0 => {
+                        return Err(Error::new(ErrorKind::InvalidData, "value is out of range"))
+                    }
+                    0 => None,
+                    count => Some(count as usize),
+                };
+            }
+            (b"block", Some(value)) => {
+                options.timeout = parse_timeout_ms(value)?;
+                options.blocking = true;
+            }
+            (b"group", Some(group)) => {
+                let consumer = args.get(position + 2).ok_or_else(syntax_error)?;
+
+                options.group = Some((group, consumer));
+                position += 1;
+            }
+            _ => return Err(syntax_error()),
+        }
+
+        position += 2;
+    }
+
+    let streams = &args[position + 1..];
+
+    if streams.is_empty() || !streams.len().is_multiple_of(2) {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Unbalanced '{}' list of streams: for each stream key an ID or '$' must be specified.",
+                command_name(args)
+            ),
+        ));
+    }
+
+    (options.keys, options.ids) = streams.split_at(streams.len() / 2);
+
+    Ok(options)
+}
+
+fn parse_interval(start: &[u8], end: &[u8]) -> Result<Option<RangeInclusive<StreamId>>, Error> {
+    let start = parse_range_id(start, 0)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "invalid start ID for the interval"))?;
+    let end = parse_range_id(end, u64::MAX)?
+        .ok_or_else(|| Error::new(ErrorKind::InvalidData, "invalid end ID for the interval"))?;
+
+    Ok((start <= end).then_some(start..=end))
+}
+
+fn parse_min_idle(arg: &[u8], command: &str) -> Result<u64, Error> {
+    parse_integer(arg)
+        .map(|min_idle| min_idle.max(0) as u64)
+        .map_err(|_| {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid min-idle-time argument for {}", command),
+            )
+        })
+}
+
+fn claim_target<'a>(
+    db: &'a mut dyn MemoryDatabase,
+    key: &[u8],
+    name: &[u8],
+) -> Result<&'a mut Stream, Error> {
+    if db
+        .get_stream(key)?
+        .and_then(|stream| stream.group(name))
+        .is_none()
+    {
+        return Err(no_group(key, name));
+    }
+
+    Ok(db.get_stream_mut(key, false)?.expect("stream was checked"))
+}
+
+fn claim_propagation(
+    key: &[u8],
+    name: &[u8],
+    consumer: &[u8],
+    group: &ConsumerGroup,
+    created: bool,
+    previous_id: StreamId,
+    claimed: &[StreamId],
+) -> Commands {
+    let mut commands = vec![];
+
+    if created {
+        commands.push(vec![
+            b"XGROUP".to_vec(),
+            b"CREATECONSUMER".to_vec(),
+            key.to_vec(),
+            name.to_vec(),
+            consumer.to_vec(),
+        ]);
+    }
+
+    if group.last_id != previous_id {
+        let entries_read = group
+            .entries_read
+            .map_or(String::from("-1"), |read| read.to_string());
+
+        commands.push(vec![
+            b"XGROUP".to_vec(),
+            b"SETID".to_vec(),
+            key.to_vec(),
+            name.to_vec(),
+            group.last_id.to_string().into_bytes(),
+            b"ENTRIESREAD".to_vec(),
+            entries_read.into_bytes(),
+        ]);
+    }
+
+    for id in claimed {
+        let entry = &group.pending[id];
+
+        commands.push(vec![
+            b"XCLAIM".to_vec(),
+            key.to_vec(),
+            name.to_vec(),
+            consumer.to_vec(),
+            b"0".to_vec(),
+            id.to_string().into_bytes(),
+            b"TIME".to_vec(),
+            entry.delivery_time.to_string().into_bytes(),
+            b"RETRYCOUNT".to_vec(),
+            entry.delivery_count.to_string().into_bytes(),
+            b"JUSTID".to_vec(),
+            b"FORCE".to_vec(),
+        ]);
+    }
+
+    commands
+}
+
+fn ack_propagation(key: &[u8], name: &[u8], deleted: &[StreamId]) -> Option<Vec<Vec<u8>>> {
+    if deleted.is_empty() {
+        return None;
+    }
+
+    let mut xack = vec![b"XACK".to_vec(), key.to_vec(), name.to_vec()];
+
+    xack.extend(deleted.iter().map(|id| id.to_string().into_bytes()));
+
+    Some(xack)
+}
+
+fn claim_reply(stream: &Stream, claimed: &[StreamId], justid: bool) -> Vec<Reply> {
+    claimed
+        .iter()
+        .map(|id| match (justid, stream.get(id)) {
+            (false, Some(fields)) => entry_reply(id, fields),
+            _ => Reply::bulk(id.to_string()),
+        })
+        .collect()
+}
+
+fn stream_info(stream: &Stream, full: Option<usize>) -> Reply {
+    let nodes = stream.len().div_ceil(STREAM_NODE_MAX_ENTRIES) as i64;
+    let first_id = stream.first_entry().map_or(StreamId::MIN, |(id, _)| *id);
+    let mut fields = vec![
+        field("length", Reply::Integer(stream.len() as i64)),
+        field("radix-tree-keys", Reply::Integer(nodes)),
+        field("radix-tree-nodes", Reply::Integer(nodes)),
+        field(
+            "last-generated-id",
+            Reply::bulk(stream.last_id().to_string()),
+        ),
+        field(
+            "max-deleted-entry-id",
+            Reply::bulk(stream.max_deleted_id().to_string()),
+        ),
+        field(
+            "entries-added",
+            Reply::Integer(stream.entries_added() as i64),
+        ),
+        field("recorded-first-entry-id", Reply::bulk(first_id.to_string())),
+    ];
+
+    let Some(count) = full else {
+        let entry = |entry: Option<(&StreamId, &StreamFields)>| {
+            entry.map_or(Reply::Null, |(id, fields)| entry_reply(id, fields))
+        };
+
+        fields.extend([
+            field("groups", Reply::Integer(stream.groups().count() as i64)),
+            field("first-entry", entry(stream.first_entry())),
+            field("last-entry", entry(stream.last_entry())),
+        ]);
+
+        return Reply::Map(fields);
+    };
+
+    let limit = match count {
+        0 => usize::MAX,
+        count => count,
+    };
+
+    let groups = stream
+        .groups()
+        .map(|(name, group)| {
+            let pending = group
+                .pending
+                .iter()
+                .take(limit)
+                .map(|(id, entry)| {
+                    Reply::Array(vec![
+                        Reply::bulk(id.to_string()),
+                        Reply::bulk(&entry.consumer),
+                        Reply::Integer(entry.delivery_time as i64),
+                        Reply::Integer(entry.delivery_count as i64),
+                    ])
+                })
+                .collect();
+
+            let consumers = group
+                .consumers
+                .iter()
+                .map(|(name, consumer)| {
+                    let pending = consumer
+                        .pending
+                        .iter()
+                        .take(limit)
+                        .filter_map(|id| group.pending.get(id).map(|entry| (id, entry)))
+                        .map(|(id, entry)| {
+                            Reply::Array(vec![
+                                Reply::bulk(id.to_string()),
+                                Reply::Integer(entry.delivery_time as i64),
+                                Reply::Integer(entry.delivery_count as i64),
+                            ])
+                        })
+                        .collect();
+
+                    Reply::Map(vec![
+                        field("name", Reply::bulk(name)),
+                        field("seen-time", Reply::Integer(consumer.seen_time as i64)),
+                        field(
+                            "active-time",
+                            Reply::Integer(consumer.active_time.map_or(-1, |time| time as i64)),
+                        ),
+                        field("pel-count", Reply::Integer(consumer.pending.len() as i64)),
+                        field("pending", Reply::Array(pending)),
+                    ])
+                })
+                .collect();
+
+            Reply::Map(vec![
+                field("name", Reply::bulk(name)),
+                field("last-delivered-id", Reply::bulk(group.last_id.to_string())),
+                field("entries-read", optional_integer(group.entries_read)),
+                field("lag", optional_integer(stream.lag(group))),
+                field("pel-count", Reply::Integer(group.pending.len() as i64)),
+                field("pending", Reply::Array(pending)),
+                field("consumers", Reply::Array(consumers)),
+            ])
+        })
+        .collect();
+
+    let entries = stream
+        .range(StreamId::MIN..=StreamId::MAX)
+        .take(limit)
+        .map(|(id, fields)| entry_reply(id, fields))
+        .collect();
+
+    fields.extend([
+        field("entries", Reply::Array(entries)),
+        field("groups", Reply::Array(groups)),
+    ]);
+
+    Reply::Map(fields)
+}
+
+fn field(name: &str, value: Reply) -> (Reply, Reply) {
+    (Reply::bulk(name), value)
+}
+
+fn optional_integer(value: Option<u64>) -> Reply {
+    value.map_or(Reply::Null, |value| Reply::Integer(value as i64))
+}
+
+fn no_group(key: &[u8], group: &[u8]) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "-NOGROUP No such key '{}' or consumer group '{}'",
+            String::from_utf8_lossy(key),
+            String::from_utf8_lossy(group)
+        ),
+    )
+}
+
+fn no_such_group(key: &[u8], group: &[u8]) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "-NOGROUP No such consumer group '{}' for key name '{}'",
+            String::from_utf8_lossy(group),
+            String::from_utf8_lossy(key)
+        ),
+    )
+}
@@ -0,0 +1,125 @@
+use crate::redis::{
+    client::Client,
+    client::Transaction,
+    command::{lookup_command, CommandContext},
+    db::MemoryDatabase,
+    reply::Reply,
+};
+use std::io::{Error, ErrorKind};
+
+pub fn cmd_multi(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    if ctx.client.transaction.is_some() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "MULTI calls can not be nested",
+        ));
+    }
+
+    ctx.client.transaction = Some(Transaction::default());
+
+    Ok(Reply::Simple("OK".to_string()))
+}
+
+pub fn cmd_exec(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let Some(transaction) = ctx.client.transaction.take() else {
+        return Err(Error::new(ErrorKind::InvalidData, "EXEC without MULTI"));
+    };
+
+    let dirty = ctx
+        .client
+        .watched
+        .iter()
+        .any(|(key, version)| ctx.db.version(key) != *version);
+
+    unwatch_all(ctx.client, ctx.db);
+
+    if transaction.aborted {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "-EXECABORT Transaction discarded because of previous errors.",
+        ));
+    }
+
+    if dirty {
+        return Ok(Reply::NullArray);
+    }
+
+    let mut replies = Vec::with_capacity(transaction.commands.len());
+    let mut propagated = vec![];
+
+    for args in &transaction.commands {
+        let command = lookup_command(&args[0]).expect("queued commands are known");
+        let dirty = ctx.db.dirty();
+        let mut queued = CommandContext {
+            args,
+            db: &mut *ctx.db,
+            client: &mut *ctx.client,
+            pubsub: ctx.pubsub,
+            persistence: ctx.persistence,
+            ready_keys: vec![],
+            block: None,
+            propagate: None,
+        };
+
+        let reply = (command.handler)(&mut queued);
+
+        if reply.is_ok() && queued.db.dirty() != dirty {
+            propagated.extend(queued.propagated(command));
+        }
+
+        replies.push(reply.unwrap_or_else(Reply::from_error));
+        ctx.ready_keys.append(&mut queued.ready_keys);
+    }
+
+    if !propagated.is_empty() {
+        propagated.insert(0, vec![b"MULTI".to_vec()]);
+        propagated.push(vec![b"EXEC".to_vec()]);
+    }
+
+    ctx.propagate = Some(propagated);
+
+    Ok(Reply::Array(replies))
+}
+
+pub fn cmd_discard(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    if ctx.client.transaction.take().is_none() {
+        return Err(Error::new(ErrorKind::InvalidData, "DISCARD without MULTI"));
+    }
+
+    unwatch_all(ctx.client, ctx.db);
+
+    Ok(Reply::Simple("OK".to_string()))
+}
+
+pub fn cmd_watch(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    if ctx.client.transaction.is_some() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "WATCH inside MULTI is not allowed",
+        ));
+    }
+
+    for key in &ctx.args[1..] {
+        if ctx.client.watched.iter().any(|(watched, _)| watched == key) {
+            continue;
+        }
+
+        let version = ctx.db.watch(key);
+
+        ctx.client.watched.push((key.clone(), version));
+    }
+
+    Ok(Reply::Simple("OK".to_string()))
+}
+
+pub fn cmd_unwatch(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    unwatch_all(ctx.client, ctx.db);
+
+    Ok(Reply::Simple("OK".to_string()))
+}
+
+pub fn unwatch_all(client: &mut Client, db: &mut dyn MemoryDatabase) {
+    for (key, _) in client.watched.drain(..) {
+        db.unwatch(&key);
+    }
+}
@@ -0,0 +1,715 @@
+use crate::redis::{
+    cmd::{
+        args::{command_name, parse_float, parse_integer, syntax_error},
+        list::normalize_range,
+    },
+    command::CommandContext,
+    db::{wrong_type, MemoryDatabase},
+    reply::{Protocol, Reply},
+    types::{LexBound, ScoreBound, Value, ZSet},
+};
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind},
+    ops::Range,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum RangeKind {
+    Rank,
+    Score,
+    Lex,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Aggregate {
+    Sum,
+    Min,
+    Max,
+}
+
+#[derive(Default)]
+struct ZAddOptions {
+    nx: bool,
+    xx: bool,
+    gt: bool,
+    lt: bool,
+    ch: bool,
+    incr: bool,
+}
+
+struct RangeQuery {
+    kind: RangeKind,
+    reverse: bool,
+    limit: Option<(i64, i64)>,
+    with_scores: bool,
+}
+
+pub fn cmd_zadd(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let mut options = ZAddOptions::default();
+    let mut position = 2;
+
+    while let Some(option) = ctx.args.get(position) {
+        match option.to_ascii_lowercase().as_slice() {
+            b"nx" => options.nx = true,
+            b"xx" => options.xx = true,
+            b"gt" => options.gt = true,
+            b"lt" => options.lt = true,
+            b"ch" => options.ch = true,
+            b"incr" => options.incr = true,
+            _ => break,
+        }
+
+        position += 1;
+    }
+
+    let pairs = &ctx.args[position..];
+
+    if pairs.is_empty() || !pairs.len().is_multiple_of(2) {
+        return Err(syntax_error());
+    }
+
+    if options.nx && options.xx {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "XX and NX options at the same time are not compatible",
+        ));
+    }
+
+    if [options.gt, options.lt, options.nx]
+        .iter()
+        .filter(|set| **set)
+        .count()
+        > 1
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "GT, LT, and/or NX options at the same time are not compatible",
+        ));
+    }
+
+    if options.incr && pairs.len() > 2 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "INCR option supports a single increment-element pair",
+        ));
+    }
+
+    let pairs = pairs
+        .chunks(2)
+        .map(|pair| Ok((parse_float(&pair[0])?, &pair[1])))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    if options.incr && !options.nx {
+        let zset = ctx.db.get_zset(&ctx.args[1])?;
+
+        if pairs.iter().any(|(score, member)| {
+            zset.and_then(|zset| zset.score(member))
+                .is_some_and(|current| (current + score).is_nan())
+        }) {
+            return Err(nan_score());
+        }
+    }
+
+    let Some(zset) = ctx.db.get_zset_mut(&ctx.args[1], !options.xx)? else {
+        return match options.incr {
+            true => Ok(Reply::Null),
+            false => Ok(Reply::Integer(0)),
+        };
+    };
+
+    let (mut added, mut changed, mut result) = (0, 0, None);
+
+    for (score, member) in pairs {
+        let Some(current) = zset.score(member) else {
+            if !options.xx {
+                zset.insert(member, score);
+                added += 1;
+                result = Some(score);
+            }
+
+            continue;
+        };
+
+        if options.nx {
+            continue;
+        }
+
+        let score = match options.incr {
+            true => current + score,
+            false => score,
+        };
+
+        if (options.gt && score <= current) || (options.lt && score >= current) {
+            continue;
+        }
+
+        if score != current {
+            zset.insert(member, score);
+            changed += 1;
+        }
+
+        result = Some(score);
+    }
+
+    if added + changed > 0 {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    match (options.incr, options.ch) {
+        (true, _) => Ok(result.map_or(Reply::Null, Reply::Double)),
+        (false, true) => Ok(Reply::Integer(added + changed)),
+        (false, false) => Ok(Reply::Integer(added)),
+    }
+}
+
+pub fn cmd_zincrby(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let increment = parse_float(&ctx.args[2])?;
+    let score = ctx
+        .db
+        .get_zset(&ctx.args[1])?
+        .and_then(|zset| zset.score(&ctx.args[3]))
+        .unwrap_or(0.0)
+        + increment;
+
+    if score.is_nan() {
+        return Err(nan_score());
+    }
+
+    ctx.db
+        .get_zset_mut(&ctx.args[1], true)?
+        .expect("zset was just created")
+        .insert(&ctx.args[3], score);
+    ctx.db.touch(&ctx.args[1]);
+
+    Ok(Reply::Double(score))
+}
+
+pub fn cmd_zrem(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let Some(zset) = ctx.db.get_zset_mut(&ctx.args[1], false)? else {
+        return Ok(Reply::Integer(0));
+    };
+
+    let removed = ctx.args[2..]
+        .iter()
+        .filter(|member| zset.remove(member))
+        .count();
+
+    if removed > 0 {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    remove_if_empty(ctx.db, &ctx.args[1]);
+
+    Ok(Reply::Integer(removed as i64))
+}
+
+pub fn cmd_zcard(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let length = ctx.db.get_zset(&ctx.args[1])?.map_or(0, |zset| zset.len());
+
+    Ok(Reply::Integer(length as i64))
+}
+
+pub fn cmd_zscore(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let score = ctx
+        .db
+        .get_zset(&ctx.args[1])?
+        .and_then(|zset| zset.score(&ctx.args[2]));
+
+    Ok(score.map_or(Reply::Null, Reply::Double))
+}
+
+pub fn cmd_zmscore(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let zset = ctx.db.get_zset(&ctx.args[1])?;
+    let scores = ctx.args[2..]
+        .iter()
+        .map(|member| {
+            zset.and_then(|zset| zset.score(member))
+                .map_or(Reply::Null, Reply::Double)
+        })
+        .collect();
+
+    Ok(Reply::Array(scores))
+}
+
+pub fn cmd_zrank(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    rank(ctx, false)
+}
+
+pub fn cmd_zrevrank(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    rank(ctx, true)
+}
+
+pub fn cmd_zcount(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let min = parse_score_bound(&ctx.args[2])?;
+    let max = parse_score_bound(&ctx.args[3])?;
+
+    let count = ctx
+        .db
+        .get_zset(&ctx.args[1])?
+        .map_or(0, |zset| zset.score_range(&min, &max).len());
+
+    Ok(Reply::Integer(count as i64))
+}
+
+pub fn cmd_zlexcount(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let min = parse_lex_bound(&ctx.args[2])?;
+    let max = parse_lex_bound(&ctx.args[3])?;
+
+    let count = ctx
+        .db
+        .get_zset(&ctx.args[1])?
+        .map_or(0, |zset| zset.lex_range(&min, &max).len());
+
+    Ok(Reply::Integer(count as i64))
+}
+
+pub fn cmd_zrange(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    let mut query = RangeQuery {
+        kind: RangeKind::Rank,
+        reverse: false,
+        limit: None,
+        with_scores: false,
+    };
+    let mut options = ctx.args[4..].iter();
+
+    while let Some(option) = options.next() {
+        match option.to_ascii_lowercase().as_slice() {
+            b"byscore" if query.kind == RangeKind::Rank => query.kind = RangeKind::Score,
+            b"bylex" if query.kind == RangeKind::Rank => query.kind = RangeKind::Lex,
+            b"rev" => query.reverse = true,
+            b"withscores" => query.with_scores = true,
+            b"limit" => query.limit = Some(parse_limit(&mut options)?),
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    if query.limit.is_some() && query.kind == RangeKind::Rank {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX",
+        ));
+    }
+
+    if query.with_scores && query.kind == RangeKind::Lex {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            "syntax error, WITHSCORES not supported in combination with BYLEX",
+        ));
+    }
+
+    range(ctx, query)
+}
+
+pub fn cmd_zrevrange(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    legacy_range(ctx, RangeKind::Rank, true)
+}
+
+pub fn cmd_zrangebyscore(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    legacy_range(ctx, RangeKind::Score, false)
+}
+
+pub fn cmd_zrevrangebyscore(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    legacy_range(ctx, RangeKind::Score, true)
+}
+
+pub fn cmd_zrangebylex(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    legacy_range(ctx, RangeKind::Lex, false)
+}
+
+pub fn cmd_zrevrangebylex(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    legacy_range(ctx, RangeKind::Lex, true)
+}
+
+pub fn cmd_zpopmin(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    pop(ctx, false)
+}
+
+pub fn cmd_zpopmax(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    pop(ctx, true)
+}
+
+pub fn cmd_zunionstore(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    store(ctx, false)
+}
+
+pub fn cmd_zinterstore(ctx: &mut CommandContext) -> Result<Reply, Error> {
+    store(ctx, true)
+}
+
+fn rank(ctx: &mut CommandContext, reverse: bool) -> Result<Reply, Error> {
+    let with_score = match ctx.args.get(3) {
+        Some(option) if option.eq_ignore_ascii_case(b"withscore") => true,
+        Some(_) => return Err(syntax_error()),
+        None => false,
+    };
+
+    if ctx.args.len() > 4 {
+        return Err(syntax_error());
+    }
+
+    let member = &ctx.args[2];
+    let found = ctx
+        .db
+        .get_zset(&ctx.args[1])?
+        .and_then(|zset| zset.rank(member, reverse).zip(zset.score(member)));
+
+    match (found, with_score) {
+        (Some((rank, _)), false) => Ok(Reply::Integer(rank as i64)),
+        (Some((rank, score)), true) => Ok(Reply::Array(vec![
+            Reply::Integer(rank as i64),
+            Reply::Double(score),
+        ])),
+        (None, false) => Ok(Reply::Null),
+        (None, true) => Ok(Reply::NullArray),
+    }
+}
+
+fn legacy_range(ctx: &mut CommandContext, kind: RangeKind, reverse: bool) -> Result<Reply, Error> {
+    let mut query = RangeQuery {
+        kind,
+        reverse,
+        limit: None,
+        with_scores: false,
+    };
+    let mut options = ctx.args[4..].iter();
+
+    while let Some(option) = options.next() {
+        match option.to_ascii_lowercase().as_slice() {
+            b"withscores" if kind != RangeKind::Lex => query.with_scores = true,
+            b"limit" if kind != RangeKind::Rank => query.limit = Some(parse_limit(&mut options)?),
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    range(ctx, query)
+}
+
+fn range(ctx: &mut CommandContext, query: RangeQuery) -> Result<Reply, Error> {
+    let (start, stop) = match query.reverse && query.kind != RangeKind::Rank {
+        true => (&ctx.args[3], &ctx.args[2]),
+        false => (&ctx.args[2], &ctx.args[3]),
+    };
+
+    let zset = ctx.db.get_zset(&ctx.args[1])?;
+    let length = zset.map_or(0, |zset| zset.len());
+
+    let ranks = match query.kind {
+        RangeKind::Rank => {
+            let (start, stop) = (parse_integer(start)?, parse_integer(stop)?);
+
+            match normalize_range(start, stop, length) {
+                Some((start, stop)) if query.reverse => (length - 1 - stop)..(length - start),
+                Some((start, stop)) => start..stop + 1,
+                None => 0..0,
+            }
+        }
+        RangeKind::Score => {
+            let (min, max) = (parse_score_bound(start)?, parse_score_bound(stop)?);
+
+            zset.map_or(0..0, |zset| zset.score_range(&min, &max))
+        }
+        RangeKind::Lex => {
+            let (min, max) = (parse_lex_bound(start)?, parse_lex_bound(stop)?);
+
+            zset.map_or(0..0, |zset| zset.lex_range(&min, &max))
+        }
+    };
+
+    let entries = match zset {
+        Some(zset) => collect_range(zset, ranks, query.reverse, query.limit),
+        None => vec![],
+    };
+
+    Ok(entries_reply(
+        entries,
+        query.with_scores,
+        ctx.client.protocol,
+    ))
+}
+
+fn collect_range(
+    zset: &ZSet,
+    ranks: Range<usize>,
+    reverse: bool,
+    limit: Option<(i64, i64)>,
+) -> Vec<(Vec<u8>, f64)> {
+    let (offset, count) = limit.unwrap_or((0, -1));
+
+    if offset < 0 || offset as usize >= ranks.len() {
+        return vec![];
+    }
+
+    let available = ranks.len() - offset as usize;
+    let count = match count < 0 {
+        true => available,
+        false => available.min(count as usize),
+    };
+    let first = match reverse {
+        true => ranks.end - 1 - offset as usize,
+        false => ranks.start + offset as usize,
+    };
+
+    zset.iter_from(first, reverse)
+        .take(count)
+        .map(|(member, score)| (member.to_vec(), score))
+        .collect()
+}
+
+fn pop(ctx: &mut CommandContext, max: bool) -> Result<Reply, Error> {
+    let count = match ctx.args.get(2) {
+        Some(count) => match parse_integer(count)? {
+            count if count < 0 => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    "value is out of range, must be positive",
+                ))
+            }
+            count => Some(count as usize),
+        },
+        None => None,
+    };
+
+    if ctx.args.len() > 3 {
+        return Err(syntax_error());
+    }
+
+    let Some(zset) = ctx.db.get_zset_mut(&ctx.args[1], false)? else {
+        return Ok(Reply::Array(vec![]));
+    };
+
+    let popped: Vec<(Vec<u8>, f64)> = match max {
+        true => zset.iter_from(zset.len().saturating_sub(1), true),
+        false => zset.iter(),
+    }
+    .take(count.unwrap_or(1))
+    .map(|(member, score)| (member.to_vec(), score))
+    .collect();
+
+    for (member, _) in &popped {
+        zset.remove(member);
+    }
+
+    if !popped.is_empty() {
+        ctx.db.touch(&ctx.args[1]);
+    }
+
+    remove_if_empty(ctx.db, &ctx.args[1]);
+
+    let protocol = match count {
+        Some(_) => ctx.client.protocol,
+        None => Protocol::RESP2,
+    };
+
+    Ok(entries_reply(popped, true, protocol))
+}
+
+fn store(ctx: &mut CommandContext, intersect: bool) -> Result<Reply, Error> {
+    let numkeys = parse_integer(&ctx.args[2])?;
+
+    if numkeys < 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "at least 1 input key is needed for '{}' command",
+                command_name(ctx.args)
+            ),
+        ));
+    }
+
+    if numkeys as usize > ctx.args.len() - 3 {
+        return Err(syntax_error());
+    }
+
+    let keys = &ctx.args[3..3 + numkeys as usize];
+    let mut weights = vec![1.0; keys.len()];
+    let mut aggregate = Aggregate::Sum;
+    let mut options = ctx.args[3 + keys.len()..].iter();
+
+    while let Some(option) = options.next() {
+        match option.to_ascii_lowercase().as_slice() {
+            b"weights" => {
+                for weight in weights.iter_mut() {
+                    let arg = options.next().ok_or_else(syntax_error)?;
+
+                    *weight = parse_float(arg).map_err(|_| {
+                        Error::new(ErrorKind::InvalidData, "weight value is not a float")
+                    })?;
+                }
+            }
+            b"aggregate" => {
+                let arg = options.next().ok_or_else(syntax_error)?;
+
+                aggregate = match arg.to_ascii_lowercase().as_slice() {
+                    b"sum" => Aggregate::Sum,
+                    b"min" => Aggregate::Min,
+                    b"max" => Aggregate::Max,
+                    _ => return Err(syntax_error()),
+                };
+            }
+            _ => return Err(syntax_error()),
+        }
+    }
+
+    let mut sources = keys
+        .iter()
+        .zip(weights)
+        .map(|(key, weight)| load_weighted(ctx.db, key, weight))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    if intersect {
+        sources.sort_by_key(|source| source.len());
+    }
+
+    let mut result: HashMap<Vec<u8>, f64> = HashMap::new();
+
+    match intersect {
+        true => {
+            if let Some((first, others)) = sources.split_first() {
+                for (member, score) in first {
+                    let scores = others
+                        .iter()
+                        .map(|other| other.get(member).copied())
+                        .collect::<Option<Vec<_>>>();
+
+                    if let Some(scores) = scores {
+                        let score = scores
+                            .into_iter()
+                            .fold(*score, |current, score| combine(current, score, aggregate));
+
+                        result.insert(member.clone(), score);
+                    }
+                }
+            }
+        }
+        false => {
+            for (member, score) in sources.into_iter().flatten() {
+                let score = match result.get(&member) {
+                    Some(current) => combine(*current, score, aggregate),
+                    None => score,
+                };
+
+                result.insert(member, score);
+            }
+        }
+    }
+
+    let length = result.len();
+
+    match length {
+        0 => {
+            ctx.db.del(&ctx.args[1]);
+        }
+        _ => {
+            let mut zset = ZSet::new();
+
+            for (member, score) in result {
+                zset.insert(&member, score);
+            }
+
+            ctx.db.set(&ctx.args[1], Value::ZSet(zset), None)?;
+        }
+    }
+
+    Ok(Reply::Integer(length as i64))
+}
+
+fn load_weighted(
+    db: &mut dyn MemoryDatabase,
+    key: &[u8],
+    weight: f64,
+) -> Result<HashMap<Vec<u8>, f64>, Error> {
+    let weighted = |score: f64| match score * weight {
+        score if score.is_nan() => 0.0,
+        score => score,
+    };
+
+    match db.get(key) {
+        Some(Value::ZSet(zset)) => Ok(zset
+            .iter()
+            .map(|(member, score)| (member.to_vec(), weighted(score)))
+            .collect()),
+        Some(Value::Set(set)) => Ok(set
+            .members()
+            .into_iter()
+            .map(|member| (member, weighted(1.0)))
+            .collect()),
+        Some(_) => Err(wrong_type()),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn combine(current: f64, score: f64, aggregate: Aggregate) -> f64 {
+    match aggregate {
+        Aggregate::Sum => match current + score {
+            sum if sum.is_nan() => 0.0,
+            sum => sum,
+        },
+        Aggregate::Min => current.min(score),
+        Aggregate::Max => current.max(score),
+    }
+}
+
+fn entries_reply(entries: Vec<(Vec<u8>, f64)>, with_scores: bool, protocol: Protocol) -> Reply {
+    let entries = entries.into_iter();
+
+    let items = match (with_scores, protocol) {
+        (false, _) => entries.map(|(member, _)| Reply::Bulk(member)).collect(),
+        (true, Protocol::RESP2) => entries
+            .flat_map(|(member, score)| [Reply::Bulk(member), Reply::Double(score)])
+            .collect(),
+        (true, Protocol::RESP3) => entries
+            .map(|(member, score)| Reply::Array(vec![Reply::Bulk(member), Reply::Double(score)]))
+            .collect(),
+    };
+
+    Reply::Array(items)
+}
+
+fn parse_limit<'a>(options: &mut impl Iterator<Item = &'a Vec<u8>>) -> Result<(i64, i64), Error> {
+    let offset = parse_integer(options.next().ok_or_else(syntax_error)?)?;
+    let count = parse_integer(options.next().ok_or_else(syntax_error)?)?;
+
+    Ok((offset, count))
+}
+
+fn parse_score_bound(arg: &[u8]) -> Result<ScoreBound, Error> {
+    let invalid = || Error::new(ErrorKind::InvalidData, "min or max is not a float");
+
+    match arg.strip_prefix(b"(") {
+        Some(score) => Ok(ScoreBound::Exclusive(
+            parse_float(score).map_err(|_| invalid())?,
+        )),
+        None => Ok(ScoreBound::Inclusive(
+            parse_float(arg).map_err(|_| invalid())?,
+        )),
+    }
+}
+
+fn parse_lex_bound(arg: &[u8]) -> Result<LexBound, Error> {
+    match arg.split_first() {
+        Some((b'-', [])) => Ok(LexBound::Min),
+        Some((b'+', [])) => Ok(LexBound::Max),
+        Some((b'[', member)) => Ok(LexBound::Inclusive(member.to_vec())),
+        Some((b'(', member)) => Ok(LexBound::Exclusive(member.to_vec())),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            "min or max not valid string range item",
+        )),
+    }
+}
+
+fn nan_score() -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        "resulting score is not a number (NaN)",
+    )
+}
+
+fn remove_if_empty(db: &mut dyn MemoryDatabase, key: &[u8]) {
+    if let Ok(Some(zset)) = db.get_zset(key) {
+        if zset.is_empty() {
+            db.del(key);
+        }
+    }
+}
@@ -0,0 +1,888 @@
+use std::{
+    collections::HashMap,
+    io::{Error, ErrorKind},
+    sync::OnceLock,
+};
+
+use super::{
+    blocking::BlockRequest, client::Client, cmd::*, db::MemoryDatabase, persistence::Persistence,
+    pubsub::PubSubHub, reply::Reply,
+};
+
+use CommandFlag::*;
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum CommandFlag {
+    Write,
+    ReadOnly,
+    DenyOom,
+    Admin,
+    PubSub,
+    NoScript,
+    Blocking,
+    Loading,
+    Stale,
+    Fast,
+    MovableKeys,
+}
+
+impl CommandFlag {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Write => "write",
+            ReadOnly => "readonly",
+            DenyOom => "denyoom",
+            Admin => "admin",
+            PubSub => "pubsub",
+            NoScript => "noscript",
+            Blocking => "blocking",
+            Loading => "loading",
+            Stale => "stale",
+            Fast => "fast",
+            MovableKeys => "movablekeys",
+        }
+    }
+}
+
+pub struct CommandContext<'a> {
+    pub args: &'a [Vec<u8>],
+    pub db: &'a mut dyn MemoryDatabase,
+    pub client: &'a mut Client,
+    pub pubsub: &'a PubSubHub,
+    pub persistence: &'a Persistence,
+    pub ready_keys: Vec<Vec<u8>>,
+    pub block: Option<BlockRequest>,
+    pub propagate: Option<Vec<Vec<Vec<u8>>>>,
+}
+
+impl CommandContext<'_> {
+    pub fn propagated(&mut self, command: &CommandSpec) -> Vec<Vec<Vec<u8>>> {
+        match self.propagate.take() {
+            Some(commands) => commands,
+            None if command.has_flag(Write) => vec![self.args.to_vec()],
+            None => vec![],
+        }
+    }
+}
+
+pub type CommandHandler = fn(&mut CommandContext<'_>) -> Result<Reply, Error>;
+
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub arity: i64,
+    pub flags: &'static [CommandFlag],
+    pub first_key: i64,
+    pub last_key: i64,
+    pub step: i64,
+    pub keynum: Option<(usize, usize)>,
+    pub group: &'static str,
+    pub since: &'static str,
+    pub summary: &'static str,
+    pub handler: CommandHandler,
+}
+
+impl CommandSpec {
+    const fn new(
+        name: &'static str,
+        arity: i64,
+        flags: &'static [CommandFlag],
+        keys: (i64, i64, i64),
+        handler: CommandHandler,
+    ) -> Self {
+        Self {
+            name,
+            arity,
+            flags,
+            first_key: keys.0,
+            last_key: keys.1,
+            step: keys.2,
+            keynum: None,
+            group: "generic",
+            since: "1.0.0",
+            summary: "",
+            handler,
+        }
+    }
+
+    const fn docs(
+        mut self,
+        group: &'static str,
+        since: &'static str,
+        summary: &'static str,
+    ) -> Self {
+        self.group = group;
+        self.since = since;
+        self.summary = summary;
+        self
+    }
+
+    const fn keynum(mut self, index: usize, first: usize) -> Self {
+        self.keynum = Some((index, first));
+        self
+    }
+
+    pub fn has_flag(&self, flag: CommandFlag) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    pub fn check_arity(&self, argc: usize) -> Result<(), Error> {
+        let argc = argc as i64;
+
+        if (self.arity > 0 && argc != self.arity) || argc < -self.arity {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("wrong number of arguments for '{}' command", self.name),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn keys<'a>(&self, args: &'a [Vec<u8>]) -> Vec<&'a [u8]> {
+        if self.first_key <= 0 {
+            return vec![];
+        }
+
+        let last_key = match self.last_key {
+            last_key if last_key < 0 => args.len() as i64 + last_key,
+            last_key => last_key,
+        };
+
+        let mut keys: Vec<&[u8]> = (self.first_key..=last_key)
+            .step_by(self.step.max(1) as usize)
+            .filter_map(|position| args.get(position as usize))
+            .map(Vec::as_slice)
+            .collect();
+
+        if let Some((index, first)) = self.keynum {
+            let numkeys = args
+                .get(index)
+                .and_then(|numkeys| std::str::from_utf8(numkeys).ok())
+                .and_then(|numkeys| numkeys.parse::<usize>().ok())
+                .unwrap_or(0);
+
+            keys.extend(
+                args.iter()
+                    .skip(index + first)
+                    .take(numkeys)
+                    .map(Vec::as_slice),
+            );
+        }
+
+        keys
+    }
+
+    pub fn acl_categories(&self) -> Vec<String> {
+        let mut categories = vec![];
+
+        if self.has_flag(Write) {
+            categories.push(String::from("@write"));
+        }
+        if self.has_flag(ReadOnly) {
+            categories.push(String::from("@read"));
+        }
+        if self.group != "server" && self.group != "connection" && self.first_key > 0 {
+            categories.push(format!("@{}", self.group));
+        }
+        if self.has_flag(Admin) {
+            categories.push(String::from("@admin"));
+            categories.push(String::from("@dangerous"));
+        }
+        if self.has_flag(PubSub) {
+            categories.push(String::from("@pubsub"));
+        }
+        if self.has_flag(Blocking) {
+            categories.push(String::from("@blocking"));
+        }
+        if self.group == "connection" {
+            categories.push(String::from("@connection"));
+        }
+
+        categories.push(String::from(match self.has_flag(Fast) {
+            true => "@fast",
+            false => "@slow",
+        }));
+
+        categories
+    }
+}
+
+pub static COMMAND_TABLE: &[CommandSpec] = &[
+    CommandSpec::new("ping", -1, &[Fast], (0, 0, 0), cmd_ping).docs(
+        "connection",
+        "1.0.0",
+        "Returns the server's liveliness response.",
+    ),
+    CommandSpec::new("echo", 2, &[Fast], (0, 0, 0), cmd_echo).docs(
+        "connection",
+        "1.0.0",
+        "Returns the given string.",
+    ),
+    CommandSpec::new("hello", -1, &[NoScript, Loading, Stale, Fast], (0, 0, 0), cmd_hello).docs(
+        "connection",
+        "6.0.0",
+        "Handshakes with the Redis server.",
+    ),
+    CommandSpec::new("get", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_get).docs(
+        "string",
+        "1.0.0",
+        "Returns the string value of a key.",
+    ),
+    CommandSpec::new("set", -3, &[Write, DenyOom], (1, 1, 1), cmd_set).docs(
+        "string",
+        "1.0.0",
+        "Sets the string value of a key, ignoring its type. The key is created if it doesn't exist.",
+    ),
+    CommandSpec::new("del", -2, &[Write], (1, -1, 1), cmd_del).docs(
+        "generic",
+        "1.0.0",
+        "Deletes one or more keys.",
+    ),
+    CommandSpec::new("exists", -2, &[ReadOnly, Fast], (1, -1, 1), cmd_exists).docs(
+        "generic",
+        "1.0.0",
+        "Determines whether one or more keys exist.",
+    ),
+    CommandSpec::new("type", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_type).docs(
+        "generic",
+        "1.0.0",
+        "Determines the type of value stored at a key.",
+    ),
+    CommandSpec::new("expire", -3, &[Write, Fast], (1, 1, 1), cmd_expire).docs(
+        "generic",
+        "1.0.0",
+        "Sets the expiration time of a key in seconds.",
+    ),
+    CommandSpec::new("pexpire", -3, &[Write, Fast], (1, 1, 1), cmd_pexpire).docs(
+        "generic",
+        "2.6.0",
+        "Sets the expiration time of a key in milliseconds.",
+    ),
+    CommandSpec::new("expireat", -3, &[Write, Fast], (1, 1, 1), cmd_expireat).docs(
+        "generic",
+        "1.2.0",
+        "Sets the expiration time of a key to a Unix timestamp.",
+    ),
+    CommandSpec::new("pexpireat", -3, &[Write, Fast], (1, 1, 1), cmd_pexpireat).docs(
+        "generic",
+        "2.6.0",
+        "Sets the expiration time of a key to a Unix milliseconds timestamp.",
+    ),
+    CommandSpec::new("ttl", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_ttl).docs(
+        "generic",
+        "1.0.0",
+        "Returns the expiration time in seconds of a key.",
+    ),
+    CommandSpec::new("pttl", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_pttl).docs(
+        "generic",
+        "2.6.0",
+        "Returns the expiration time in milliseconds of a key.",
+    ),
+    CommandSpec::new("expiretime", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_expiretime).docs(
+        "generic",
+        "7.0.0",
+        "Returns the expiration time of a key as a Unix timestamp.",
+    ),
+    CommandSpec::new("pexpiretime", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_pexpiretime).docs(
+        "generic",
+        "7.0.0",
+        "Returns the expiration time of a key as a Unix milliseconds timestamp.",
+    ),
+    CommandSpec::new("persist", 2, &[Write, Fast], (1, 1, 1), cmd_persist).docs(
+        "generic",
+        "2.2.0",
+        "Removes the expiration time of a key.",
+    ),
+    CommandSpec::new("lpush", -3, &[Write, DenyOom, Fast], (1, 1, 1), cmd_lpush).docs(
+        "list",
+        "1.0.0",
+        "Prepends one or more elements to a list. Creates the key if it doesn't exist.",
+    ),
+    CommandSpec::new("rpush", -3, &[Write, DenyOom, Fast], (1, 1, 1), cmd_rpush).docs(
+        "list",
+        "1.0.0",
+        "Appends one or more elements to a list. Creates the key if it doesn't exist.",
+    ),
+    CommandSpec::new("lpushx", -3, &[Write, DenyOom, Fast], (1, 1, 1), cmd_lpushx).docs(
+        "list",
+        "2.2.0",
+        "Prepends one or more elements to a list only when the list exists.",
+    ),
+    CommandSpec::new("rpushx", -3, &[Write, DenyOom, Fast], (1, 1, 1), cmd_rpushx).docs(
+        "list",
+        "2.2.0",
+        "Appends an element to a list only when the list exists.",
+    ),
+    CommandSpec::new("lpop", -2, &[Write, Fast], (1, 1, 1), cmd_lpop).docs(
+        "list",
+        "1.0.0",
+        "Returns the first elements in a list after removing it. Deletes the list if the last element was popped.",
+    ),
+    CommandSpec::new("rpop", -2, &[Write, Fast], (1, 1, 1), cmd_rpop).docs(
+        "list",
+        "1.0.0",
+        "Returns and removes the last elements of a list. Deletes the list if the last element was popped.",
+    ),
+    CommandSpec::new("llen", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_llen).docs(
+        "list",
+        "1.0.0",
+        "Returns the length of a list.",
+    ),
+    CommandSpec::new("lrange", 4, &[ReadOnly], (1, 1, 1), cmd_lrange).docs(
+        "list",
+        "1.0.0",
+        "Returns a range of elements from a list.",
+    ),
+    CommandSpec::new("lindex", 3, &[ReadOnly], (1, 1, 1), cmd_lindex).docs(
+        "list",
+        "1.0.0",
+        "Returns an element from a list by its index.",
+    ),
+    CommandSpec::new("lset", 4, &[Write, DenyOom], (1, 1, 1), cmd_lset).docs(
+        "list",
+        "1.0.0",
+        "Sets the value of an element in a list by its index.",
+    ),
+    CommandSpec::new("lrem", 4, &[Write], (1, 1, 1), cmd_lrem).docs(
+        "list",
+        "1.0.0",
+        "Removes elements from a list. Deletes the list if the last element was removed.",
+    ),
+    CommandSpec::new("ltrim", 4, &[Write], (1, 1, 1), cmd_ltrim).docs(
+        "list",
+        "1.0.0",
+        "Removes elements from both ends a list. Deletes the list if all elements were trimmed.",
+    ),
+    CommandSpec::new("linsert", 5, &[Write, DenyOom], (1, 1, 1), cmd_linsert).docs(
+        "list",
+        "2.2.0",
+        "Inserts an element before or after another element in a list.",
+    ),
+    CommandSpec::new("lpos", -3, &[ReadOnly], (1, 1, 1), cmd_lpos).docs(
+        "list",
+        "6.0.6",
+        "Returns the index of matching elements in a list.",
+    ),
+    CommandSpec::new("lmove", 5, &[Write, DenyOom], (1, 2, 1), cmd_lmove).docs(
+        "list",
+        "6.2.0",
+        "Returns an element after popping it from one list and pushing it to another. Deletes the list if the last element was moved.",
+    ),
+    CommandSpec::new("rpoplpush", 3, &[Write, DenyOom], (1, 2, 1), cmd_rpoplpush).docs(
+        "list",
+        "1.2.0",
+        "Returns the last element of a list after removing and pushing it to another list. Deletes the list if the last element was popped.",
+    ),
+    CommandSpec::new("lmpop", -4, &[Write], (0, 0, 0), cmd_lmpop).docs(
+        "list",
+        "7.0.0",
+        "Returns multiple elements from a list after removing them. Deletes the list if the last element was popped.",
+    ),
+    CommandSpec::new("blpop", -3, &[Write, Blocking], (1, -2, 1), cmd_blpop).docs(
+        "list",
+        "2.0.0",
+        "Removes and returns the first element in a list. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+    ),
+    CommandSpec::new("brpop", -3, &[Write, Blocking], (1, -2, 1), cmd_brpop).docs(
+        "list",
+        "2.0.0",
+        "Removes and returns the last element in a list. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+    ),
+    CommandSpec::new("blmove", 6, &[Write, DenyOom, Blocking], (1, 2, 1), cmd_blmove).docs(
+        "list",
+        "6.2.0",
+        "Pops an element from a list, pushes it to another list and returns it. Blocks until an element is available otherwise. Deletes the list if the last element was moved.",
+    ),
+    CommandSpec::new("brpoplpush", 4, &[Write, DenyOom, Blocking], (1, 2, 1), cmd_brpoplpush).docs(
+        "list",
+        "2.2.0",
+        "Pops an element from a list, pushes it to another list and returns it. Block until an element is available otherwise. Deletes the list if the last element was popped.",
+    ),
+    CommandSpec::new("blmpop", -5, &[Write, Blocking], (0, 0, 0), cmd_blmpop).docs(
+        "list",
+        "7.0.0",
+        "Pops the first element from one of multiple lists. Blocks until an element is available otherwise. Deletes the list if the last element was popped.",
+    ),
+    CommandSpec::new("hset", -4, &[Write, DenyOom, Fast], (1, 1, 1), cmd_hset).docs(
+        "hash",
+        "2.0.0",
+        "Creates or modifies the value of a field in a hash.",
+    ),
+    CommandSpec::new("hmset", -4, &[Write, DenyOom, Fast], (1, 1, 1), cmd_hmset).docs(
+        "hash",
+        "2.0.0",
+        "Sets the values of multiple fields.",
+    ),
+    CommandSpec::new("hsetnx", 4, &[Write, DenyOom, Fast], (1, 1, 1), cmd_hsetnx).docs(
+        "hash",
+        "2.0.0",
+        "Sets the value of a field in a hash only when the field doesn't exist.",
+    ),
+    CommandSpec::new("hget", 3, &[ReadOnly, Fast], (1, 1, 1), cmd_hget).docs(
+        "hash",
+        "2.0.0",
+        "Returns the value of a field in a hash.",
+    ),
+    CommandSpec::new("hmget", -3, &[ReadOnly, Fast], (1, 1, 1), cmd_hmget).docs(
+        "hash",
+        "2.0.0",
+        "Returns the values of all fields in a hash.",
+    ),
+    CommandSpec::new("hdel", -3, &[Write, Fast], (1, 1, 1), cmd_hdel).docs(
+        "hash",
+        "2.0.0",
+        "Deletes one or more fields and their values from a hash. Deletes the hash if no fields remain.",
+    ),
+    CommandSpec::new("hgetall", 2, &[ReadOnly], (1, 1, 1), cmd_hgetall).docs(
+        "hash",
+        "2.0.0",
+        "Returns all fields and values in a hash.",
+    ),
+    CommandSpec::new("hkeys", 2, &[ReadOnly], (1, 1, 1), cmd_hkeys).docs(
+        "hash",
+        "2.0.0",
+        "Returns all fields in a hash.",
+    ),
+    CommandSpec::new("hvals", 2, &[ReadOnly], (1, 1, 1), cmd_hvals).docs(
+        "hash",
+        "2.0.0",
+        "Returns all values in a hash.",
+    ),
+    CommandSpec::new("hlen", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_hlen).docs(
+        "hash",
+        "2.0.0",
+        "Returns the number of fields in a hash.",
+    ),
+    CommandSpec::new("hexists", 3, &[ReadOnly, Fast], (1, 1, 1), cmd_hexists).docs(
+        "hash",
+        "2.0.0",
+        "Determines whether a field exists in a hash.",
+    ),
+    CommandSpec::new("hstrlen", 3, &[ReadOnly, Fast], (1, 1, 1), cmd_hstrlen).docs(
+        "hash",
+        "3.2.0",
+        "Returns the length of the value of a field.",
+    ),
+    CommandSpec::new("hincrby", 4, &[Write, DenyOom, Fast], (1, 1, 1), cmd_hincrby).docs(
+        "hash",
+        "2.0.0",
+        "Increments the integer value of a field in a hash by a number. Uses 0 as initial value if the field doesn't exist.",
+    ),
+    CommandSpec::new("hincrbyfloat", 4, &[Write, DenyOom, Fast], (1, 1, 1), cmd_hincrbyfloat).docs(
+        "hash",
+        "2.6.0",
+        "Increments the floating point value of a field by a number. Uses 0 as initial value if the field doesn't exist.",
+    ),
+    CommandSpec::new("hrandfield", -2, &[ReadOnly], (1, 1, 1), cmd_hrandfield).docs(
+        "hash",
+        "6.2.0",
+        "Returns one or more random fields from a hash.",
+    ),
+    CommandSpec::new("sadd", -3, &[Write, DenyOom, Fast], (1, 1, 1), cmd_sadd).docs(
+        "set",
+        "1.0.0",
+        "Adds one or more members to a set. Creates the key if it doesn't exist.",
+    ),
+    CommandSpec::new("srem", -3, &[Write, Fast], (1, 1, 1), cmd_srem).docs(
+        "set",
+        "1.0.0",
+        "Removes one or more members from a set. Deletes the set if the last member was removed.",
+    ),
+    CommandSpec::new("smove", 4, &[Write, Fast], (1, 2, 1), cmd_smove).docs(
+        "set",
+        "1.0.0",
+        "Moves a member from one set to another.",
+    ),
+    CommandSpec::new("smembers", 2, &[ReadOnly], (1, 1, 1), cmd_smembers).docs(
+        "set",
+        "1.0.0",
+        "Returns all members of a set.",
+    ),
+    CommandSpec::new("sismember", 3, &[ReadOnly, Fast], (1, 1, 1), cmd_sismember).docs(
+        "set",
+        "1.0.0",
+        "Determines whether a member belongs to a set.",
+    ),
+    CommandSpec::new("smismember", -3, &[ReadOnly, Fast], (1, 1, 1), cmd_smismember).docs(
+        "set",
+        "6.2.0",
+        "Determines whether multiple members belong to a set.",
+    ),
+    CommandSpec::new("scard", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_scard).docs(
+        "set",
+        "1.0.0",
+        "Returns the number of members in a set.",
+    ),
+    CommandSpec::new("spop", -2, &[Write, Fast], (1, 1, 1), cmd_spop).docs(
+        "set",
+        "1.0.0",
+        "Returns one or more random members from a set after removing them. Deletes the set if the last member was popped.",
+    ),
+    CommandSpec::new("srandmember", -2, &[ReadOnly], (1, 1, 1), cmd_srandmember).docs(
+        "set",
+        "1.0.0",
+        "Get one or multiple random members from a set",
+    ),
+    CommandSpec::new("sinter", -2, &[ReadOnly], (1, -1, 1), cmd_sinter).docs(
+        "set",
+        "1.0.0",
+        "Returns the intersect of multiple sets.",
+    ),
+    CommandSpec::new("sinterstore", -3, &[Write, DenyOom], (1, -1, 1), cmd_sinterstore).docs(
+        "set",
+        "1.0.0",
+        "Stores the intersect of multiple sets in a key.",
+    ),
+    CommandSpec::new("sintercard", -3, &[ReadOnly], (0, 0, 0), cmd_sintercard).docs(
+        "set",
+        "7.0.0",
+        "Returns the number of members of the intersect of multiple sets.",
+    ),
+    CommandSpec::new("sunion", -2, &[ReadOnly], (1, -1, 1), cmd_sunion).docs(
+        "set",
+        "1.0.0",
+        "Returns the union of multiple sets.",
+    ),
+    CommandSpec::new("sunionstore", -3, &[Write, DenyOom], (1, -1, 1), cmd_sunionstore).docs(
+        "set",
+        "1.0.0",
+        "Stores the union of multiple sets in a key.",
+    ),
+    CommandSpec::new("sdiff", -2, &[ReadOnly], (1, -1, 1), cmd_sdiff).docs(
+        "set",
+        "1.0.0",
+        "Returns the difference of multiple sets.",
+    ),
+    CommandSpec::new("sdiffstore", -3, &[Write, DenyOom], (1, -1, 1), cmd_sdiffstore).docs(
+        "set",
+        "1.0.0",
+        "Stores the difference of multiple sets in a key.",
+    ),
+    CommandSpec::new("zadd", -4, &[Write, DenyOom, Fast], (1, 1, 1), cmd_zadd).docs(
+        "sorted_set",
+        "1.2.0",
+        "Adds one or more members to a sorted set, or updates their scores. Creates the key if it doesn't exist.",
+    ),
+    CommandSpec::new("zincrby", 4, &[Write, DenyOom, Fast], (1, 1, 1), cmd_zincrby).docs(
+        "sorted_set",
+        "1.2.0",
+        "Increments the score of a member in a sorted set.",
+    ),
+    CommandSpec::new("zrem", -3, &[Write, Fast], (1, 1, 1), cmd_zrem).docs(
+        "sorted_set",
+        "1.2.0",
+        "Removes one or more members from a sorted set. Deletes the sorted set if all members were removed.",
+    ),
+    CommandSpec::new("zcard", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_zcard).docs(
+        "sorted_set",
+        "1.2.0",
+        "Returns the number of members in a sorted set.",
+    ),
+    CommandSpec::new("zscore", 3, &[ReadOnly, Fast], (1, 1, 1), cmd_zscore).docs(
+        "sorted_set",
+        "1.2.0",
+        "Returns the score of a member in a sorted set.",
+    ),
+    CommandSpec::new("zmscore", -3, &[ReadOnly, Fast], (1, 1, 1), cmd_zmscore).docs(
+        "sorted_set",
+        "6.2.0",
+        "Returns the score of one or more members in a sorted set.",
+    ),
+    CommandSpec::new("zrank", -3, &[ReadOnly, Fast], (1, 1, 1), cmd_zrank).docs(
+        "sorted_set",
+        "2.0.0",
+        "Returns the index of a member in a sorted set ordered by ascending scores.",
+    ),
+    CommandSpec::new("zrevrank", -3, &[ReadOnly, Fast], (1, 1, 1), cmd_zrevrank).docs(
+        "sorted_set",
+        "2.0.0",
+        "Returns the index of a member in a sorted set ordered by descending scores.",
+    ),
+    CommandSpec::new("zcount", 4, &[ReadOnly, Fast], (1, 1, 1), cmd_zcount).docs(
+        "sorted_set",
+        "2.0.0",
+        "Returns the count of members in a sorted set that have scores within a range.",
+    ),
+    CommandSpec::new("zlexcount", 4, &[ReadOnly, Fast], (1, 1, 1), cmd_zlexcount).docs(
+        "sorted_set",
+        "2.8.9",
+        "Returns the number of members in a sorted set within a lexicographical range.",
+    ),
+    CommandSpec::new("zrange", -4, &[ReadOnly], (1, 1, 1), cmd_zrange).docs(
+        "sorted_set",
+        "1.2.0",
+        "Returns members in a sorted set within a range of indexes.",
+    ),
+    CommandSpec::new("zrevrange", -4, &[ReadOnly], (1, 1, 1), cmd_zrevrange).docs(
+        "sorted_set",
+        "1.2.0",
+        "Returns members in a sorted set within a range of indexes in reverse order.",
+    ),
+    CommandSpec::new("zrangebyscore", -4, &[ReadOnly], (1, 1, 1), cmd_zrangebyscore).docs(
+        "sorted_set",
+        "1.0.5",
+        "Returns members in a sorted set within a range of scores.",
+    ),
+    CommandSpec::new("zrevrangebyscore", -4, &[ReadOnly], (1, 1, 1), cmd_zrevrangebyscore).docs(
+        "sorted_set",
+        "2.2.0",
+        "Returns members in a sorted set within a range of scores in reverse order.",
+    ),
+    CommandSpec::new("zrangebylex", -4, &[ReadOnly], (1, 1, 1), cmd_zrangebylex).docs(
+        "sorted_set",
+        "2.8.9",
+        "Returns members in a sorted set within a lexicographical range.",
+    ),
+    CommandSpec::new("zrevrangebylex", -4, &[ReadOnly], (1, 1, 1), cmd_zrevrangebylex).docs(
+        "sorted_set",
+        "2.8.9",
+        "Returns members in a sorted set within a lexicographical range in reverse order.",
+    ),
+    CommandSpec::new("zpopmin", -2, &[Write, Fast], (1, 1, 1), cmd_zpopmin).docs(
+        "sorted_set",
+        "5.0.0",
+        "Returns the lowest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.",
+    ),
+    CommandSpec::new("zpopmax", -2, &[Write, Fast], (1, 1, 1), cmd_zpopmax).docs(
+        "sorted_set",
+        "5.0.0",
+        "Returns the highest-scoring members from a sorted set after removing them. Deletes the sorted set if the last member was popped.",
+    ),
+    CommandSpec::new(
+        "zunionstore",
+        -4,
+        &[Write, DenyOom, MovableKeys],
+        (1, 1, 1),
+        cmd_zunionstore,
+    )
+    .keynum(2, 1)
+    .docs(
+        "sorted_set",
+        "2.0.0",
+        "Stores the union of multiple sorted sets in a key.",
+    ),
+    CommandSpec::new(
+        "zinterstore",
+        -4,
+        &[Write, DenyOom, MovableKeys],
+        (1, 1, 1),
+        cmd_zinterstore,
+    )
+    .keynum(2, 1)
+    .docs(
+        "sorted_set",
+        "2.0.0",
+        "Stores the intersect of multiple sorted sets in a key.",
+    ),
+    CommandSpec::new("xadd", -5, &[Write, DenyOom, Fast], (1, 1, 1), cmd_xadd).docs(
+        "stream",
+        "5.0.0",
+        "Appends a new message to a stream. Creates the key if it doesn't exist.",
+    ),
+    CommandSpec::new("xrange", -4, &[ReadOnly], (1, 1, 1), cmd_xrange).docs(
+        "stream",
+        "5.0.0",
+        "Returns the messages from a stream within a range of IDs.",
+    ),
+    CommandSpec::new("xrevrange", -4, &[ReadOnly], (1, 1, 1), cmd_xrevrange).docs(
+        "stream",
+        "5.0.0",
+        "Returns the messages from a stream within a range of IDs in reverse order.",
+    ),
+    CommandSpec::new("xlen", 2, &[ReadOnly, Fast], (1, 1, 1), cmd_xlen).docs(
+        "stream",
+        "5.0.0",
+        "Return the number of messages in a stream.",
+    ),
+    CommandSpec::new("xread", -4, &[ReadOnly, Blocking], (0, 0, 0), cmd_xread).docs(
+        "stream",
+        "5.0.0",
+        "Returns messages from multiple streams with IDs greater than the ones requested. Blocks until a message is available otherwise.",
+    ),
+    CommandSpec::new("xgroup", -2, &[Write, DenyOom], (2, 2, 1), cmd_xgroup).docs(
+        "stream",
+        "5.0.0",
+        "Creates, destroys and manages consumer groups and their consumers.",
+    ),
+    CommandSpec::new("xreadgroup", -7, &[Write, Blocking], (0, 0, 0), cmd_xreadgroup).docs(
+        "stream",
+        "5.0.0",
+        "Returns new or historical messages from a stream for a consumer in a group. Blocks until a message is available otherwise.",
+    ),
+    CommandSpec::new("xack", -4, &[Write, Fast], (1, 1, 1), cmd_xack).docs(
+        "stream",
+        "5.0.0",
+        "Returns the number of messages that were successfully acknowledged by the consumer group member of a stream.",
+    ),
+    CommandSpec::new("xpending", -3, &[ReadOnly], (1, 1, 1), cmd_xpending).docs(
+        "stream",
+        "5.0.0",
+        "Returns the information and entries from a stream consumer group's pending entries list.",
+    ),
+    CommandSpec::new("xclaim", -6, &[Write, Fast], (1, 1, 1), cmd_xclaim).docs(
+        "stream",
+        "5.0.0",
+        "Changes, or acquires, ownership of a message in a consumer group, as if the message was delivered a consumer group member.",
+    ),
+    CommandSpec::new("xautoclaim", -6, &[Write, Fast], (1, 1, 1), cmd_xautoclaim).docs(
+        "stream",
+        "6.2.0",
+        "Changes, or acquires, ownership of messages in a consumer group, as if the messages were delivered to as consumer group member.",
+    ),
+    CommandSpec::new("xinfo", -3, &[ReadOnly], (2, 2, 1), cmd_xinfo).docs(
+        "stream",
+        "5.0.0",
+        "Returns information about a stream, its consumer groups or the consumers of a group.",
+    ),
+    CommandSpec::new("xtrim", -4, &[Write], (1, 1, 1), cmd_xtrim).docs(
+        "stream",
+        "5.0.0",
+        "Deletes messages from the beginning of a stream.",
+    ),
+    CommandSpec::new("xdel", -3, &[Write, Fast], (1, 1, 1), cmd_xdel).docs(
+        "stream",
+        "5.0.0",
+        "Returns the number of messages after removing them from a stream.",
+    ),
+    CommandSpec::new("xsetid", -3, &[Write, DenyOom, Fast], (1, 1, 1), cmd_xsetid).docs(
+        "stream",
+        "5.0.0",
+        "An internal command for replicating stream values.",
+    ),
+    CommandSpec::new("subscribe", -2, &[PubSub, NoScript, Loading, Stale], (0, 0, 0), cmd_subscribe).docs(
+        "pubsub",
+        "2.0.0",
+        "Listens for messages published to channels.",
+    ),
+    CommandSpec::new("unsubscribe", -1, &[PubSub, NoScript, Loading, Stale], (0, 0, 0), cmd_unsubscribe).docs(
+        "pubsub",
+        "2.0.0",
+        "Stops listening to messages posted to channels.",
+    ),
+    CommandSpec::new("psubscribe", -2, &[PubSub, NoScript, Loading, Stale], (0, 0, 0), cmd_psubscribe).docs(
+        "pubsub",
+        "2.0.0",
+        "Listens for messages published to channels that match one or more patterns.",
+    ),
+    CommandSpec::new("punsubscribe", -1, &[PubSub, NoScript, Loading, Stale], (0, 0, 0), cmd_punsubscribe).docs(
+        "pubsub",
+        "2.0.0",
+        "Stops listening to messages published to channels that match one or more patterns.",
+    ),
+    CommandSpec::new("publish", 3, &[PubSub, Loading, Stale, Fast], (0, 0, 0), cmd_publish).docs(
+        "pubsub",
+        "2.0.0",
+        "Posts a message to a channel.",
+    ),
+    CommandSpec::new("ssubscribe", -2, &[PubSub, NoScript, Loading, Stale], (0, 0, 0), cmd_ssubscribe).docs(
+        "pubsub",
+        "7.0.0",
+        "Listens for messages published to shard channels.",
+    ),
+    CommandSpec::new("sunsubscribe", -1, &[PubSub, NoScript, Loading, Stale], (0, 0, 0), cmd_sunsubscribe).docs(
+        "pubsub",
+        "7.0.0",
+        "Stops listening to messages posted to shard channels.",
+    ),
+    CommandSpec::new("spublish", 3, &[PubSub, Loading, Stale, Fast], (0, 0, 0), cmd_spublish).docs(
+        "pubsub",
+        "7.0.0",
+        "Post a message to a shard channel",
+    ),
+    CommandSpec::new("pubsub", -2, &[PubSub, Loading, Stale], (0, 0, 0), cmd_pubsub).docs(
+        "pubsub",
+        "2.8.0",
+        "Inspects the state of the Pub/Sub subsystem.",
+    ),
+    CommandSpec::new("multi", 1, &[NoScript, Loading, Stale, Fast], (0, 0, 0), cmd_multi).docs(
+        "transactions",
+        "1.2.0",
+        "Starts a transaction.",
+    ),
+    CommandSpec::new("exec", 1, &[NoScript, Loading, Stale], (0, 0, 0), cmd_exec).docs(
+        "transactions",
+        "1.2.0",
+        "Executes all commands in a transaction.",
+    ),
+    CommandSpec::new("discard", 1, &[NoScript, Loading, Stale, Fast], (0, 0, 0), cmd_discard).docs(
+        "transactions",
+        "2.0.0",
+        "Discards a transaction.",
+    ),
+    CommandSpec::new("watch", -2, &[NoScript, Loading, Stale, Fast], (1, -1, 1), cmd_watch).docs(
+        "transactions",
+        "2.2.0",
+        "Monitors changes to keys to determine the execution of a transaction.",
+    ),
+    CommandSpec::new("unwatch", 1, &[NoScript, Loading, Stale, Fast], (0, 0, 0), cmd_unwatch).docs(
+        "transactions",
+        "2.2.0",
+        "Forgets about watched keys of a transaction.",
+    ),
+    CommandSpec::new("flushdb", -1, &[Write], (0, 0, 0), cmd_flushdb).docs(
+        "server",
+        "1.0.0",
+        "Removes all keys from the current database.",
+    ),
+    CommandSpec::new("save", 1, &[Admin, NoScript], (0, 0, 0), cmd_save).docs(
+        "server",
+        "1.0.0",
+        "Synchronously saves the database(s) to disk.",
+    ),
+    CommandSpec::new("bgsave", -1, &[Admin, NoScript], (0, 0, 0), cmd_bgsave).docs(
+        "server",
+        "1.0.0",
+        "Asynchronously saves the database(s) to disk.",
+    ),
+    CommandSpec::new("lastsave", 1, &[Loading, Stale, Fast], (0, 0, 0), cmd_lastsave).docs(
+        "server",
+        "1.0.0",
+        "Returns the Unix timestamp of the last successful save to disk.",
+    ),
+    CommandSpec::new("bgrewriteaof", 1, &[Admin, NoScript], (0, 0, 0), cmd_bgrewriteaof).docs(
+        "server",
+        "1.0.0",
+        "Asynchronously rewrites the append-only file to disk.",
+    ),
+    CommandSpec::new("info", -1, &[Loading, Stale], (0, 0, 0), cmd_info).docs(
+        "server",
+        "1.0.0",
+        "Returns information and statistics about the server.",
+    ),
+    CommandSpec::new("command", -1, &[Loading, Stale], (0, 0, 0), cmd_command).docs(
+        "server",
+        "2.8.13",
+        "Returns detailed information about all commands.",
+    ),
+];
+
+pub fn lookup_command(name: &[u8]) -> Option<&'static CommandSpec> {
+    static INDEX: OnceLock<HashMap<&'static [u8], &'static CommandSpec>> = OnceLock::new();
+
+    INDEX
+        .get_or_init(|| {
+            COMMAND_TABLE
+                .iter()
+                .map(|command| (command.name.as_bytes(), command))
+                .collect()
+        })
+        .get(name.to_ascii_lowercase().as_slice())
+        .copied()
+}
+
+pub fn unknown_command(args: &[Vec<u8>]) -> Error {
+    let beginning = args
+        .iter()
+        .skip(1)
+        .map(|arg| format!("'{}' ", String::from_utf8_lossy(arg)))
+        .collect::<String>();
+
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "unknown command '{}', with args beginning with: {}",
+            String::from_utf8_lossy(args.first().map(Vec::as_slice).unwrap_or_default()),
+            beginning
+        ),
+    )
+}
@@ -0,0 +1,128 @@
+use std::{
+    io::{Error, ErrorKind},
+    path::PathBuf,
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Config {
+    pub dir: String,
+    pub dbfilename: String,
+    pub save: Vec<SaveRule>,
+    pub appendonly: bool,
+    pub appendfilename: String,
+    pub appendfsync: AppendFsync,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            dir: ".".to_string(),
+            dbfilename: "dump.rdb".to_string(),
+            save: parse_save_rules("3600 1 300 100 60 10000").expect("default save rules"),
+            appendonly: false,
+            appendfilename: "appendonly.aof".to_string(),
+            appendfsync: AppendFsync::EverySec,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let mut config = Config::default();
+        let mut args = args.into_iter();
+
+        while let Some(flag) = args.next() {
+            let value = args.next().ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("missing value for '{}'", flag),
+                )
+            })?;
+
+            match flag.as_str() {
+                "--dir" => config.dir = value,
+                "--dbfilename" => config.dbfilename = value,
+                "--save" => config.save = parse_save_rules(&value)?,
+                "--appendonly" => {
+                    config.appendonly = match value.to_ascii_lowercase().as_str() {
+                        "yes" => true,
+                        "no" => false,
+                        _ => return Err(invalid_argument(&flag, &value)),
+                    }
+                }
+                "--appendfilename" => config.appendfilename = value,
+                "--appendfsync" => {
+                    config.appendfsync =
+                        AppendFsync::parse(&value).ok_or_else(|| invalid_argument(&flag, &value))?
+                }
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("unknown option '{}'", flag),
+                    ))
+                }
+            }
+        }
+
+        Ok(config)
+    }
+
+    pub fn rdb_path(&self) -> PathBuf {
+        PathBuf::from(&self.dir).join(&self.dbfilename)
+    }
+
+    pub fn aof_path(&self) -> PathBuf {
+        PathBuf::from(&self.dir).join(&self.appendfilename)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AppendFsync {
+    Always,
+    EverySec,
+    No,
+}
+
+impl AppendFsync {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "always" => Some(AppendFsync::Always),
+            "everysec" => Some(AppendFsync::EverySec),
+            "no" => Some(AppendFsync::No),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SaveRule {
+    pub seconds: u64,
+    pub changes: u64,
+}
+
+pub fn parse_save_rules(value: &str) -> Result<Vec<SaveRule>, Error> {
+    let invalid = || Error::new(ErrorKind::InvalidInput, "Invalid save parameters");
+    let numbers = value
+        .split_whitespace()
+        .map(|number| number.parse::<u64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if !numbers.len().is_multiple_of(2) {
+        return Err(invalid());
+    }
+
+    Ok(numbers
+        .chunks(2)
+        .map(|rule| SaveRule {
+            seconds: rule[0],
+            changes: rule[1],
+        })
+        .collect())
+}
+
+fn invalid_argument(flag: &str, value: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidInput,
+        format!("invalid value '{}' for '{}'", value, flag),
+    )
+}
@@ -1,7 +1,201 @@
-use std::io::Error;
+use std::{
+    io::{Error, ErrorKind},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::{
+    expire::ExpireStats,
+    types::{Hash, List, Set, Stream, Value, ZSet},
+};
+
+pub type Snapshot = Vec<(Vec<u8>, Arc<Value>, Option<u64>)>;
 
 pub trait MemoryDatabase: Sync + Send {
-    fn set(&mut self, key: &str, value: &str) -> Result<(), Error>;
-    fn get(&self, key: &str) -> Option<String>;
-    fn del(&mut self, key: &str) -> Option<String>;
+    fn set(&mut self, key: &[u8], value: Value, expires_at: Option<u64>) -> Result<(), Error>;
+    fn get(&mut self, key: &[u8]) -> Option<&Value>;
+    fn get_mut(&mut self, key: &[u8]) -> Option<&mut Value>;
+    fn touch(&mut self, key: &[u8]);
+    fn del(&mut self, key: &[u8]) -> Option<Value>;
+    fn exists(&mut self, key: &[u8]) -> bool;
+    fn get_expiry(&mut self, key: &[u8]) -> Option<u64>;
+    fn set_expiry(&mut self, key: &[u8], expires_at: Option<u64>) -> bool;
+    fn len(&self) -> usize;
+    fn expires_len(&self) -> usize;
+    fn sample_expired(&mut self, count: usize) -> (usize, usize);
+    fn expire_stats(&mut self) -> &mut ExpireStats;
+    fn random(&mut self) -> u64;
+    fn flush(&mut self);
+    fn watch(&mut self, key: &[u8]) -> u64;
+    fn unwatch(&mut self, key: &[u8]);
+    fn version(&mut self, key: &[u8]) -> u64;
+    fn dirty(&self) -> u64;
+    fn snapshot(&self) -> Snapshot;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn get_string(&mut self, key: &[u8]) -> Result<Option<&Vec<u8>>, Error> {
+        match self.get(key) {
+            Some(Value::String(string)) => Ok(Some(string)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_list(&mut self, key: &[u8]) -> Result<Option<&List>, Error> {
+        match self.get(key) {
+            Some(Value::List(list)) => Ok(Some(list)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_list_mut(&mut self, key: &[u8], create: bool) -> Result<Option<&mut List>, Error> {
+        self.get_list(key)?;
+
+        if create && !self.exists(key) {
+            self.set(key, Value::List(List::new()), None)?;
+        }
+
+        match self.get_mut(key) {
+            Some(Value::List(list)) => Ok(Some(list)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_hash(&mut self, key: &[u8]) -> Result<Option<&Hash>, Error> {
+        match self.get(key) {
+            Some(Value::Hash(hash)) => Ok(Some(hash)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_hash_mut(&mut self, key: &[u8], create: bool) -> Result<Option<&mut Hash>, Error> {
+        self.get_hash(key)?;
+
+        if create && !self.exists(key) {
+            self.set(key, Value::Hash(Hash::new()), None)?;
+        }
+
+        match self.get_mut(key) {
+            Some(Value::Hash(hash)) => Ok(Some(hash)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_set(&mut self, key: &[u8]) -> Result<Option<&Set>, Error> {
+        match self.get(key) {
+            Some(Value::Set(set)) => Ok(Some(set)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_set_mut(&mut self, key: &[u8], create: bool) -> Result<Option<&mut Set>, Error> {
+        self.get_set(key)?;
+
+        if create && !self.exists(key) {
+            self.set(key, Value::Set(Set::new()), None)?;
+        }
+
+        match self.get_mut(key) {
+            Some(Value::Set(set)) => Ok(Some(set)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_zset(&mut self, key: &[u8]) -> Result<Option<&ZSet>, Error> {
+        match self.get(key) {
+            Some(Value::ZSet(zset)) => Ok(Some(zset)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_zset_mut(&mut self, key: &[u8], create: bool) -> Result<Option<&mut ZSet>, Error> {
+        self.get_zset(key)?;
+
+        if create && !self.exists(key) {
+            self.set(key, Value::ZSet(ZSet::new()), None)?;
+        }
+
+        match self.get_mut(key) {
+            Some(Value::ZSet(zset)) => Ok(Some(zset)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_stream(&mut self, key: &[u8]) -> Result<Option<&Stream>, Error> {
+        match self.get(key) {
+            Some(Value::Stream(stream)) => Ok(Some(stream)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+
+    fn get_stream_mut(&mut self, key: &[u8], create: bool) -> Result<Option<&mut Stream>, Error> {
+        self.get_stream(key)?;
+
+        if create && !self.exists(key) {
+            self.set(key, Value::Stream(Stream::new()), None)?;
+        }
+
+        match self.get_mut(key) {
+            Some(Value::Stream(stream)) => Ok(Some(stream)),
+            Some(_) => Err(wrong_type()),
+            None => Ok(None),
+        }
+    }
+}
+
+pub fn wrong_type() -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        "-WRONGTYPE Operation against a key holding the wrong kind of value",
+    )
+}
+
+pub fn pick_random<T: Clone>(
+    db: &mut dyn MemoryDatabase,
+    mut items: Vec<T>,
+    count: usize,
+    allow_duplicates: bool,
+) -> Vec<T> {
+    if items.is_empty() {
+        return vec![];
+    }
+
+    if allow_duplicates {
+        let mut picked = Vec::with_capacity(count.min(items.len()));
+
+        for _ in 0..count {
+            picked.push(items[db.random() as usize % items.len()].clone());
+        }
+
+        return picked;
+    }
+
+    let count = count.min(items.len());
+
+    for i in 0..count {
+        let j = i + db.random() as usize % (items.len() - i);
+        items.swap(i, j);
+    }
+
+    items.truncate(count);
+    items
+}
+
+pub fn unix_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
 }
@@ -0,0 +1,79 @@
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{sync::Mutex, task::JoinHandle};
+
+use super::db::MemoryDatabase;
+
+pub const ACTIVE_EXPIRE_CYCLE_HZ: u64 = 10;
+pub const ACTIVE_EXPIRE_CYCLE_KEYS_PER_LOOP: usize = 20;
+pub const ACTIVE_EXPIRE_CYCLE_ACCEPTABLE_STALE: usize = 25;
+pub const ACTIVE_EXPIRE_CYCLE_TIME_PERCENT: u64 = 25;
+
+#[derive(Default, Clone, Debug)]
+pub struct ExpireStats {
+    pub expired_keys: u64,
+    pub expired_stale_perc: f64,
+    pub expired_time_cap_reached_count: u64,
+    pub expire_cycle_cpu_milliseconds: u64,
+    pub active_expire_cycles: u64,
+    expire_cycle_cpu_microseconds: u64,
+}
+
+pub fn spawn_active_expire(db: Arc<Mutex<impl MemoryDatabase + 'static>>) -> JoinHandle<()> {
+    let period = Duration::from_millis(1000 / ACTIVE_EXPIRE_CYCLE_HZ);
+    let time_limit = period * ACTIVE_EXPIRE_CYCLE_TIME_PERCENT as u32 / 100;
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(period);
+
+        loop {
+            interval.tick().await;
+            active_expire_cycle(&db, time_limit).await;
+        }
+    })
+}
+
+pub async fn active_expire_cycle(db: &Arc<Mutex<impl MemoryDatabase>>, time_limit: Duration) {
+    let start = Instant::now();
+    let (mut total_sampled, mut total_expired) = (0, 0);
+    let mut time_cap_reached = false;
+
+    loop {
+        let (sampled, expired) = db
+            .lock()
+            .await
+            .sample_expired(ACTIVE_EXPIRE_CYCLE_KEYS_PER_LOOP);
+
+        total_sampled += sampled;
+        total_expired += expired;
+
+        if sampled == 0 || expired * 100 <= sampled * ACTIVE_EXPIRE_CYCLE_ACCEPTABLE_STALE {
+            break;
+        }
+
+        if start.elapsed() > time_limit {
+            time_cap_reached = true;
+            break;
+        }
+
+        tokio::task::yield_now().await;
+    }
+
+    let mut db = db.lock().await;
+    let stats = db.expire_stats();
+    let current_perc = match total_sampled {
+        0 => 0.0,
+        _ => total_expired as f64 / total_sampled as f64,
+    };
+
+    stats.active_expire_cycles += 1;
+    stats.expire_cycle_cpu_microseconds += start.elapsed().as_micros() as u64;
+    stats.expire_cycle_cpu_milliseconds = stats.expire_cycle_cpu_microseconds / 1000;
+    stats.expired_stale_perc = current_perc * 0.05 + stats.expired_stale_perc * 0.95;
+
+    if time_cap_reached {
+        stats.expired_time_cap_reached_count += 1;
+    }
+}
@@ -1,21 +1,127 @@
+pub mod aof;
+pub mod blocking;
+pub mod client;
+pub mod command;
+pub mod config;
 pub mod db;
+pub mod expire;
+pub mod persistence;
+pub mod pubsub;
+pub mod reply;
 pub mod server;
+#[cfg(test)]
+mod tests;
 pub mod cmd {
+    pub mod args;
+    pub mod command;
     pub mod echo;
+    pub mod expire;
     pub mod get;
+    pub mod hash;
+    pub mod hello;
+    pub mod info;
+    pub mod keys;
+    pub mod list;
+    pub mod ping;
+    pub mod pubsub;
+    pub mod save;
     pub mod set;
+    pub mod sets;
+    pub mod stream;
+    pub mod transaction;
+    pub mod zset;
 
+    pub use command::cmd_command;
     pub use echo::cmd_echo;
+    pub use expire::{
+        cmd_expire, cmd_expireat, cmd_expiretime, cmd_persist, cmd_pexpire, cmd_pexpireat,
+        cmd_pexpiretime, cmd_pttl, cmd_ttl,
+    };
     pub use get::cmd_get;
+    pub use hash::{
+        cmd_hdel, cmd_hexists, cmd_hget, cmd_hgetall, cmd_hincrby, cmd_hincrbyfloat, cmd_hkeys,
+        cmd_hlen, cmd_hmget, cmd_hmset, cmd_hrandfield, cmd_hset, cmd_hsetnx, cmd_hstrlen,
+        cmd_hvals,
+    };
+    pub use hello::cmd_hello;
+    pub use info::cmd_info;
+    pub use keys::{cmd_del, cmd_exists, cmd_flushdb, cmd_type};
+    pub use list::{
+        cmd_blmove, cmd_blmpop, cmd_blpop, cmd_brpop, cmd_brpoplpush, cmd_lindex, cmd_linsert,
+        cmd_llen, cmd_lmove, cmd_lmpop, cmd_lpop, cmd_lpos, cmd_lpush, cmd_lpushx, cmd_lrange,
+        cmd_lrem, cmd_lset, cmd_ltrim, cmd_rpop, cmd_rpoplpush, cmd_rpush, cmd_rpushx,
+    };
+    pub use ping::cmd_ping;
+    pub use pubsub::{
+        cmd_psubscribe, cmd_publish, cmd_pubsub, cmd_punsubscribe, cmd_spublish, cmd_ssubscribe,
+        cmd_subscribe, cmd_sunsubscribe, cmd_unsubscribe,
+    };
+    pub use save::{cmd_bgrewriteaof, cmd_bgsave, cmd_lastsave, cmd_save};
     pub use set::cmd_set;
+    pub use sets::{
+        cmd_sadd, cmd_scard, cmd_sdiff, cmd_sdiffstore, cmd_sinter, cmd_sintercard,
+        cmd_sinterstore, cmd_sismember, cmd_smembers, cmd_smismember, cmd_smove, cmd_spop,
+        cmd_srandmember, cmd_srem, cmd_sunion, cmd_sunionstore,
+    };
+    pub use stream::{
+        cmd_xack, cmd_xadd, cmd_xautoclaim, cmd_xclaim, cmd_xdel, cmd_xgroup, cmd_xinfo, cmd_xlen,
+        cmd_xpending, cmd_xrange, cmd_xread, cmd_xreadgroup, cmd_xrevrange, cmd_xsetid, cmd_xtrim,
+    };
+    pub use transaction::{cmd_discard, cmd_exec, cmd_multi, cmd_unwatch, cmd_watch};
+    pub use zset::{
+        cmd_zadd, cmd_zcard, cmd_zcount, cmd_zincrby, cmd_zinterstore, cmd_zlexcount, cmd_zmscore,
+        cmd_zpopmax, cmd_zpopmin, cmd_zrange, cmd_zrangebylex, cmd_zrangebyscore, cmd_zrank,
+        cmd_zrem, cmd_zrevrange, cmd_zrevrangebylex, cmd_zrevrangebyscore, cmd_zrevrank,
+        cmd_zscore, cmd_zunionstore,
+    };
+}
+pub mod types {
+    pub mod set;
+    pub mod skiplist;
+    pub mod stream;
+    pub mod value;
+    pub mod zset;
+
+    pub use set::Set;
+    pub use stream::{
+        Consumer, ConsumerGroup, PendingEntry, Stream, StreamFields, StreamId, StreamTrim,
+        TrimStrategy,
+    };
+    pub use value::{Hash, List, Value};
+    pub use zset::{LexBound, ScoreBound, ZSet};
+}
+pub mod rdb {
+    pub mod crc64;
+    pub mod format;
+    pub mod lzf;
+    pub mod reader;
+    #[cfg(test)]
+    mod tests;
+    pub mod writer;
+
+    pub use reader::{load, RdbReader};
+    pub use writer::{save, RdbWriter};
+}
+pub mod resp3 {
+    pub mod primitives;
+    pub mod serializer;
+    #[cfg(test)]
+    mod tests;
+
+    pub use primitives::RESP3Type;
+    pub use serializer::format_double;
+    pub use serializer::Serialize;
 }
 pub mod respv2 {
+    pub mod decoder;
+    pub mod inline;
     pub mod parser;
     pub mod primitives;
     pub mod serializer;
     #[cfg(test)]
     mod tests;
 
+    pub use decoder::RESPv2Decoder;
     pub use parser::Parser;
     pub use parser::RESPv2Parser;
     pub use primitives::RESPv2Error;
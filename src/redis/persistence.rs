@@ -0,0 +1,253 @@
+use std::{
+    fs,
+    io::Error,
+    path::Path,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+
+use super::{
+    aof::{self, AppendOnlyFile},
+    config::Config,
+    db::{unix_time_ms, MemoryDatabase, Snapshot},
+    rdb,
+};
+
+pub const BGSAVE_RETRY_DELAY: u64 = 5;
+
+pub struct SaveState {
+    pub last_save: u64,
+    pub dirty_at_save: u64,
+    pub bgsave_in_progress: bool,
+    pub last_bgsave_ok: bool,
+    pub last_bgsave_attempt: u64,
+    pub aof_rewrite_in_progress: bool,
+    pub aof_last_rewrite_ok: bool,
+}
+
+#[derive(Clone)]
+pub struct Persistence {
+    config: Arc<Mutex<Config>>,
+    state: Arc<Mutex<SaveState>>,
+    aof: Arc<Mutex<Option<AppendOnlyFile>>>,
+}
+
+impl Persistence {
+    pub fn new(config: Config, dirty: u64) -> Self {
+        let state = SaveState {
+            last_save: unix_time_ms() / 1000,
+            dirty_at_save: dirty,
+            bgsave_in_progress: false,
+            last_bgsave_ok: true,
+            last_bgsave_attempt: 0,
+            aof_rewrite_in_progress: false,
+            aof_last_rewrite_ok: true,
+        };
+
+        Self {
+            config: Arc::new(Mutex::new(config)),
+            state: Arc::new(Mutex::new(state)),
+            aof: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    pub fn config(&self) -> MutexGuard<'_, Config> {
+        self.config.lock().expect("config lock poisoned")
+    }
+
+    pub fn state(&self) -> MutexGuard<'_, SaveState> {
+        self.state.lock().expect("save state lock poisoned")
+    }
+
+    pub fn aof(&self) -> MutexGuard<'_, Option<AppendOnlyFile>> {
+        self.aof.lock().expect("aof lock poisoned")
+    }
+
+    pub fn open_aof(&self, db: &mut dyn MemoryDatabase) -> Result<(), Error> {
+        let config = self.config();
+
+        if !config.appendonly {
+            return Ok(());
+        }
+
+        let path = config.aof_path();
+
+        if !path.exists() {
+            rewrite_aof(&path, &db.snapshot())?;
+        }
+
+        *self.aof() = Some(AppendOnlyFile::open(&path, config.appendfsync)?);
+
+        Ok(())
+    }
+
+    pub fn append(&self, commands: &[Vec<Vec<u8>>]) {
+        if commands.is_empty() {
+            return;
+        }
+
+        if let Some(aof) = self.aof().as_mut() {
+            if let Err(err) = aof.append(commands) {
+                eprintln!("Error writing to the AOF file: {}", err);
+            }
+        }
+    }
+
+    pub fn fsync_aof(&self) -> Option<JoinHandle<()>> {
+        let file = match self.aof().as_mut().map(AppendOnlyFile::take_pending_fsync) {
+            Some(Ok(Some(file))) => file,
+            Some(Err(err)) => {
+                eprintln!("Error syncing the AOF file: {}", err);
+                return None;
+            }
+            _ => return None,
+        };
+
+        Some(tokio::task::spawn_blocking(move || {
+            if let Err(err) = file.sync_data() {
+                eprintln!("Error syncing the AOF file: {}", err);
+            }
+        }))
+    }
+
+    pub fn save(&self, db: &mut dyn MemoryDatabase) -> Result<(), Error> {
+        let path = self.config().rdb_path();
+
+        rdb::save(&path, &db.snapshot())?;
+
+        let mut state = self.state();
+
+        state.last_save = unix_time_ms() / 1000;
+        state.dirty_at_save = db.dirty();
+
+        Ok(())
+    }
+
+    pub fn background_save(&self, db: &mut dyn MemoryDatabase) -> Option<JoinHandle<()>> {
+        let mut state = self.state();
+
+        if state.bgsave_in_progress {
+            return None;
+        }
+
+        let path = self.config().rdb_path();
+        let snapshot = db.snapshot();
+        let dirty = db.dirty();
+        let shared = Arc::clone(&self.state);
+
+        state.bgsave_in_progress = true;
+        state.last_bgsave_attempt = unix_time_ms() / 1000;
+
+        Some(tokio::task::spawn_blocking(move || {
+            let result = rdb::save(&path, &snapshot);
+            let mut state = shared.lock().expect("save state lock poisoned");
+
+            state.bgsave_in_progress = false;
+            state.last_bgsave_ok = result.is_ok();
+
+            match result {
+                Ok(()) => {
+                    state.last_save = unix_time_ms() / 1000;
+                    state.dirty_at_save = dirty;
+                }
+                Err(err) => eprintln!("Background saving error: {}", err),
+            }
+        }))
+    }
+
+    pub fn background_rewrite_aof(&self, db: &mut dyn MemoryDatabase) -> Option<JoinHandle<()>> {
+        let mut state = self.state();
+
+        if state.aof_rewrite_in_progress {
+            return None;
+        }
+
+        let path = self.config().aof_path();
+        let snapshot = db.snapshot();
+        let shared = Arc::clone(&self.state);
+        let file = Arc::clone(&self.aof);
+
+        if let Some(aof) = self.aof().as_mut() {
+            aof.start_rewrite();
+        }
+
+        state.aof_rewrite_in_progress = true;
+
+        Some(tokio::task::spawn_blocking(move || {
+            let temp =
+                path.with_file_name(format!("temp-rewriteaof-bg-{}.aof", std::process::id()));
+            let result = aof::write_rewrite(&temp, &snapshot).and_then(|()| {
+                match file.lock().expect("aof lock poisoned").as_mut() {
+                    Some(aof) => aof.finish_rewrite(&temp, &path),
+                    None => fs::rename(&temp, &path),
+                }
+            });
+
+            if result.is_err() {
+                let _ = fs::remove_file(&temp);
+
+                if let Some(aof) = file.lock().expect("aof lock poisoned").as_mut() {
+                    aof.cancel_rewrite();
+                }
+            }
+
+            let mut state = shared.lock().expect("save state lock poisoned");
+
+            state.aof_rewrite_in_progress = false;
+            state.aof_last_rewrite_ok = result.is_ok();
+
+            if let Err(err) = result {
+                eprintln!("Background AOF rewrite error: {}", err);
+            }
+        }))
+    }
+
+    pub fn save_due(&self, dirty: u64) -> bool {
+        let now = unix_time_ms() / 1000;
+        let state = self.state();
+        let changes = dirty.saturating_sub(state.dirty_at_save);
+
+        if state.bgsave_in_progress
+            || (!state.last_bgsave_ok
+                && now.saturating_sub(state.last_bgsave_attempt) <= BGSAVE_RETRY_DELAY)
+        {
+            return false;
+        }
+
+        self.config().save.iter().any(|rule| {
+            changes >= rule.changes && now.saturating_sub(state.last_save) > rule.seconds
+        })
+    }
+}
+
+pub fn spawn_save_scheduler(
+    db: Arc<tokio::sync::Mutex<impl MemoryDatabase + 'static>>,
+    persistence: Persistence,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(1));
+
+        loop {
+            interval.tick().await;
+            persistence.fsync_aof();
+
+            let mut db = db.lock().await;
+
+            if persistence.save_due(db.dirty()) {
+                persistence.background_save(&mut *db);
+            }
+        }
+    })
+}
+
+fn rewrite_aof(path: &Path, snapshot: &Snapshot) -> Result<(), Error> {
+    let temp = path.with_file_name(format!("temp-rewriteaof-{}.aof", std::process::id()));
+
+    if let Err(err) = aof::write_rewrite(&temp, snapshot).and_then(|()| fs::rename(&temp, path)) {
+        let _ = fs::remove_file(&temp);
+        return Err(err);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,247 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+};
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::{client::Client, reply::Reply};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum SubscriptionKind {
+    Channel,
+    Pattern,
+    Shard,
+}
+
+impl SubscriptionKind {
+    pub const ALL: [SubscriptionKind; 3] = [
+        SubscriptionKind::Channel,
+        SubscriptionKind::Pattern,
+        SubscriptionKind::Shard,
+    ];
+
+    pub fn subscribe_verb(&self) -> &'static str {
+        match self {
+            SubscriptionKind::Channel => "subscribe",
+            SubscriptionKind::Pattern => "psubscribe",
+            SubscriptionKind::Shard => "ssubscribe",
+        }
+    }
+
+    pub fn unsubscribe_verb(&self) -> &'static str {
+        match self {
+            SubscriptionKind::Channel => "unsubscribe",
+            SubscriptionKind::Pattern => "punsubscribe",
+            SubscriptionKind::Shard => "sunsubscribe",
+        }
+    }
+}
+
+type Subscribers = HashMap<u64, UnboundedSender<Reply>>;
+
+#[derive(Default)]
+pub struct Subscriptions {
+    channels: HashMap<Vec<u8>, Subscribers>,
+    patterns: HashMap<Vec<u8>, Subscribers>,
+    shard_channels: HashMap<Vec<u8>, Subscribers>,
+}
+
+impl Subscriptions {
+    pub fn subscribe(&mut self, kind: SubscriptionKind, name: &[u8], client: &Client) {
+        self.registry_mut(kind)
+            .entry(name.to_vec())
+            .or_default()
+            .insert(client.id, client.sender());
+    }
+
+    pub fn unsubscribe(&mut self, kind: SubscriptionKind, name: &[u8], client: &Client) {
+        let registry = self.registry_mut(kind);
+
+        if let Some(subscribers) = registry.get_mut(name) {
+            subscribers.remove(&client.id);
+
+            if subscribers.is_empty() {
+                registry.remove(name);
+            }
+        }
+    }
+
+    pub fn publish(&self, channel: &[u8], message: &[u8]) -> usize {
+        let mut receivers = 0;
+
+        for sender in self
+            .channels
+            .get(channel)
+            .into_iter()
+            .flat_map(|s| s.values())
+        {
+            let push = Reply::Push(vec![
+                Reply::bulk("message"),
+                Reply::bulk(channel),
+                Reply::bulk(message),
+            ]);
+
+            receivers += sender.send(push).is_ok() as usize;
+        }
+
+        for (pattern, subscribers) in &self.patterns {
+            if !glob_match(pattern, channel) {
+                continue;
+            }
+
+            for sender in subscribers.values() {
+                let push = Reply::Push(vec![
+                    Reply::bulk("pmessage"),
+                    Reply::bulk(pattern),
+                    Reply::bulk(channel),
+                    Reply::bulk(message),
+                ]);
+
+                receivers += sender.send(push).is_ok() as usize;
+            }
+        }
+
+        receivers
+    }
+
+    pub fn publish_shard(&self, channel: &[u8], message: &[u8]) -> usize {
+        let mut receivers = 0;
+
+        for sender in self
+            .shard_channels
+            .get(channel)
+            .into_iter()
+            .flat_map(|s| s.values())
+        {
+            let push = Reply::Push(vec![
+                Reply::bulk("smessage"),
+                Reply::bulk(channel),
+                Reply::bulk(message),
+            ]);
+
+            receivers += sender.send(push).is_ok() as usize;
+        }
+
+        receivers
+    }
+
+    pub fn channels(&self, kind: SubscriptionKind, pattern: Option<&[u8]>) -> Vec<Vec<u8>> {
+        self.registry(kind)
+            .keys()
+            .filter(|channel| pattern.is_none_or(|pattern| glob_match(pattern, channel)))
+            .cloned()
+            .collect()
+    }
+
+    pub fn subscribers(&self, kind: SubscriptionKind, channel: &[u8]) -> usize {
+        self.registry(kind).get(channel).map_or(0, HashMap::len)
+    }
+
+    pub fn patterns(&self) -> usize {
+        self.patterns.len()
+    }
+
+    fn registry(&self, kind: SubscriptionKind) -> &HashMap<Vec<u8>, Subscribers> {
+        match kind {
+            SubscriptionKind::Channel => &self.channels,
+            SubscriptionKind::Pattern => &self.patterns,
+            SubscriptionKind::Shard => &self.shard_channels,
+        }
+    }
+
+    fn registry_mut(&mut self, kind: SubscriptionKind) -> &mut HashMap<Vec<u8>, Subscribers> {
+        match kind {
+            SubscriptionKind::Channel => &mut self.channels,
+            SubscriptionKind::Pattern => &mut self.patterns,
+            SubscriptionKind::Shard => &mut self.shard_channels,
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct PubSubHub {
+    inner: Arc<Mutex<Subscriptions>>,
+}
+
+impl PubSubHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, Subscriptions> {
+        self.inner.lock().unwrap()
+    }
+
+    pub fn disconnect(&self, client: &Client) {
+        let mut subscriptions = self.lock();
+
+        for kind in SubscriptionKind::ALL {
+            for name in client.subscriptions(kind) {
+                subscriptions.unsubscribe(kind, name, client);
+            }
+        }
+    }
+}
+
+pub fn glob_match(pattern: &[u8], string: &[u8]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    let mut star = None;
+
+    while s < string.len() {
+        if pattern.get(p) == Some(&b'*') {
+            p += 1;
+            star = Some((p, s));
+            continue;
+        }
+
+        if let Some(next) = match_byte(pattern, p, string[s]) {
+            p = next;
+            s += 1;
+            continue;
+        }
+
+        let Some((star_p, star_s)) = star else {
+            return false;
+        };
+
+        p = star_p;
+        s = star_s + 1;
+        star = Some((star_p, s));
+    }
+
+    pattern[p.min(pattern.len())..]
+        .iter()
+        .all(|byte| *byte == b'*')
+}
+
+fn match_byte(pattern: &[u8], position: usize, byte: u8) -> Option<usize> {
+    match pattern.get(position..)?.split_first()? {
+        (b'?', _) => Some(position + 1),
+        (b'[', rest) => {
+            let (negate, mut offset) = match rest.first() {
+                Some(b'^') => (true, 1),
+                _ => (false, 0),
+            };
+            let mut matched = false;
+
+            while offset < rest.len() && rest[offset] != b']' {
+                if rest[offset] == b'\\' && offset + 1 < rest.len() {
+                    matched |= rest[offset + 1] == byte;
+                    offset += 2;
+                } else if offset + 2 < rest.len() && rest[offset + 1] == b'-' {
+                    let (start, end) = (rest[offset], rest[offset + 2]);
+
+                    matched |= (start.min(end)..=start.max(end)).contains(&byte);
+                    offset += 3;
+                } else {
+                    matched |= rest[offset] == byte;
+                    offset += 1;
+                }
+            }
+
+            (matched != negate).then_some((position + offset + 2).min(pattern.len()))
+        }
+        (b'\\', [escaped, ..]) => (*escaped == byte).then_some(position + 2),
+        (literal, _) => (*literal == byte).then_some(position + 1),
+    }
+}
@@ -0,0 +1,30 @@
+use std::sync::OnceLock;
+
+const CRC64_POLY_REFLECTED: u64 = 0x95ac_9329_ac4b_c9b5;
+
+pub fn crc64(crc: u64, data: &[u8]) -> u64 {
+    static TABLE: OnceLock<[u64; 256]> = OnceLock::new();
+
+    let table = TABLE.get_or_init(|| {
+        let mut table = [0; 256];
+
+        for (byte, entry) in table.iter_mut().enumerate() {
+            let mut crc = byte as u64;
+
+            for _ in 0..8 {
+                crc = match crc & 1 {
+                    1 => (crc >> 1) ^ CRC64_POLY_REFLECTED,
+                    _ => crc >> 1,
+                };
+            }
+
+            *entry = crc;
+        }
+
+        table
+    });
+
+    data.iter().fold(crc, |crc, byte| {
+        table[((crc ^ *byte as u64) & 0xff) as usize] ^ (crc >> 8)
+    })
+}
@@ -0,0 +1,50 @@
+pub const RDB_MAGIC: &[u8] = b"REDIS";
+pub const RDB_VERSION: u32 = 11;
+
+pub const RDB_OPCODE_IDLE: u8 = 0xf8;
+pub const RDB_OPCODE_FREQ: u8 = 0xf9;
+pub const RDB_OPCODE_AUX: u8 = 0xfa;
+pub const RDB_OPCODE_RESIZEDB: u8 = 0xfb;
+pub const RDB_OPCODE_EXPIRETIME_MS: u8 = 0xfc;
+pub const RDB_OPCODE_EXPIRETIME: u8 = 0xfd;
+pub const RDB_OPCODE_SELECTDB: u8 = 0xfe;
+pub const RDB_OPCODE_EOF: u8 = 0xff;
+
+pub const RDB_TYPE_STRING: u8 = 0;
+pub const RDB_TYPE_LIST: u8 = 1;
+pub const RDB_TYPE_SET: u8 = 2;
+pub const RDB_TYPE_ZSET: u8 = 3;
+pub const RDB_TYPE_HASH: u8 = 4;
+pub const RDB_TYPE_ZSET_2: u8 = 5;
+pub const RDB_TYPE_HASH_ZIPMAP: u8 = 9;
+pub const RDB_TYPE_LIST_ZIPLIST: u8 = 10;
+pub const RDB_TYPE_SET_INTSET: u8 = 11;
+pub const RDB_TYPE_ZSET_ZIPLIST: u8 = 12;
+pub const RDB_TYPE_HASH_ZIPLIST: u8 = 13;
+pub const RDB_TYPE_LIST_QUICKLIST: u8 = 14;
+pub const RDB_TYPE_STREAM_LISTPACKS: u8 = 15;
+pub const RDB_TYPE_HASH_LISTPACK: u8 = 16;
+pub const RDB_TYPE_ZSET_LISTPACK: u8 = 17;
+pub const RDB_TYPE_LIST_QUICKLIST_2: u8 = 18;
+pub const RDB_TYPE_STREAM_LISTPACKS_2: u8 = 19;
+pub const RDB_TYPE_SET_LISTPACK: u8 = 20;
+pub const RDB_TYPE_STREAM_LISTPACKS_3: u8 = 21;
+
+pub const RDB_6BITLEN: u8 = 0;
+pub const RDB_14BITLEN: u8 = 1;
+pub const RDB_32BITLEN: u8 = 0x80;
+pub const RDB_64BITLEN: u8 = 0x81;
+pub const RDB_ENCVAL: u8 = 3;
+
+pub const RDB_ENC_INT8: u8 = 0;
+pub const RDB_ENC_INT16: u8 = 1;
+pub const RDB_ENC_INT32: u8 = 2;
+pub const RDB_ENC_LZF: u8 = 3;
+
+pub const QUICKLIST_NODE_CONTAINER_PLAIN: u64 = 1;
+pub const QUICKLIST_NODE_CONTAINER_PACKED: u64 = 2;
+
+pub const STREAM_ITEM_FLAG_DELETED: i64 = 1;
+pub const STREAM_ITEM_FLAG_SAMEFIELDS: i64 = 2;
+
+pub const RDB_MIN_VERSION_CHECKSUM: u32 = 5;
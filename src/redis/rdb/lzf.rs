@@ -0,0 +1,53 @@
+use std::io::{Error, ErrorKind};
+
+pub fn decompress(input: &[u8], expected_len: usize) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::new(ErrorKind::InvalidData, "Invalid LZF compressed data.");
+    let mut output = Vec::with_capacity(expected_len.min(input.len().saturating_mul(8)));
+    let mut position = 0;
+
+    while position < input.len() {
+        let control = input[position] as usize;
+        position += 1;
+
+        if control < 32 {
+            let literal = input
+                .get(position..position + control + 1)
+                .ok_or_else(invalid)?;
+
+            if output.len() + literal.len() > expected_len {
+                return Err(invalid());
+            }
+
+            output.extend_from_slice(literal);
+            position += control + 1;
+            continue;
+        }
+
+        let mut len = control >> 5;
+
+        if len == 7 {
+            len += *input.get(position).ok_or_else(invalid)? as usize;
+            position += 1;
+        }
+
+        let low = *input.get(position).ok_or_else(invalid)? as usize;
+        position += 1;
+
+        let offset = ((control & 0x1f) << 8) + low + 1;
+        let start = output.len().checked_sub(offset).ok_or_else(invalid)?;
+
+        if output.len() + len + 2 > expected_len {
+            return Err(invalid());
+        }
+
+        for i in start..start + len + 2 {
+            output.push(output[i]);
+        }
+    }
+
+    if output.len() != expected_len {
+        return Err(invalid());
+    }
+
+    Ok(output)
+}
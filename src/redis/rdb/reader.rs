@@ -0,0 +1,678 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    io::{Error, ErrorKind},
+    path::Path,
+};
+
+use super::{crc64::crc64, format::*, lzf};
+use crate::redis::{
+    db::{unix_time_ms, MemoryDatabase},
+    types::{
+        Consumer, ConsumerGroup, Hash, List, PendingEntry, Set, Stream, StreamFields, StreamId,
+        Value, ZSet,
+    },
+};
+
+pub fn load(path: &Path, db: &mut dyn MemoryDatabase) -> Result<usize, Error> {
+    match fs::read(path) {
+        Ok(data) => RdbReader::new(&data).load(db),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(0),
+        Err(err) => Err(err),
+    }
+}
+
+pub struct RdbReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> RdbReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    pub fn load(&mut self, db: &mut dyn MemoryDatabase) -> Result<usize, Error> {
+        let version = self.read_header()?;
+        let now = unix_time_ms();
+        let mut selected = 0;
+        let mut expires_at = None;
+        let mut loaded = 0;
+
+        loop {
+            match self.read_u8()? {
+                RDB_OPCODE_EOF => {
+                    self.verify_checksum(version)?;
+
+                    return Ok(loaded);
+                }
+                RDB_OPCODE_AUX => {
+                    self.read_string()?;
+                    self.read_string()?;
+                }
+                RDB_OPCODE_SELECTDB => selected = self.read_length()?,
+                RDB_OPCODE_RESIZEDB => {
+                    self.read_length()?;
+                    self.read_length()?;
+                }
+                RDB_OPCODE_EXPIRETIME => {
+                    let seconds = u32::from_le_bytes(self.read_array()?);
+
+                    expires_at = Some(seconds as u64 * 1000);
+                }
+                RDB_OPCODE_EXPIRETIME_MS => {
+                    expires_at = Some(u64::from_le_bytes(self.read_array()?));
+                }
+                RDB_OPCODE_FREQ => {
+                    self.read_u8()?;
+                }
+                RDB_OPCODE_IDLE => {
+                    self.read_length()?;
+                }
+                value_type => {
+                    let key = self.read_string()?;
+                    let value = self.read_value(value_type)?;
+                    let expires_at = expires_at.take();
+
+                    if selected != 0 || expires_at.is_some_and(|expires_at| expires_at <= now) {
+                        continue;
+                    }
+
+                    db.set(&key, value, expires_at)?;
+                    loaded += 1;
+                }
+            }
+        }
+    }
+
+    fn read_header(&mut self) -> Result<u32, Error> {
+        if self.read_bytes(RDB_MAGIC.len())? != RDB_MAGIC {
+            return Err(invalid("Wrong signature trying to load DB from file"));
+        }
+
+        let version = std::str::from_utf8(self.read_bytes(4)?)
+            .ok()
+            .and_then(|version| version.parse::<u32>().ok())
+            .filter(|version| (1..=12).contains(version))
+            .ok_or_else(|| invalid("Can't handle RDB format version"))?;
+
+        Ok(version)
+    }
+
+    fn verify_checksum(&mut self, version: u32) -> Result<(), Error> {
+        if version < RDB_MIN_VERSION_CHECKSUM {
+            return Ok(());
+        }
+
+        let computed = crc64(0, &self.data[..self.position]);
+        let expected = u64::from_le_bytes(self.read_array()?);
+
+        if expected != 0 && expected != computed {
+            return Err(invalid("Wrong RDB checksum"));
+        }
+
+        Ok(())
+    }
+
+    fn read_value(&mut self, value_type: u8) -> Result<Value, Error> {
+        let value = match value_type {
+            RDB_TYPE_STRING => Value::String(self.read_string()?),
+            RDB_TYPE_LIST => {
+                let len = self.read_length()?;
+                let mut list = List::new();
+
+                for _ in 0..len {
+                    list.push_back(self.read_string()?);
+                }
+
+                Value::List(list)
+            }
+            RDB_TYPE_SET => {
+                let len = self.read_length()?;
+                let mut set = Set::new();
+
+                for _ in 0..len {
+                    set.insert(&self.read_string()?);
+                }
+
+                Value::Set(set)
+            }
+            RDB_TYPE_ZSET | RDB_TYPE_ZSET_2 => {
+                let len = self.read_length()?;
+                let mut zset = ZSet::new();
+
+                for _ in 0..len {
+                    let member = self.read_string()?;
+                    let score = match value_type {
+                        RDB_TYPE_ZSET => self.read_string_double()?,
+                        _ => f64::from_le_bytes(self.read_array()?),
+                    };
+
+                    zset.insert(&member, score);
+                }
+
+                Value::ZSet(zset)
+            }
+            RDB_TYPE_HASH => {
+                let len = self.read_length()?;
+                let mut hash = Hash::new();
+
+                for _ in 0..len {
+                    let field = self.read_string()?;
+
+                    hash.insert(field, self.read_string()?);
+                }
+
+                Value::Hash(hash)
+            }
+            RDB_TYPE_HASH_ZIPMAP => Value::Hash(read_zipmap(&self.read_string()?)?),
+            RDB_TYPE_LIST_ZIPLIST => Value::List(read_ziplist(&self.read_string()?)?.into()),
+            RDB_TYPE_SET_INTSET => {
+                let mut set = Set::new();
+
+                for member in read_intset(&self.read_string()?)? {
+                    set.insert(member.to_string().as_bytes());
+                }
+
+                Value::Set(set)
+            }
+            RDB_TYPE_SET_LISTPACK => {
+                let mut set = Set::new();
+
+                for member in read_listpack(&self.read_string()?)? {
+                    set.insert(&member);
+                }
+
+                Value::Set(set)
+            }
+            RDB_TYPE_ZSET_ZIPLIST | RDB_TYPE_ZSET_LISTPACK => {
+                let blob = self.read_string()?;
+                let entries = match value_type {
+                    RDB_TYPE_ZSET_ZIPLIST => read_ziplist(&blob)?,
+                    _ => read_listpack(&blob)?,
+                };
+                let mut zset = ZSet::new();
+
+                for (member, score) in pairs(entries)? {
+                    let score = std::str::from_utf8(&score)
+                        .ok()
+                        .and_then(|score| score.parse::<f64>().ok())
+                        .ok_or_else(|| invalid("Invalid sorted set score"))?;
+
+                    zset.insert(&member, score);
+                }
+
+                Value::ZSet(zset)
+            }
+            RDB_TYPE_HASH_ZIPLIST | RDB_TYPE_HASH_LISTPACK => {
+                let blob = self.read_string()?;
+                let entries = match value_type {
+                    RDB_TYPE_HASH_ZIPLIST => read_ziplist(&blob)?,
+                    _ => read_listpack(&blob)?,
+                };
+
+                Value::Hash(pairs(entries)?.collect())
+            }
+            RDB_TYPE_LIST_QUICKLIST | RDB_TYPE_LIST_QUICKLIST_2 => {
+                let len = self.read_length()?;
+                let mut list = List::new();
+
+                for _ in 0..len {
+                    let container = match value_type {
+                        RDB_TYPE_LIST_QUICKLIST_2 => self.read_length()?,
+                        _ => QUICKLIST_NODE_CONTAINER_PACKED,
+                    };
+                    let node = self.read_string()?;
+
+                    match (value_type, container) {
+                        (_, QUICKLIST_NODE_CONTAINER_PLAIN) => list.push_back(node),
+                        (RDB_TYPE_LIST_QUICKLIST, _) => list.extend(read_ziplist(&node)?),
+                        (_, QUICKLIST_NODE_CONTAINER_PACKED) => list.extend(read_listpack(&node)?),
+                        _ => return Err(invalid("Unknown quicklist node container")),
+                    }
+                }
+
+                Value::List(list)
+            }
+            RDB_TYPE_STREAM_LISTPACKS
+            | RDB_TYPE_STREAM_LISTPACKS_2
+            | RDB_TYPE_STREAM_LISTPACKS_3 => Value::Stream(self.read_stream(value_type)?),
+            _ => {
+                return Err(invalid(&format!(
+                    "Unsupported RDB object type {}",
+                    value_type
+                )))
+            }
+        };
+
+        Ok(value)
+    }
+
+    fn read_stream(&mut self, value_type: u8) -> Result<Stream, Error> {
+        let mut stream = Stream::new();
+
+        for _ in 0..self.read_length()? {
+            let master = stream_id_from_bytes(&self.read_string()?)
+                .ok_or_else(|| invalid("Stream node key entry is not the size of a stream ID"))?;
+
+            for (id, fields) in read_stream_listpack(master, &self.read_string()?)? {
+                if !stream.add(id, fields) {
+                    return Err(invalid("Stream entries are not in ascending order"));
+                }
+            }
+        }
+
+        if self.read_length()? != stream.len() as u64 {
+            return Err(invalid("Stream length does not match its entries"));
+        }
+
+        let last_id = self.read_stream_id()?;
+        let (max_deleted_id, entries_added) = match value_type {
+            RDB_TYPE_STREAM_LISTPACKS => (StreamId::MIN, stream.len() as u64),
+            _ => {
+                self.read_stream_id()?;
+
+                (self.read_stream_id()?, self.read_length()?)
+            }
+        };
+
+        stream.set_id(last_id, Some(entries_added), Some(max_deleted_id));
+
+        for _ in 0..self.read_length()? {
+            let name = self.read_string()?;
+            let last_id = self.read_stream_id()?;
+            let entries_read = match value_type {
+                RDB_TYPE_STREAM_LISTPACKS => stream.estimate_entries_read(last_id),
+                _ => Some(self.read_length()?).filter(|read| *read != u64::MAX),
+            };
+            let mut group = ConsumerGroup::new(last_id, entries_read);
+            let mut deliveries = BTreeMap::new();
+
+            for _ in 0..self.read_length()? {
+                let id = self.read_raw_stream_id()?;
+                let delivery_time = u64::from_le_bytes(self.read_array()?);
+
+                deliveries.insert(id, (delivery_time, self.read_length()?));
+            }
+
+            for _ in 0..self.read_length()? {
+                let consumer = self.read_string()?;
+                let seen_time = u64::from_le_bytes(self.read_array()?);
+                let active_time = match value_type {
+                    RDB_TYPE_STREAM_LISTPACKS_3 => {
+                        let active_time = i64::from_le_bytes(self.read_array()?);
+
+                        (active_time >= 0).then_some(active_time as u64)
+                    }
+                    _ => Some(seen_time),
+                };
+                let mut pending = BTreeSet::new();
+
+                for _ in 0..self.read_length()? {
+                    let id = self.read_raw_stream_id()?;
+                    let (delivery_time, delivery_count) = deliveries
+                        .remove(&id)
+                        .ok_or_else(|| invalid("Consumer PEL entry not found in group PEL"))?;
+
+                    group.pending.insert(
+                        id,
+                        PendingEntry {
+                            consumer: consumer.clone(),
+                            delivery_time,
+                            delivery_count,
+                        },
+                    );
+                    pending.insert(id);
+                }
+
+                group.consumers.insert(
+                    consumer,
+                    Consumer {
+                        seen_time,
+                        active_time,
+                        pending,
+                    },
+                );
+            }
+
+            if !deliveries.is_empty() {
+                return Err(invalid("Group PEL entry without a consumer"));
+            }
+
+            if !stream.create_group(&name, group) {
+                return Err(invalid("Duplicated consumer group name"));
+            }
+        }
+
+        Ok(stream)
+    }
+
+    fn read_stream_id(&mut self) -> Result<StreamId, Error> {
+        Ok(StreamId::new(self.read_length()?, self.read_length()?))
+    }
+
+    fn read_raw_stream_id(&mut self) -> Result<StreamId, Error> {
+        Ok(stream_id_from_bytes(self.read_bytes(16)?).expect("read 16 bytes"))
+    }
+
+    fn read_length_with_encoding(&mut self) -> Result<(u64, bool), Error> {
+        let first = self.read_u8()?;
+
+        match (first >> 6, first) {
+            (RDB_6BITLEN, _) => Ok(((first & 0x3f) as u64, false)),
+            (RDB_14BITLEN, _) => Ok((
+                (((first & 0x3f) as u64) << 8) | self.read_u8()? as u64,
+                false,
+            )),
+            (_, RDB_32BITLEN) => Ok((u32::from_be_bytes(self.read_array()?) as u64, false)),
+            (_, RDB_64BITLEN) => Ok((u64::from_be_bytes(self.read_array()?), false)),
+            (RDB_ENCVAL, _) => Ok(((first & 0x3f) as u64, true)),
+            _ => Err(invalid("Unknown length encoding in RDB file")),
+        }
+    }
+
+    fn read_length(&mut self) -> Result<u64, Error> {
+        match self.read_length_with_encoding()? {
+            (len, false) => Ok(len),
+            (_, true) => Err(invalid("Unexpected encoded value in RDB length")),
+        }
+    }
+
+    fn read_string(&mut self) -> Result<Vec<u8>, Error> {
+        let (len, encoded) = self.read_length_with_encoding()?;
+
+        if !encoded {
+            return Ok(self.read_bytes(len as usize)?.to_vec());
+        }
+
+        let value = match len as u8 {
+            RDB_ENC_INT8 => self.read_u8()? as i8 as i64,
+            RDB_ENC_INT16 => i16::from_le_bytes(self.read_array()?) as i64,
+            RDB_ENC_INT32 => i32::from_le_bytes(self.read_array()?) as i64,
+            RDB_ENC_LZF => {
+                let compressed_len = self.read_length()? as usize;
+                let len = self.read_length()? as usize;
+
+                return lzf::decompress(self.read_bytes(compressed_len)?, len);
+            }
+            _ => return Err(invalid("Unknown RDB string encoding type")),
+        };
+
+        Ok(value.to_string().into_bytes())
+    }
+
+    fn read_string_double(&mut self) -> Result<f64, Error> {
+        match self.read_u8()? {
+            253 => Ok(f64::NAN),
+            254 => Ok(f64::INFINITY),
+            255 => Ok(f64::NEG_INFINITY),
+            len => std::str::from_utf8(self.read_bytes(len as usize)?)
+                .ok()
+                .and_then(|score| score.parse::<f64>().ok())
+                .ok_or_else(|| invalid("Invalid double value in RDB file")),
+        }
+    }
+
+    fn read_u8(&mut self) -> Result<u8, Error> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        Ok(self.read_bytes(N)?.try_into().expect("slice has N bytes"))
+    }
+
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .position
+            .checked_add(len)
+            .and_then(|end| self.data.get(self.position..end))
+            .ok_or_else(|| invalid("Short read or OOM loading DB. Unrecoverable error"))?;
+
+        self.position += len;
+
+        Ok(bytes)
+    }
+}
+
+fn read_intset(blob: &[u8]) -> Result<Vec<i64>, Error> {
+    let mut reader = RdbReader::new(blob);
+    let encoding = u32::from_le_bytes(reader.read_array()?);
+    let len = u32::from_le_bytes(reader.read_array()?);
+
+    (0..len)
+        .map(|_| match encoding {
+            2 => Ok(i16::from_le_bytes(reader.read_array()?) as i64),
+            4 => Ok(i32::from_le_bytes(reader.read_array()?) as i64),
+            8 => Ok(i64::from_le_bytes(reader.read_array()?)),
+            _ => Err(invalid("Invalid intset encoding")),
+        })
+        .collect()
+}
+
+fn read_zipmap(blob: &[u8]) -> Result<Hash, Error> {
+    let mut reader = RdbReader::new(blob);
+    let mut hash = Hash::new();
+
+    reader.read_u8()?;
+
+    loop {
+        let Some(field_len) = read_zipmap_len(&mut reader)? else {
+            return Ok(hash);
+        };
+        let field = reader.read_bytes(field_len)?.to_vec();
+        let value_len = read_zipmap_len(&mut reader)?.ok_or_else(|| invalid("Invalid zipmap"))?;
+        let free = reader.read_u8()? as usize;
+
+        hash.insert(field, reader.read_bytes(value_len)?.to_vec());
+        reader.read_bytes(free)?;
+    }
+}
+
+fn read_zipmap_len(reader: &mut RdbReader) -> Result<Option<usize>, Error> {
+    match reader.read_u8()? {
+        255 => Ok(None),
+        254 => Ok(Some(u32::from_le_bytes(reader.read_array()?) as usize)),
+        len => Ok(Some(len as usize)),
+    }
+}
+
+fn read_ziplist(blob: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+    let mut reader = RdbReader::new(blob);
+    let mut entries = vec![];
+
+    reader.read_bytes(10)?;
+
+    loop {
+        match reader.read_u8()? {
+            255 => return Ok(entries),
+            254 => {
+                reader.read_bytes(4)?;
+            }
+            _ => {}
+        }
+
+        let encoding = reader.read_u8()?;
+        let entry = match encoding >> 6 {
+            0 => reader.read_bytes((encoding & 0x3f) as usize)?.to_vec(),
+            1 => {
+                let len = (((encoding & 0x3f) as usize) << 8) | reader.read_u8()? as usize;
+
+                reader.read_bytes(len)?.to_vec()
+            }
+            2 => {
+                let len = u32::from_be_bytes(reader.read_array()?) as usize;
+
+                reader.read_bytes(len)?.to_vec()
+            }
+            _ => {
+                let value = match encoding {
+                    0xc0 => i16::from_le_bytes(reader.read_array()?) as i64,
+                    0xd0 => i32::from_le_bytes(reader.read_array()?) as i64,
+                    0xe0 => i64::from_le_bytes(reader.read_array()?),
+                    0xf0 => read_int24(reader.read_array()?),
+                    0xfe => reader.read_u8()? as i8 as i64,
+                    0xf1..=0xfd => (encoding & 0x0f) as i64 - 1,
+                    _ => return Err(invalid("Invalid ziplist entry encoding")),
+                };
+
+                value.to_string().into_bytes()
+            }
+        };
+
+        entries.push(entry);
+    }
+}
+
+fn read_listpack(blob: &[u8]) -> Result<Vec<Vec<u8>>, Error> {
+    let mut reader = RdbReader::new(blob);
+    let mut entries = vec![];
+
+    reader.read_bytes(6)?;
+
+    loop {
+        let start = reader.position;
+        let encoding = reader.read_u8()?;
+        let entry = match encoding {
+            0xff => return Ok(entries),
+            0x00..=0x7f => (encoding as i64).to_string().into_bytes(),
+            0x80..=0xbf => reader.read_bytes((encoding & 0x3f) as usize)?.to_vec(),
+            0xc0..=0xdf => {
+                let value = (((encoding & 0x1f) as i64) << 8) | reader.read_u8()? as i64;
+
+                match value >= 1 << 12 {
+                    true => value - (1 << 13),
+                    false => value,
+                }
+                .to_string()
+                .into_bytes()
+            }
+            0xe0..=0xef => {
+                let len = (((encoding & 0x0f) as usize) << 8) | reader.read_u8()? as usize;
+
+                reader.read_bytes(len)?.to_vec()
+            }
+            0xf0 => {
+                let len = u32::from_le_bytes(reader.read_array()?) as usize;
+
+                reader.read_bytes(len)?.to_vec()
+            }
+            0xf1 => (i16::from_le_bytes(reader.read_array()?) as i64)
+                .to_string()
+                .into_bytes(),
+            0xf2 => read_int24(reader.read_array()?).to_string().into_bytes(),
+            0xf3 => (i32::from_le_bytes(reader.read_array()?) as i64)
+                .to_string()
+                .into_bytes(),
+            0xf4 => i64::from_le_bytes(reader.read_array()?)
+                .to_string()
+                .into_bytes(),
+            _ => return Err(invalid("Invalid listpack entry encoding")),
+        };
+
+        reader.read_bytes(backlen_size(reader.position - start))?;
+        entries.push(entry);
+    }
+}
+
+fn read_stream_listpack(
+    master: StreamId,
+    blob: &[u8],
+) -> Result<Vec<(StreamId, StreamFields)>, Error> {
+    let mut elements = read_listpack(blob)?.into_iter();
+    let count = listpack_integer(elements.next())?;
+    let deleted = listpack_integer(elements.next())?;
+    let master_fields = (0..listpack_integer(elements.next())?)
+        .map(|_| {
+            elements
+                .next()
+                .ok_or_else(|| invalid("Truncated stream listpack"))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let mut entries = vec![];
+
+    if listpack_integer(elements.next())? != 0 {
+        return Err(invalid("Missing stream master entry terminator"));
+    }
+
+    for _ in 0..count + deleted {
+        let flags = listpack_integer(elements.next())?;
+        let ms = master
+            .ms
+            .wrapping_add(listpack_integer(elements.next())? as u64);
+        let seq = master
+            .seq
+            .wrapping_add(listpack_integer(elements.next())? as u64);
+        let mut next = || {
+            elements
+                .next()
+                .ok_or_else(|| invalid("Truncated stream listpack"))
+        };
+        let fields: StreamFields = match flags & STREAM_ITEM_FLAG_SAMEFIELDS {
+            0 => (0..listpack_integer(Some(next()?))?)
+                .map(|_| Ok((next()?, next()?)))
+                .collect::<Result<_, Error>>()?,
+            _ => master_fields
+                .iter()
+                .map(|field| Ok((field.clone(), next()?)))
+                .collect::<Result<_, Error>>()?,
+        };
+
+        next()?;
+
+        if flags & STREAM_ITEM_FLAG_DELETED == 0 {
+            entries.push((StreamId::new(ms, seq), fields));
+        }
+    }
+
+    if entries.len() as i64 != count || elements.next().is_some() {
+        return Err(invalid("Stream listpack entry count mismatch"));
+    }
+
+    Ok(entries)
+}
+
+fn listpack_integer(element: Option<Vec<u8>>) -> Result<i64, Error> {
+    element
+        .and_then(|element| std::str::from_utf8(&element).ok()?.parse::<i64>().ok())
+        .ok_or_else(|| invalid("Invalid integer in stream listpack"))
+}
+
+fn stream_id_from_bytes(bytes: &[u8]) -> Option<StreamId> {
+    let bytes: &[u8; 16] = bytes.try_into().ok()?;
+    let (ms, seq) = bytes.split_at(8);
+
+    Some(StreamId::new(
+        u64::from_be_bytes(ms.try_into().ok()?),
+        u64::from_be_bytes(seq.try_into().ok()?),
+    ))
+}
+
+fn read_int24(bytes: [u8; 3]) -> i64 {
+    (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as i64
+}
+
+fn backlen_size(len: usize) -> usize {
+    match len {
+        0..=127 => 1,
+        128..=16382 => 2,
+        16383..=2097150 => 3,
+        2097151..=268435454 => 4,
+        _ => 5,
+    }
+}
+
+fn pairs(entries: Vec<Vec<u8>>) -> Result<impl Iterator<Item = (Vec<u8>, Vec<u8>)>, Error> {
+    if !entries.len().is_multiple_of(2) {
+        return Err(invalid("Odd number of entries in encoded pairs"));
+    }
+
+    let mut entries = entries.into_iter();
+
+    Ok(std::iter::from_fn(move || {
+        Some((entries.next()?, entries.next()?))
+    }))
+}
+
+fn invalid(message: &str) -> Error {
+    Error::new(ErrorKind::InvalidData, message.to_string())
+}
@@ -0,0 +1,375 @@
+use crate::{
+    mem_db::MemDB,
+    redis::{
+        db::{unix_time_ms, MemoryDatabase},
+        rdb::{crc64::crc64, lzf, RdbReader, RdbWriter},
+        types::{ConsumerGroup, Hash, List, Set, Stream, StreamId, Value, ZSet},
+    },
+};
+
+fn rdb(body: &[u8]) -> Vec<u8> {
+    let mut data = b"REDIS0011".to_vec();
+
+    data.extend_from_slice(b"\xfa\x09redis-ver\x057.2.0\xfa\x0aredis-bits\xc0\x40");
+    data.extend_from_slice(body);
+    data.push(0xff);
+    data.extend_from_slice(&[0; 8]);
+    data
+}
+
+fn string(value: &[u8]) -> Vec<u8> {
+    let mut encoded = vec![value.len() as u8];
+
+    encoded.extend_from_slice(value);
+    encoded
+}
+
+fn listpack(entries: &[&[u8]]) -> Vec<u8> {
+    let mut body = vec![];
+
+    for entry in entries {
+        body.extend_from_slice(entry);
+        body.push(entry.len() as u8);
+    }
+
+    let mut blob = ((body.len() + 7) as u32).to_le_bytes().to_vec();
+
+    blob.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+    blob.extend_from_slice(&body);
+    blob.push(0xff);
+    blob
+}
+
+fn ziplist(entries: &[&[u8]]) -> Vec<u8> {
+    let mut blob = vec![0; 8];
+    let mut previous = 0;
+
+    blob.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+
+    for entry in entries {
+        blob.push(previous);
+        blob.extend_from_slice(entry);
+        previous = entry.len() as u8 + 1;
+    }
+
+    blob.push(0xff);
+    blob
+}
+
+fn load(data: &[u8]) -> MemDB {
+    let mut db = MemDB::new();
+
+    RdbReader::new(data).load(&mut db).unwrap();
+    db
+}
+
+#[test]
+fn rdb_lzf_decompress() {
+    assert_eq!(
+        lzf::decompress(b"\x02abc\xe0\x00\x02", 12).unwrap(),
+        b"abcabcabcabc"
+    );
+    assert!(lzf::decompress(b"\x02abc\xe0\x00\x02", 13).is_err());
+    assert!(lzf::decompress(b"\x20\x00", 2).is_err());
+    assert!(lzf::decompress(b"\x02abc\xe0\x00\x02", 11).is_err());
+    assert!(lzf::decompress(b"\x02abc", 2).is_err());
+    assert!(lzf::decompress(b"\x00a", usize::MAX).is_err());
+}
+
+#[test]
+fn rdb_load_strings_and_expiries() {
+    let future = (unix_time_ms() + 60_000).to_le_bytes();
+    let past = (unix_time_ms() - 1).to_le_bytes();
+    let mut body = b"\xfe\x00\xfb\x06\x02".to_vec();
+
+    body.extend_from_slice(b"\x00\x03foo\x03bar");
+    body.extend_from_slice(b"\x00\x04int8\xc0\x7b");
+    body.extend_from_slice(b"\x00\x05int16\xc1\x30\xf8");
+    body.extend_from_slice(b"\x00\x05int32\xc2\x40\x42\x0f\x00");
+    body.extend_from_slice(b"\x00\x03lzf\xc3\x05\x0a\x00a\xe0\x00\x00");
+    body.extend_from_slice(b"\x00\x0elong-length-14\x40\x02hi");
+    body.push(0xfc);
+    body.extend_from_slice(&future);
+    body.extend_from_slice(b"\x00\x06future\x01x");
+    body.push(0xfc);
+    body.extend_from_slice(&past);
+    body.extend_from_slice(b"\x00\x04past\x01x");
+    body.extend_from_slice(b"\xfd\x01\x00\x00\x00\x00\x07seconds\x01x");
+    body.extend_from_slice(b"\xf9\x05\xf8\x10\x00\x05extra\x01y");
+    body.extend_from_slice(b"\xfe\x01\x00\x05other\x01z");
+
+    let mut db = load(&rdb(&body));
+
+    assert_eq!(db.len(), 8);
+    assert_eq!(db.get(b"foo"), Some(&Value::String(b"bar".to_vec())));
+    assert_eq!(db.get(b"int8"), Some(&Value::String(b"123".to_vec())));
+    assert_eq!(db.get(b"int16"), Some(&Value::String(b"-2000".to_vec())));
+    assert_eq!(db.get(b"int32"), Some(&Value::String(b"1000000".to_vec())));
+    assert_eq!(db.get(b"lzf"), Some(&Value::String(b"aaaaaaaaaa".to_vec())));
+    assert_eq!(
+        db.get(b"long-length-14"),
+        Some(&Value::String(b"hi".to_vec()))
+    );
+    assert_eq!(db.get_expiry(b"future"), Some(u64::from_le_bytes(future)));
+    assert_eq!(db.get(b"past"), None);
+    assert_eq!(db.get(b"seconds"), None);
+    assert_eq!(db.get(b"extra"), Some(&Value::String(b"y".to_vec())));
+    assert_eq!(db.get(b"other"), None);
+}
+
+#[test]
+fn rdb_load_collection_encodings() {
+    let mut body = vec![];
+
+    body.extend_from_slice(b"\x01\x04list\x02\x01a\x01b");
+    body.extend_from_slice(b"\x12\x05list2\x02\x01\x05plain\x02");
+    body.extend_from_slice(&string(&listpack(&[b"\x81x", b"\x07", b"\xdf\xfb"])));
+    body.extend_from_slice(b"\x0e\x05list1\x01");
+    body.extend_from_slice(&string(&ziplist(&[b"\x01z", b"\xfe\x9c", b"\xf4"])));
+    body.extend_from_slice(b"\x0a\x05list0");
+    body.extend_from_slice(&string(&ziplist(&[b"\x01w"])));
+    body.extend_from_slice(b"\x02\x03set\x02\x01a\x01b");
+    body.extend_from_slice(b"\x0b\x06intset");
+    body.extend_from_slice(&string(b"\x02\x00\x00\x00\x02\x00\x00\x00\xfd\xff\x07\x00"));
+    body.extend_from_slice(b"\x14\x06lpset1");
+    body.extend_from_slice(&string(&listpack(&[b"\x81m", b"\x05"])));
+    body.extend_from_slice(b"\x03\x04zset\x02\x01a\x031.5\x01b\xfe");
+    body.extend_from_slice(b"\x05\x05zset2\x01\x01a");
+    body.extend_from_slice(&2.5f64.to_le_bytes());
+    body.extend_from_slice(b"\x11\x06lpzset");
+    body.extend_from_slice(&string(&listpack(&[
+        b"\x81a", b"\x83-.5", b"\x81b", b"\x03",
+    ])));
+    body.extend_from_slice(b"\x0c\x06zlzset");
+    body.extend_from_slice(&string(&ziplist(&[b"\x01a", b"\xf3"])));
+    body.extend_from_slice(b"\x04\x04hash\x01\x01f\x01v");
+    body.extend_from_slice(b"\x10\x06lphash");
+    body.extend_from_slice(&string(&listpack(&[b"\x81f", b"\x01"])));
+    body.extend_from_slice(b"\x0d\x06zlhash");
+    body.extend_from_slice(&string(&ziplist(&[b"\x01f", b"\x01v"])));
+    body.extend_from_slice(b"\x09\x06zmhash");
+    body.extend_from_slice(&string(b"\x01\x01f\x01\x00v\xff"));
+    body.extend_from_slice(b"\x0f\x06stream\x01");
+    body.extend_from_slice(&string(&[5u64.to_be_bytes(), 1u64.to_be_bytes()].concat()));
+    body.extend_from_slice(&string(&listpack(&[
+        b"\x01",
+        b"\x01",
+        b"\x01",
+        b"\x81f",
+        b"\x00",
+        b"\x03",
+        b"\x00",
+        b"\x00",
+        b"\x81a",
+        b"\x04",
+        b"\x00",
+        b"\x01",
+        b"\xdf\xff",
+        b"\x01",
+        b"\x81g",
+        b"\x81h",
+        b"\x06",
+    ])));
+    body.extend_from_slice(b"\x01\x06\x00\x01\x01g\x06\x00\x01");
+    body.extend_from_slice(&[6u64.to_be_bytes(), 0u64.to_be_bytes()].concat());
+    body.extend_from_slice(&1_000u64.to_le_bytes());
+    body.extend_from_slice(b"\x02\x01\x01c");
+    body.extend_from_slice(&2_000u64.to_le_bytes());
+    body.extend_from_slice(b"\x01");
+    body.extend_from_slice(&[6u64.to_be_bytes(), 0u64.to_be_bytes()].concat());
+
+    let mut db = load(&rdb(&body));
+    let list =
+        |items: &[&[u8]]| Value::List(items.iter().map(|item| item.to_vec()).collect::<List>());
+    let hash =
+        |field: &[u8], value: &[u8]| Value::Hash(Hash::from([(field.to_vec(), value.to_vec())]));
+    let zscore = |db: &mut MemDB, key: &[u8], member: &[u8]| {
+        db.get_zset(key).unwrap().unwrap().score(member)
+    };
+
+    assert_eq!(db.get(b"list"), Some(&list(&[b"a", b"b"])));
+    assert_eq!(
+        db.get(b"list2"),
+        Some(&list(&[b"plain", b"x", b"7", b"-5"]))
+    );
+    assert_eq!(db.get(b"list1"), Some(&list(&[b"z", b"-100", b"3"])));
+    assert_eq!(db.get(b"list0"), Some(&list(&[b"w"])));
+
+    let set = db.get_set(b"set").unwrap().unwrap();
+    assert!(set.contains(b"a") && set.contains(b"b") && set.len() == 2);
+
+    let intset = db.get_set(b"intset").unwrap().unwrap();
+    assert_eq!(intset.encoding(), "intset");
+    assert!(intset.contains(b"-3") && intset.contains(b"7"));
+
+    let lpset = db.get_set(b"lpset1").unwrap().unwrap();
+    assert!(lpset.contains(b"m") && lpset.contains(b"5"));
+
+    assert_eq!(zscore(&mut db, b"zset", b"a"), Some(1.5));
+    assert_eq!(zscore(&mut db, b"zset", b"b"), Some(f64::INFINITY));
+    assert_eq!(zscore(&mut db, b"zset2", b"a"), Some(2.5));
+    assert_eq!(zscore(&mut db, b"lpzset", b"a"), Some(-0.5));
+    assert_eq!(zscore(&mut db, b"lpzset", b"b"), Some(3.0));
+    assert_eq!(zscore(&mut db, b"zlzset", b"a"), Some(2.0));
+
+    assert_eq!(db.get(b"hash"), Some(&hash(b"f", b"v")));
+    assert_eq!(db.get(b"lphash"), Some(&hash(b"f", b"1")));
+    assert_eq!(db.get(b"zlhash"), Some(&hash(b"f", b"v")));
+    assert_eq!(db.get(b"zmhash"), Some(&hash(b"f", b"v")));
+
+    let stream = db.get_stream(b"stream").unwrap().unwrap();
+    let id = StreamId::new(6, 0);
+    let group = stream.group(b"g").unwrap();
+
+    assert_eq!(stream.len(), 1);
+    assert_eq!(stream.get(&id), Some(&vec![(b"g".to_vec(), b"h".to_vec())]));
+    assert_eq!(stream.last_id(), id);
+    assert_eq!(stream.entries_added(), 1);
+    assert_eq!(group.entries_read, Some(1));
+    assert_eq!(group.pending[&id].consumer, b"c");
+    assert_eq!(group.pending[&id].delivery_time, 1_000);
+    assert_eq!(group.pending[&id].delivery_count, 2);
+    assert_eq!(group.consumers[&b"c".to_vec()].active_time, Some(2_000));
+}
+
+#[test]
+fn rdb_load_rejects_invalid_files() {
+    let mut db = MemDB::new();
+
+    assert!(RdbReader::new(b"RODIS0011\xff").load(&mut db).is_err());
+    assert!(RdbReader::new(b"REDIS0099\xff").load(&mut db).is_err());
+    assert!(RdbReader::new(b"REDIS0011\x00\x03foo\x05ba")
+        .load(&mut db)
+        .is_err());
+    assert!(RdbReader::new(b"REDIS0011\x06\x01k\x00")
+        .load(&mut db)
+        .is_err());
+}
+
+#[test]
+fn rdb_crc64_check_value() {
+    assert_eq!(crc64(0, b"123456789"), 0xe9c6d914c4b8d9ca);
+    assert_eq!(crc64(crc64(0, b"1234"), b"56789"), 0xe9c6d914c4b8d9ca);
+}
+
+#[test]
+fn rdb_write_round_trip() {
+    let mut db = MemDB::new();
+    let expires_at = unix_time_ms() + 60_000;
+    let long = vec![b'x'; 20_000];
+    let mut set = Set::new();
+    let mut intset = Set::new();
+    let mut zset = ZSet::new();
+
+    set.insert(b"member");
+    intset.insert(b"-70000");
+    zset.insert(b"a", 1.5);
+    zset.insert(b"b", f64::NEG_INFINITY);
+
+    let values = [
+        (b"string".to_vec(), Value::String(b"hello".to_vec())),
+        (b"small".to_vec(), Value::String(b"-12".to_vec())),
+        (b"int16".to_vec(), Value::String(b"3000".to_vec())),
+        (b"int32".to_vec(), Value::String(b"-70000".to_vec())),
+        (b"padded".to_vec(), Value::String(b"007".to_vec())),
+        (b"wide".to_vec(), Value::String(b"12345678901".to_vec())),
+        (b"long".to_vec(), Value::String(long)),
+        (
+            b"list".to_vec(),
+            Value::List(List::from([b"a".to_vec(), b"1".to_vec()])),
+        ),
+        (b"set".to_vec(), Value::Set(set)),
+        (b"intset".to_vec(), Value::Set(intset)),
+        (b"zset".to_vec(), Value::ZSet(zset)),
+        (
+            b"hash".to_vec(),
+            Value::Hash(Hash::from([(b"f".to_vec(), b"v".to_vec())])),
+        ),
+    ];
+
+    for (key, value) in &values {
+        db.set(key, value.clone(), None).unwrap();
+    }
+
+    db.set_expiry(b"string", Some(expires_at));
+
+    let data = RdbWriter::new().write(&db.snapshot()).unwrap();
+    let mut loaded = load(&data);
+
+    assert_eq!(loaded.len(), values.len());
+
+    for (key, value) in &values {
+        assert_eq!(loaded.get(key), Some(value));
+    }
+
+    assert_eq!(loaded.get_expiry(b"string"), Some(expires_at));
+    assert_eq!(loaded.get_expiry(b"list"), None);
+
+    let mut corrupted = data.clone();
+    let position = corrupted.len() - 12;
+
+    corrupted[position] ^= 0xff;
+    assert!(RdbReader::new(&corrupted).load(&mut MemDB::new()).is_err());
+}
+
+#[test]
+fn rdb_write_stream_round_trip() {
+    let mut db = MemDB::new();
+    let mut stream = Stream::new();
+    let now = unix_time_ms();
+
+    for seq in 1..=250 {
+        let fields = match seq % 50 {
+            0 => vec![(b"other".to_vec(), vec![b'x'; 5_000])],
+            _ => vec![
+                (b"n".to_vec(), seq.to_string().into_bytes()),
+                (
+                    b"neg".to_vec(),
+                    (-70_000 * seq as i64).to_string().into_bytes(),
+                ),
+                (b"empty".to_vec(), vec![]),
+            ],
+        };
+
+        stream.add(StreamId::new(1_700_000_000_000 + seq / 3, seq % 3), fields);
+    }
+
+    stream.delete(&StreamId::new(1_700_000_000_000, 2));
+    stream.create_group(b"readers", ConsumerGroup::new(StreamId::MIN, Some(0)));
+    stream.create_group(b"idle", ConsumerGroup::new(StreamId::new(5, 5), None));
+    stream.read_group(b"readers", b"alice", Some(3), false, now);
+    stream.read_group(b"readers", b"bob", Some(2), false, now + 10);
+    stream
+        .group_mut(b"idle")
+        .unwrap()
+        .create_consumer(b"carol", now);
+
+    let mut empty = Stream::new();
+
+    empty.set_id(StreamId::new(7, 0), Some(4), Some(StreamId::new(7, 0)));
+
+    let values = [
+        (b"stream".to_vec(), Value::Stream(stream)),
+        (b"empty".to_vec(), Value::Stream(empty)),
+    ];
+
+    for (key, value) in &values {
+        db.set(key, value.clone(), None).unwrap();
+    }
+
+    let data = RdbWriter::new().write(&db.snapshot()).unwrap();
+    let mut loaded = load(&data);
+
+    for (key, value) in &values {
+        assert_eq!(loaded.get(key), Some(value));
+    }
+
+    let Some(Value::Stream(stream)) = loaded.get(b"stream") else {
+        panic!("stream was not loaded");
+    };
+    let group = stream.group(b"readers").unwrap();
+
+    assert_eq!(group.pending.len(), 5);
+    assert_eq!(group.consumers[&b"bob".to_vec()].pending.len(), 2);
+    assert_eq!(stream.group(b"idle").unwrap().entries_read, None);
+}
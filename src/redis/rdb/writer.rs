@@ -0,0 +1,399 @@
+use std::{
+    fs::{self, File},
+    io::{Error, Write},
+    path::Path,
+};
+
+use super::{crc64::crc64, format::*};
+use crate::redis::{
+    cmd::hello::SERVER_VERSION,
+    db::{unix_time_ms, Snapshot},
+    types::{stream::STREAM_NODE_MAX_ENTRIES, Stream, StreamFields, StreamId, Value},
+};
+
+pub fn save(path: &Path, snapshot: &Snapshot) -> Result<(), Error> {
+    let data = RdbWriter::new().write(snapshot)?;
+    let temp = path.with_file_name(format!("temp-{}.rdb", std::process::id()));
+
+    let result = File::create(&temp).and_then(|mut file| {
+        file.write_all(&data)?;
+        file.sync_all()
+    });
+
+    if let Err(err) = result.and_then(|_| fs::rename(&temp, path)) {
+        let _ = fs::remove_file(&temp);
+        return Err(err);
+    }
+
+    Ok(())
+}
+
+#[derive(Default)]
+pub struct RdbWriter {
+    buffer: Vec<u8>,
+}
+
+impl RdbWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn write(mut self, snapshot: &Snapshot) -> Result<Vec<u8>, Error> {
+        self.buffer.extend_from_slice(RDB_MAGIC);
+        self.buffer
+            .extend_from_slice(format!("{:04}", RDB_VERSION).as_bytes());
+
+        self.write_aux(b"redis-ver", SERVER_VERSION.as_bytes());
+        self.write_aux(b"redis-bits", b"64");
+        self.write_aux(b"ctime", (unix_time_ms() / 1000).to_string().as_bytes());
+
+        if !snapshot.is_empty() {
+            let expires = snapshot
+                .iter()
+                .filter(|(_, _, expires_at)| expires_at.is_some())
+                .count();
+
+            self.buffer.push(RDB_OPCODE_SELECTDB);
+            self.write_length(0);
+            self.buffer.push(RDB_OPCODE_RESIZEDB);
+            self.write_length(snapshot.len() as u64);
+            self.write_length(expires as u64);
+        }
+
+        for (key, value, expires_at) in snapshot {
+            if let Some(expires_at) = expires_at {
+                self.buffer.push(RDB_OPCODE_EXPIRETIME_MS);
+                self.buffer.extend_from_slice(&expires_at.to_le_bytes());
+            }
+
+            self.write_value(key, value);
+        }
+
+        self.buffer.push(RDB_OPCODE_EOF);
+
+        let checksum = crc64(0, &self.buffer);
+
+        self.buffer.extend_from_slice(&checksum.to_le_bytes());
+
+        Ok(self.buffer)
+    }
+
+    fn write_aux(&mut self, key: &[u8], value: &[u8]) {
+        self.buffer.push(RDB_OPCODE_AUX);
+        self.write_string(key);
+        self.write_string(value);
+    }
+
+    fn write_value(&mut self, key: &[u8], value: &Value) {
+        match value {
+            Value::String(string) => {
+                self.buffer.push(RDB_TYPE_STRING);
+                self.write_string(key);
+                self.write_string(string);
+            }
+            Value::List(list) => {
+                self.buffer.push(RDB_TYPE_LIST);
+                self.write_string(key);
+                self.write_length(list.len() as u64);
+
+                for item in list {
+                    self.write_string(item);
+                }
+            }
+            Value::Set(set) => {
+                self.buffer.push(RDB_TYPE_SET);
+                self.write_string(key);
+                self.write_length(set.len() as u64);
+
+                for member in set.members() {
+                    self.write_string(&member);
+                }
+            }
+            Value::ZSet(zset) => {
+                self.buffer.push(RDB_TYPE_ZSET_2);
+                self.write_string(key);
+                self.write_length(zset.len() as u64);
+
+                for (member, score) in zset.iter() {
+                    self.write_string(member);
+                    self.buffer.extend_from_slice(&score.to_le_bytes());
+                }
+            }
+            Value::Hash(hash) => {
+                self.buffer.push(RDB_TYPE_HASH);
+                self.write_string(key);
+                self.write_length(hash.len() as u64);
+
+                for (field, value) in hash {
+                    self.write_string(field);
+                    self.write_string(value);
+                }
+            }
+            Value::Stream(stream) => {
+                self.buffer.push(RDB_TYPE_STREAM_LISTPACKS_3);
+                self.write_string(key);
+                self.write_stream(stream);
+            }
+        }
+    }
+
+    fn write_stream(&mut self, stream: &Stream) {
+        let entries: Vec<_> = stream.range(StreamId::MIN..=StreamId::MAX).collect();
+        let nodes = entries.chunks(STREAM_NODE_MAX_ENTRIES);
+
+        self.write_length(nodes.len() as u64);
+
+        for node in nodes {
+            let master = *node[0].0;
+
+            self.write_string(&stream_id_bytes(master));
+            self.write_string(&stream_listpack(master, node));
+        }
+
+        self.write_length(stream.len() as u64);
+        self.write_stream_id(stream.last_id());
+        self.write_stream_id(stream.first_entry().map_or(StreamId::MIN, |(id, _)| *id));
+        self.write_stream_id(stream.max_deleted_id());
+        self.write_length(stream.entries_added());
+        self.write_length(stream.groups().count() as u64);
+
+        for (name, group) in stream.groups() {
+            self.write_string(name);
+            self.write_stream_id(group.last_id);
+            self.write_length(group.entries_read.unwrap_or(u64::MAX));
+            self.write_length(group.pending.len() as u64);
+
+            for (id, entry) in &group.pending {
+                self.buffer.extend_from_slice(&stream_id_bytes(*id));
+                self.buffer
+                    .extend_from_slice(&entry.delivery_time.to_le_bytes());
+                self.write_length(entry.delivery_count);
+            }
+
+            self.write_length(group.consumers.len() as u64);
+
+            for (name, consumer) in &group.consumers {
+                let active_time = consumer.active_time.map_or(-1, |time| time as i64);
+
+                self.write_string(name);
+                self.buffer
+                    .extend_from_slice(&consumer.seen_time.to_le_bytes());
+                self.buffer.extend_from_slice(&active_time.to_le_bytes());
+                self.write_length(consumer.pending.len() as u64);
+
+                for id in &consumer.pending {
+                    self.buffer.extend_from_slice(&stream_id_bytes(*id));
+                }
+            }
+        }
+    }
+
+    fn write_stream_id(&mut self, id: StreamId) {
+        self.write_length(id.ms);
+        self.write_length(id.seq);
+    }
+
+    fn write_length(&mut self, len: u64) {
+        match len {
+            0..=0x3f => self.buffer.push(len as u8),
+            0x40..=0x3fff => self
+                .buffer
+                .extend_from_slice(&((len as u16) | (RDB_14BITLEN as u16) << 14).to_be_bytes()),
+            0x4000..=0xffff_ffff => {
+                self.buffer.push(RDB_32BITLEN);
+                self.buffer.extend_from_slice(&(len as u32).to_be_bytes());
+            }
+            _ => {
+                self.buffer.push(RDB_64BITLEN);
+                self.buffer.extend_from_slice(&len.to_be_bytes());
+            }
+        }
+    }
+
+    fn write_string(&mut self, string: &[u8]) {
+        if let Some(value) = as_encoded_integer(string) {
+            let encoded = RDB_ENCVAL << 6;
+
+            match value {
+                value if i8::try_from(value).is_ok() => self
+                    .buffer
+                    .extend_from_slice(&[encoded | RDB_ENC_INT8, value as u8]),
+                value if i16::try_from(value).is_ok() => {
+                    self.buffer.push(encoded | RDB_ENC_INT16);
+                    self.buffer.extend_from_slice(&(value as i16).to_le_bytes());
+                }
+                value => {
+                    self.buffer.push(encoded | RDB_ENC_INT32);
+                    self.buffer.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+
+            return;
+        }
+
+        self.write_length(string.len() as u64);
+        self.buffer.extend_from_slice(string);
+    }
+}
+
+fn as_encoded_integer(string: &[u8]) -> Option<i32> {
+    if string.is_empty() || string.len() > 11 {
+        return None;
+    }
+
+    let value = std::str::from_utf8(string).ok()?.parse::<i32>().ok()?;
+
+    (value.to_string().as_bytes() == string).then_some(value)
+}
+
+fn stream_id_bytes(id: StreamId) -> [u8; 16] {
+    let mut bytes = [0; 16];
+
+    bytes[..8].copy_from_slice(&id.ms.to_be_bytes());
+    bytes[8..].copy_from_slice(&id.seq.to_be_bytes());
+    bytes
+}
+
+fn stream_listpack(master: StreamId, node: &[(&StreamId, &StreamFields)]) -> Vec<u8> {
+    let master_fields: Vec<&Vec<u8>> = node[0].1.iter().map(|(field, _)| field).collect();
+    let mut listpack = Listpack::default();
+
+    listpack.push_integer(node.len() as i64);
+    listpack.push_integer(0);
+    listpack.push_integer(master_fields.len() as i64);
+
+    for field in &master_fields {
+        listpack.push(field);
+    }
+
+    listpack.push_integer(0);
+
+    for (id, fields) in node {
+        let same_fields = fields
+            .iter()
+            .map(|(field, _)| field)
+            .eq(master_fields.iter().copied());
+        let flags = match same_fields {
+            true => STREAM_ITEM_FLAG_SAMEFIELDS,
+            false => 0,
+        };
+
+        listpack.push_integer(flags);
+        listpack.push_integer(id.ms.wrapping_sub(master.ms) as i64);
+        listpack.push_integer(id.seq.wrapping_sub(master.seq) as i64);
+
+        if same_fields {
+            for (_, value) in fields.iter() {
+                listpack.push(value);
+            }
+
+            listpack.push_integer(fields.len() as i64 + 3);
+        } else {
+            listpack.push_integer(fields.len() as i64);
+
+            for (field, value) in fields.iter() {
+                listpack.push(field);
+                listpack.push(value);
+            }
+
+            listpack.push_integer(fields.len() as i64 * 2 + 4);
+        }
+    }
+
+    listpack.finish()
+}
+
+#[derive(Default)]
+struct Listpack {
+    body: Vec<u8>,
+    len: usize,
+}
+
+impl Listpack {
+    fn push(&mut self, element: &[u8]) {
+        if let Some(value) = std::str::from_utf8(element)
+            .ok()
+            .and_then(|element| element.parse::<i64>().ok())
+            .filter(|value| value.to_string().as_bytes() == element)
+        {
+            return self.push_integer(value);
+        }
+
+        let mut entry = match element.len() {
+            len @ 0..=0x3f => vec![0x80 | len as u8],
+            len @ 0x40..=0xfff => vec![0xe0 | (len >> 8) as u8, len as u8],
+            len => {
+                let mut entry = vec![0xf0];
+
+                entry.extend_from_slice(&(len as u32).to_le_bytes());
+                entry
+            }
+        };
+
+        entry.extend_from_slice(element);
+        self.push_entry(entry);
+    }
+
+    fn push_integer(&mut self, value: i64) {
+        let entry = match value {
+            0..=0x7f => vec![value as u8],
+            -4096..=4095 => {
+                let value = value as u16 & 0x1fff;
+
+                vec![0xc0 | (value >> 8) as u8, value as u8]
+            }
+            _ if i16::try_from(value).is_ok() => {
+                [&[0xf1], &(value as i16).to_le_bytes()[..]].concat()
+            }
+            -8_388_608..=8_388_607 => [&[0xf2], &(value as i32).to_le_bytes()[..3]].concat(),
+            _ if i32::try_from(value).is_ok() => {
+                [&[0xf3], &(value as i32).to_le_bytes()[..]].concat()
+            }
+            _ => [&[0xf4], &value.to_le_bytes()[..]].concat(),
+        };
+
+        self.push_entry(entry);
+    }
+
+    fn push_entry(&mut self, entry: Vec<u8>) {
+        let len = entry.len();
+
+        self.body.extend_from_slice(&entry);
+
+        match len {
+            0..=127 => self.body.push(len as u8),
+            128..=16382 => self
+                .body
+                .extend_from_slice(&[(len >> 7) as u8, (len & 127) as u8 | 128]),
+            16383..=2097150 => self.body.extend_from_slice(&[
+                (len >> 14) as u8,
+                ((len >> 7) & 127) as u8 | 128,
+                (len & 127) as u8 | 128,
+            ]),
+            2097151..=268435454 => self.body.extend_from_slice(&[
+                (len >> 21) as u8,
+                ((len >> 14) & 127) as u8 | 128,
+                ((len >> 7) & 127) as u8 | 128,
+                (len & 127) as u8 | 128,
+            ]),
+            _ => self.body.extend_from_slice(&[
+                (len >> 28) as u8,
+                ((len >> 21) & 127) as u8 | 128,
+                ((len >> 14) & 127) as u8 | 128,
+                ((len >> 7) & 127) as u8 | 128,
+                (len & 127) as u8 | 128,
+            ]),
+        }
+
+        self.len += 1;
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let mut listpack = ((self.body.len() + 7) as u32).to_le_bytes().to_vec();
+
+        listpack.extend_from_slice(&(self.len.min(u16::MAX as usize) as u16).to_le_bytes());
+        listpack.extend_from_slice(&self.body);
+        listpack.push(0xff);
+        listpack
+    }
+}
@@ -0,0 +1,159 @@
+use super::{
+    resp3::{self, RESP3Type},
+    respv2::{self, RESPv2Type},
+};
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum Protocol {
+    #[default]
+    RESP2,
+    RESP3,
+}
+
+impl Protocol {
+    pub fn version(&self) -> i64 {
+        match self {
+            Protocol::RESP2 => 2,
+            Protocol::RESP3 => 3,
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Reply {
+    Simple(String),
+    Error(String),
+    Integer(i64),
+    Bulk(Vec<u8>),
+    Null,
+    NullArray,
+    Array(Vec<Reply>),
+    Map(Vec<(Reply, Reply)>),
+    Set(Vec<Reply>),
+    Double(f64),
+    Boolean(bool),
+    BigNumber(String),
+    Verbatim(String, Vec<u8>),
+    Attribute(Vec<(Reply, Reply)>, Box<Reply>),
+    Push(Vec<Reply>),
+}
+
+impl Reply {
+    pub fn ok() -> Self {
+        Reply::Simple(String::from("OK"))
+    }
+
+    pub fn from_error(error: std::io::Error) -> Self {
+        let message = error.to_string();
+
+        match message.strip_prefix('-') {
+            Some(message) => Reply::Error(message.to_string()),
+            None => Reply::Error(format!("ERR {}", message)),
+        }
+    }
+
+    pub fn bulk(data: impl AsRef<[u8]>) -> Self {
+        Reply::Bulk(data.as_ref().to_vec())
+    }
+
+    pub fn serialize(self, protocol: Protocol) -> Vec<u8> {
+        match protocol {
+            Protocol::RESP2 => respv2::Serialize::serialize_to_respv2(&RESPv2Type::from(self)),
+            Protocol::RESP3 => resp3::Serialize::serialize_to_resp3(&RESP3Type::from(self)),
+        }
+    }
+}
+
+impl From<Reply> for RESPv2Type {
+    fn from(reply: Reply) -> Self {
+        let array = |items: Vec<Reply>| {
+            RESPv2Type::Array(
+                items
+                    .into_iter()
+                    .map(|item| Box::new(RESPv2Type::from(item)))
+                    .collect(),
+            )
+        };
+
+        match reply {
+            Reply::Simple(string) => RESPv2Type::String(string.into_bytes()),
+            Reply::Error(error) => RESPv2Type::Error(error),
+            Reply::Integer(num) => RESPv2Type::Number(num),
+            Reply::Bulk(bulk) => RESPv2Type::Bulk(bulk),
+            Reply::Null => RESPv2Type::Null,
+            Reply::NullArray => RESPv2Type::NullArray,
+            Reply::Array(items) | Reply::Set(items) | Reply::Push(items) => array(items),
+            Reply::Map(pairs) => array(
+                pairs
+                    .into_iter()
+                    .flat_map(|(key, value)| [key, value])
+                    .collect(),
+            ),
+            Reply::Double(double) => RESPv2Type::Bulk(format_score(double).into_bytes()),
+            Reply::Boolean(boolean) => RESPv2Type::Number(boolean as i64),
+            Reply::BigNumber(num) => RESPv2Type::Bulk(num.into_bytes()),
+            Reply::Verbatim(_, text) => RESPv2Type::Bulk(text),
+            Reply::Attribute(_, value) => RESPv2Type::from(*value),
+        }
+    }
+}
+
+impl From<Reply> for RESP3Type {
+    fn from(reply: Reply) -> Self {
+        let items = |items: Vec<Reply>| items.into_iter().map(RESP3Type::from).collect();
+        let pairs = |pairs: Vec<(Reply, Reply)>| {
+            pairs
+                .into_iter()
+                .map(|(key, value)| (RESP3Type::from(key), RESP3Type::from(value)))
+                .collect()
+        };
+
+        match reply {
+            Reply::Simple(string) => RESP3Type::String(string.into_bytes()),
+            Reply::Error(error) => RESP3Type::Error(error),
+            Reply::Integer(num) => RESP3Type::Number(num),
+            Reply::Bulk(bulk) => RESP3Type::Bulk(bulk),
+            Reply::Null | Reply::NullArray => RESP3Type::Null,
+            Reply::Array(array) => RESP3Type::Array(items(array)),
+            Reply::Map(map) => RESP3Type::Map(pairs(map)),
+            Reply::Set(set) => RESP3Type::Set(items(set)),
+            Reply::Double(double) => RESP3Type::Double(double),
+            Reply::Boolean(boolean) => RESP3Type::Boolean(boolean),
+            Reply::BigNumber(num) => RESP3Type::BigNumber(num),
+            Reply::Verbatim(format, text) => RESP3Type::Verbatim(format, text),
+            Reply::Attribute(attributes, value) => {
+                RESP3Type::Attribute(pairs(attributes), Box::new(RESP3Type::from(*value)))
+            }
+            Reply::Push(push) => RESP3Type::Push(items(push)),
+        }
+    }
+}
+
+fn format_score(score: f64) -> String {
+    if !score.is_finite() {
+        return resp3::format_double(score);
+    }
+
+    let scientific = format!("{:.16e}", score);
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("scientific notation has an exponent");
+    let exponent: i32 = exponent.parse().expect("exponent is an integer");
+
+    match exponent {
+        -4..=16 => trim_fraction(&format!("{:.*}", (16 - exponent) as usize, score)).to_string(),
+        _ => format!(
+            "{}e{}{:02}",
+            trim_fraction(mantissa),
+            if exponent < 0 { '-' } else { '+' },
+            exponent.abs()
+        ),
+    }
+}
+
+fn trim_fraction(number: &str) -> &str {
+    match number.contains('.') {
+        true => number.trim_end_matches('0').trim_end_matches('.'),
+        false => number,
+    }
+}
@@ -0,0 +1,17 @@
+#[derive(PartialEq, Debug, Clone)]
+pub enum RESP3Type {
+    Array(Vec<RESP3Type>),
+    Number(i64),
+    String(Vec<u8>),
+    Bulk(Vec<u8>),
+    Error(String),
+    Null,
+    Double(f64),
+    Boolean(bool),
+    BigNumber(String),
+    Verbatim(String, Vec<u8>),
+    Map(Vec<(RESP3Type, RESP3Type)>),
+    Set(Vec<RESP3Type>),
+    Attribute(Vec<(RESP3Type, RESP3Type)>, Box<RESP3Type>),
+    Push(Vec<RESP3Type>),
+}
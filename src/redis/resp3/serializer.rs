@@ -0,0 +1,106 @@
+use super::RESP3Type;
+
+pub trait Serialize {
+    fn serialize_to_resp3(&self) -> Vec<u8>;
+}
+
+impl Serialize for i64 {
+    fn serialize_to_resp3(&self) -> Vec<u8> {
+        format!(":{}\r\n", self).into_bytes()
+    }
+}
+
+impl Serialize for f64 {
+    fn serialize_to_resp3(&self) -> Vec<u8> {
+        format!(",{}\r\n", format_double(*self)).into_bytes()
+    }
+}
+
+impl Serialize for bool {
+    fn serialize_to_resp3(&self) -> Vec<u8> {
+        if *self {
+            b"#t\r\n".to_vec()
+        } else {
+            b"#f\r\n".to_vec()
+        }
+    }
+}
+
+impl Serialize for Vec<RESP3Type> {
+    fn serialize_to_resp3(&self) -> Vec<u8> {
+        serialize_aggregate(b'*', self)
+    }
+}
+
+impl Serialize for Vec<(RESP3Type, RESP3Type)> {
+    fn serialize_to_resp3(&self) -> Vec<u8> {
+        serialize_pairs(b'%', self)
+    }
+}
+
+impl Serialize for RESP3Type {
+    fn serialize_to_resp3(&self) -> Vec<u8> {
+        match self {
+            RESP3Type::Array(array) => array.serialize_to_resp3(),
+            RESP3Type::Number(num) => num.serialize_to_resp3(),
+            RESP3Type::String(string) => [b"+", string.as_slice(), b"\r\n"].concat(),
+            RESP3Type::Bulk(bulk) => serialize_blob(b'$', bulk),
+            RESP3Type::Error(error) => format!("-{}\r\n", error).into_bytes(),
+            RESP3Type::Null => b"_\r\n".to_vec(),
+            RESP3Type::Double(double) => double.serialize_to_resp3(),
+            RESP3Type::Boolean(boolean) => boolean.serialize_to_resp3(),
+            RESP3Type::BigNumber(num) => format!("({}\r\n", num).into_bytes(),
+            RESP3Type::Verbatim(format, text) => {
+                serialize_blob(b'=', &[format.as_bytes(), b":", text].concat())
+            }
+            RESP3Type::Map(map) => map.serialize_to_resp3(),
+            RESP3Type::Set(set) => serialize_aggregate(b'~', set),
+            RESP3Type::Attribute(attributes, value) => {
+                let mut output = serialize_pairs(b'|', attributes);
+
+                output.extend(value.serialize_to_resp3());
+                output
+            }
+            RESP3Type::Push(push) => serialize_aggregate(b'>', push),
+        }
+    }
+}
+
+pub fn format_double(double: f64) -> String {
+    if double.is_nan() {
+        String::from("nan")
+    } else if double.is_infinite() {
+        String::from(if double > 0.0 { "inf" } else { "-inf" })
+    } else {
+        double.to_string()
+    }
+}
+
+fn serialize_blob(prefix: u8, data: &[u8]) -> Vec<u8> {
+    let mut output = format!("{}{}\r\n", prefix as char, data.len()).into_bytes();
+
+    output.extend_from_slice(data);
+    output.extend_from_slice(b"\r\n");
+    output
+}
+
+fn serialize_aggregate(prefix: u8, items: &[RESP3Type]) -> Vec<u8> {
+    let mut output = format!("{}{}\r\n", prefix as char, items.len()).into_bytes();
+
+    for item in items {
+        output.extend(item.serialize_to_resp3());
+    }
+
+    output
+}
+
+fn serialize_pairs(prefix: u8, pairs: &[(RESP3Type, RESP3Type)]) -> Vec<u8> {
+    let mut output = format!("{}{}\r\n", prefix as char, pairs.len()).into_bytes();
+
+    for (key, value) in pairs {
+        output.extend(key.serialize_to_resp3());
+        output.extend(value.serialize_to_resp3());
+    }
+
+    output
+}
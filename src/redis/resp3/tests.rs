@@ -0,0 +1,121 @@
+use crate::redis::{
+    reply::{Protocol, Reply},
+    resp3::{RESP3Type, Serialize},
+};
+
+#[test]
+fn resp3_serializer_null() {
+    let result = RESP3Type::Null.serialize_to_resp3();
+
+    assert_eq!(result, b"_\r\n");
+}
+
+#[test]
+fn resp3_serializer_double() {
+    assert_eq!(RESP3Type::Double(1.5).serialize_to_resp3(), b",1.5\r\n");
+    assert_eq!(RESP3Type::Double(3.0).serialize_to_resp3(), b",3\r\n");
+    assert_eq!(
+        RESP3Type::Double(f64::NEG_INFINITY).serialize_to_resp3(),
+        b",-inf\r\n"
+    );
+}
+
+#[test]
+fn resp3_serializer_boolean() {
+    assert_eq!(RESP3Type::Boolean(true).serialize_to_resp3(), b"#t\r\n");
+    assert_eq!(RESP3Type::Boolean(false).serialize_to_resp3(), b"#f\r\n");
+}
+
+#[test]
+fn resp3_serializer_big_number() {
+    let data = RESP3Type::BigNumber(String::from("3492890328409238509324850943850943825024385"));
+    let result = data.serialize_to_resp3();
+
+    assert_eq!(result, b"(3492890328409238509324850943850943825024385\r\n");
+}
+
+#[test]
+fn resp3_serializer_verbatim_string() {
+    let data = RESP3Type::Verbatim(String::from("txt"), b"Some string".to_vec());
+    let result = data.serialize_to_resp3();
+
+    assert_eq!(result, b"=15\r\ntxt:Some string\r\n");
+}
+
+#[test]
+fn resp3_serializer_map() {
+    let data = RESP3Type::Map(vec![
+        (RESP3Type::String(b"first".to_vec()), RESP3Type::Number(1)),
+        (RESP3Type::String(b"second".to_vec()), RESP3Type::Number(2)),
+    ]);
+    let result = data.serialize_to_resp3();
+
+    assert_eq!(result, b"%2\r\n+first\r\n:1\r\n+second\r\n:2\r\n");
+}
+
+#[test]
+fn resp3_serializer_set() {
+    let data = RESP3Type::Set(vec![
+        RESP3Type::Bulk(b"a".to_vec()),
+        RESP3Type::Bulk(b"b".to_vec()),
+    ]);
+    let result = data.serialize_to_resp3();
+
+    assert_eq!(result, b"~2\r\n$1\r\na\r\n$1\r\nb\r\n");
+}
+
+#[test]
+fn resp3_serializer_attribute() {
+    let data = RESP3Type::Attribute(
+        vec![(RESP3Type::String(b"ttl".to_vec()), RESP3Type::Number(3600))],
+        Box::new(RESP3Type::Bulk(b"value".to_vec())),
+    );
+    let result = data.serialize_to_resp3();
+
+    assert_eq!(result, b"|1\r\n+ttl\r\n:3600\r\n$5\r\nvalue\r\n");
+}
+
+#[test]
+fn resp3_serializer_push() {
+    let data = RESP3Type::Push(vec![
+        RESP3Type::Bulk(b"message".to_vec()),
+        RESP3Type::Bulk(b"news".to_vec()),
+    ]);
+    let result = data.serialize_to_resp3();
+
+    assert_eq!(result, b">2\r\n$7\r\nmessage\r\n$4\r\nnews\r\n");
+}
+
+#[test]
+fn reply_map_renders_per_protocol() {
+    let reply = Reply::Map(vec![(Reply::bulk("proto"), Reply::Integer(3))]);
+
+    assert_eq!(
+        reply.clone().serialize(Protocol::RESP2),
+        b"*2\r\n$5\r\nproto\r\n:3\r\n"
+    );
+    assert_eq!(
+        reply.serialize(Protocol::RESP3),
+        b"%1\r\n$5\r\nproto\r\n:3\r\n"
+    );
+}
+
+#[test]
+fn reply_null_renders_per_protocol() {
+    assert_eq!(Reply::Null.serialize(Protocol::RESP2), b"$-1\r\n");
+    assert_eq!(Reply::NullArray.serialize(Protocol::RESP2), b"*-1\r\n");
+    assert_eq!(Reply::NullArray.serialize(Protocol::RESP3), b"_\r\n");
+}
+
+#[test]
+fn reply_resp3_types_downgrade_to_resp2() {
+    assert_eq!(Reply::Boolean(true).serialize(Protocol::RESP2), b":1\r\n");
+    assert_eq!(
+        Reply::Double(2.5).serialize(Protocol::RESP2),
+        b"$3\r\n2.5\r\n"
+    );
+    assert_eq!(
+        Reply::Set(vec![Reply::bulk("a")]).serialize(Protocol::RESP2),
+        b"*1\r\n$1\r\na\r\n"
+    );
+}
@@ -0,0 +1,74 @@
+use bytes::{Buf, BytesMut};
+
+use super::{parser::Token, RESPv2Error, RESPv2Parser, RESPv2Type};
+
+struct PartialArray {
+    length: usize,
+    items: Vec<RESPv2Type>,
+}
+
+#[derive(Default)]
+pub struct RESPv2Decoder {
+    buffer: BytesMut,
+    pending: Vec<PartialArray>,
+}
+
+impl RESPv2Decoder {
+    pub fn new() -> Self {
+        Self {
+            buffer: BytesMut::with_capacity(4096),
+            pending: vec![],
+        }
+    }
+
+    pub fn feed(&mut self, data: &[u8]) {
+        self.buffer.extend_from_slice(data);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty() && self.pending.is_empty()
+    }
+
+    pub fn next_frame(&mut self) -> Result<Option<RESPv2Type>, RESPv2Error> {
+        while let Some((token, length)) =
+            RESPv2Parser::parse_token(&self.buffer, self.pending.len())?
+        {
+            self.buffer.advance(length);
+
+            let value = match token {
+                Token::Array(length) => {
+                    self.pending.push(PartialArray {
+                        length,
+                        items: Vec::with_capacity(length.min(1024)),
+                    });
+                    continue;
+                }
+                Token::Value(value) => value,
+            };
+
+            match self.complete(value) {
+                Some(RESPv2Type::Array(array)) if array.is_empty() => {}
+                Some(frame) => return Ok(Some(frame)),
+                None => {}
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn complete(&mut self, mut value: RESPv2Type) -> Option<RESPv2Type> {
+        while let Some(array) = self.pending.last_mut() {
+            array.items.push(value);
+
+            if array.items.len() < array.length {
+                return None;
+            }
+
+            let items = self.pending.pop().expect("array is pending").items;
+
+            value = RESPv2Type::Array(items.into_iter().map(Box::new).collect());
+        }
+
+        Some(value)
+    }
+}
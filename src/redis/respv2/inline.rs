@@ -0,0 +1,88 @@
+use super::RESPv2Error;
+
+pub fn split_inline_args(line: &[u8]) -> Result<Vec<Vec<u8>>, RESPv2Error> {
+    let mut args = vec![];
+    let mut chars = line.iter().copied().peekable();
+
+    loop {
+        while chars.next_if(u8::is_ascii_whitespace).is_some() {}
+
+        if chars.peek().is_none() {
+            return Ok(args);
+        }
+
+        let mut arg = vec![];
+        let mut in_double_quotes = false;
+        let mut in_single_quotes = false;
+
+        loop {
+            let char = chars.next();
+
+            if in_double_quotes {
+                match char {
+                    None => return Err(RESPv2Error::InvalidData),
+                    Some(b'\\') => match chars.next() {
+                        Some(b'x') => {
+                            let high = chars.next_if(u8::is_ascii_hexdigit);
+                            let low = chars.next_if(u8::is_ascii_hexdigit);
+
+                            match (high, low) {
+                                (Some(high), Some(low)) => arg.push(hex(high) * 16 + hex(low)),
+                                _ => return Err(RESPv2Error::InvalidData),
+                            }
+                        }
+                        Some(b'n') => arg.push(b'\n'),
+                        Some(b'r') => arg.push(b'\r'),
+                        Some(b't') => arg.push(b'\t'),
+                        Some(b'b') => arg.push(0x08),
+                        Some(b'a') => arg.push(0x07),
+                        Some(other) => arg.push(other),
+                        None => return Err(RESPv2Error::InvalidData),
+                    },
+                    Some(b'"') => {
+                        if chars.peek().is_some_and(|next| !next.is_ascii_whitespace()) {
+                            return Err(RESPv2Error::InvalidData);
+                        }
+
+                        break;
+                    }
+                    Some(other) => arg.push(other),
+                }
+            } else if in_single_quotes {
+                match char {
+                    None => return Err(RESPv2Error::InvalidData),
+                    Some(b'\\') if chars.peek() == Some(&b'\'') => {
+                        chars.next();
+                        arg.push(b'\'');
+                    }
+                    Some(b'\'') => {
+                        if chars.peek().is_some_and(|next| !next.is_ascii_whitespace()) {
+                            return Err(RESPv2Error::InvalidData);
+                        }
+
+                        break;
+                    }
+                    Some(other) => arg.push(other),
+                }
+            } else {
+                match char {
+                    None => break,
+                    Some(char) if char.is_ascii_whitespace() => break,
+                    Some(b'"') => in_double_quotes = true,
+                    Some(b'\'') => in_single_quotes = true,
+                    Some(other) => arg.push(other),
+                }
+            }
+        }
+
+        args.push(arg);
+    }
+}
+
+fn hex(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
@@ -1,4 +1,4 @@
-use super::{RESPv2Error, RESPv2Type};
+use super::{inline::split_inline_args, RESPv2Error, RESPv2Type};
 
 pub trait Parser {
     fn try_parse_to_respv2(&self) -> Result<RESPv2Type, RESPv2Error>;
@@ -6,155 +6,233 @@ pub trait Parser {
 
 impl Parser for &str {
     fn try_parse_to_respv2(&self) -> Result<RESPv2Type, RESPv2Error> {
-        RESPv2Parser::parse(self.to_string())
+        RESPv2Parser::parse(self)
     }
 }
 
 impl Parser for String {
     fn try_parse_to_respv2(&self) -> Result<RESPv2Type, RESPv2Error> {
-        RESPv2Parser::parse(self.to_string())
+        RESPv2Parser::parse(self)
     }
 }
 
 impl Parser for &String {
     fn try_parse_to_respv2(&self) -> Result<RESPv2Type, RESPv2Error> {
-        RESPv2Parser::parse(self.to_string())
+        RESPv2Parser::parse(self)
     }
 }
 
+impl Parser for &[u8] {
+    fn try_parse_to_respv2(&self) -> Result<RESPv2Type, RESPv2Error> {
+        RESPv2Parser::parse(self)
+    }
+}
+
+impl Parser for Vec<u8> {
+    fn try_parse_to_respv2(&self) -> Result<RESPv2Type, RESPv2Error> {
+        RESPv2Parser::parse(self)
+    }
+}
+
+const MAX_LINE_LENGTH: usize = 64 * 1024;
+const MAX_BULK_LENGTH: i64 = 512 * 1024 * 1024;
+const MAX_NESTING_DEPTH: usize = 32;
+
+pub enum Token {
+    Value(RESPv2Type),
+    Array(usize),
+}
+
 pub struct RESPv2Parser;
 
 impl RESPv2Parser {
-    pub fn parse(buffer: String) -> Result<RESPv2Type, RESPv2Error> {
-        let data = buffer.lines().collect::<Vec<&str>>();
-        let mut iterator = data.iter();
+    pub fn parse(buffer: impl AsRef<[u8]>) -> Result<RESPv2Type, RESPv2Error> {
+        let buffer = buffer.as_ref();
 
-        if data.len() == 0 || data[0].is_empty() {
+        if buffer.is_empty() || buffer[0] == b'\r' || buffer[0] == b'\n' {
             return Err(RESPv2Error::InvalidLength);
         }
 
-        let mut first_line = iterator.next().unwrap().chars();
+        match Self::parse_top_level(buffer) {
+            Ok((value, _)) => Ok(value),
+            Err(RESPv2Error::Incomplete) => Err(RESPv2Error::InvalidLength),
+            Err(e) => Err(e),
+        }
+    }
 
-        let operation = first_line.next().unwrap();
-        let first_line = first_line.collect::<String>();
+    pub fn parse_frame(buffer: &[u8]) -> Result<Option<(RESPv2Type, usize)>, RESPv2Error> {
+        match Self::parse_top_level(buffer) {
+            Ok(frame) => Ok(Some(frame)),
+            Err(RESPv2Error::Incomplete) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
 
-        match operation {
-            '+' | '-' => Self::parse_string(first_line.as_str()),
-            ':' => Self::parse_integer(first_line.as_str()),
-            '$' => {
-                if first_line.is_empty() {
-                    Err(RESPv2Error::InvalidData)
-                } else if first_line == "0" {
-                    Ok(RESPv2Type::String(String::from("")))
-                } else if first_line == "-1" {
-                    Ok(RESPv2Type::Null)
-                } else {
-                    Self::parse_string(iterator.next().unwrap())
-                }
-            }
-            '*' => {
-                if first_line.is_empty() || data.len() < 1 {
-                    Err(RESPv2Error::InvalidData)
-                } else if first_line == "0" {
-                    Ok(RESPv2Type::Array(vec![]))
-                } else if first_line == "-1" {
-                    Ok(RESPv2Type::Null)
-                } else {
-                    Self::parse_array(&mut iterator)
-                }
+    pub fn parse_token(buffer: &[u8], depth: usize) -> Result<Option<(Token, usize)>, RESPv2Error> {
+        let token = match buffer.first() {
+            Some(b'*') => Self::parse_array_header(buffer, depth),
+            Some(b'+' | b'-' | b':' | b'$') => Self::parse_value(buffer, 0, depth)
+                .map(|(value, length)| (Token::Value(value), length)),
+            Some(_) if depth == 0 => {
+                Self::parse_inline(buffer).map(|(value, length)| (Token::Value(value), length))
             }
+            Some(_) => Err(RESPv2Error::InvalidCommand),
+            None => Err(RESPv2Error::Incomplete),
+        };
+
+        match token {
+            Ok(token) => Ok(Some(token)),
+            Err(RESPv2Error::Incomplete) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn parse_top_level(buffer: &[u8]) -> Result<(RESPv2Type, usize), RESPv2Error> {
+        match buffer.first() {
+            Some(b'+' | b'-' | b':' | b'$' | b'*') => Self::parse_value(buffer, 0, 0),
+            Some(_) => Self::parse_inline(buffer),
+            None => Err(RESPv2Error::Incomplete),
+        }
+    }
+
+    fn parse_inline(buffer: &[u8]) -> Result<(RESPv2Type, usize), RESPv2Error> {
+        let end = match buffer.iter().position(|byte| *byte == b'\n') {
+            Some(end) => end,
+            None if buffer.len() > MAX_LINE_LENGTH => return Err(RESPv2Error::InvalidLength),
+            None => return Err(RESPv2Error::Incomplete),
+        };
+
+        let line = buffer[..end].strip_suffix(b"\r").unwrap_or(&buffer[..end]);
+        let array = split_inline_args(line)?
+            .into_iter()
+            .map(|arg| Box::new(RESPv2Type::String(arg)))
+            .collect();
+
+        Ok((RESPv2Type::Array(array), end + 1))
+    }
+
+    fn parse_value(
+        buffer: &[u8],
+        position: usize,
+        depth: usize,
+    ) -> Result<(RESPv2Type, usize), RESPv2Error> {
+        let operation = *buffer.get(position).ok_or(RESPv2Error::Incomplete)?;
+
+        if operation == b'*' && depth >= MAX_NESTING_DEPTH {
+            return Err(RESPv2Error::InvalidData);
+        }
+
+        let (line, position) = Self::read_line(buffer, position + 1)?;
+
+        match operation {
+            b'+' | b'-' => Ok((Self::parse_string(line), position)),
+            b':' => Ok((Self::parse_integer(line)?, position)),
+            b'$' => Self::parse_bulk(buffer, line, position),
+            b'*' => Self::parse_array(buffer, line, position, depth),
             _ => Err(RESPv2Error::InvalidCommand),
         }
     }
 
-    fn parse_string(data: &str) -> Result<RESPv2Type, RESPv2Error> {
-        Ok(RESPv2Type::String(data.to_string()))
+    fn parse_array_header(buffer: &[u8], depth: usize) -> Result<(Token, usize), RESPv2Error> {
+        if depth >= MAX_NESTING_DEPTH {
+            return Err(RESPv2Error::InvalidData);
+        }
+
+        let (line, position) = Self::read_line(buffer, 1)?;
+
+        match Self::parse_length(line)? {
+            -1 => Ok((Token::Value(RESPv2Type::Null), position)),
+            0 => Ok((Token::Value(RESPv2Type::Array(vec![])), position)),
+            length => Ok((Token::Array(length as usize), position)),
+        }
     }
 
-    fn parse_integer(data: &str) -> Result<RESPv2Type, RESPv2Error> {
-        match data.parse::<u64>() {
-            Ok(num) => Ok(RESPv2Type::Number(num)),
-            Err(_) => Err(RESPv2Error::InvalidType),
+    fn read_line(buffer: &[u8], position: usize) -> Result<(&[u8], usize), RESPv2Error> {
+        let rest = buffer.get(position..).ok_or(RESPv2Error::Incomplete)?;
+        let end = rest.windows(2).position(|window| window == b"\r\n");
+
+        let end = match end {
+            Some(end) => end,
+            None if rest.len() > MAX_LINE_LENGTH => return Err(RESPv2Error::InvalidLength),
+            None => return Err(RESPv2Error::Incomplete),
+        };
+
+        Ok((&rest[..end], position + end + 2))
+    }
+
+    fn parse_length(line: &[u8]) -> Result<i64, RESPv2Error> {
+        if line.is_empty() {
+            return Err(RESPv2Error::InvalidData);
         }
+
+        std::str::from_utf8(line)
+            .ok()
+            .and_then(|length| length.parse::<i64>().ok())
+            .filter(|length| *length >= -1)
+            .ok_or(RESPv2Error::InvalidData)
     }
 
-    fn parse_array(iterator: &mut std::slice::Iter<'_, &str>) -> Result<RESPv2Type, RESPv2Error> {
-        let mut error = false;
-        let mut error_type = RESPv2Error::InvalidType;
+    fn parse_string(data: &[u8]) -> RESPv2Type {
+        RESPv2Type::String(data.to_vec())
+    }
 
-        let mut array: Vec<Box<RESPv2Type>> = vec![];
+    fn parse_integer(data: &[u8]) -> Result<RESPv2Type, RESPv2Error> {
+        std::str::from_utf8(data)
+            .ok()
+            .and_then(|num| num.parse::<i64>().ok())
+            .map(RESPv2Type::Number)
+            .ok_or(RESPv2Error::InvalidType)
+    }
 
-        while let Some(line) = iterator.next() {
-            if error {
-                break;
-            }
+    fn parse_bulk(
+        buffer: &[u8],
+        line: &[u8],
+        position: usize,
+    ) -> Result<(RESPv2Type, usize), RESPv2Error> {
+        let length = Self::parse_length(line)?;
 
-            let mut chars = line.chars();
+        if length == -1 {
+            return Ok((RESPv2Type::Null, position));
+        }
 
-            let operation = chars.next().unwrap();
-            let num = chars.collect::<String>();
+        if length > MAX_BULK_LENGTH {
+            return Err(RESPv2Error::InvalidLength);
+        }
 
-            if num.is_empty() {
-                error = true;
-                error_type = RESPv2Error::InvalidData;
-            }
+        let end = position + length as usize;
 
-            match operation {
-                '+' | '-' => match Self::parse_string(num.as_str()) {
-                    Ok(data) => array.push(Box::new(data)),
-                    Err(e) => {
-                        error = true;
-                        error_type = e;
-                    }
-                },
-                ':' => match Self::parse_integer(num.as_str()) {
-                    Ok(data) => array.push(Box::new(data)),
-                    Err(e) => {
-                        error = true;
-                        error_type = e;
-                    }
-                },
-                '$' => {
-                    if num == "0" {
-                        array.push(Box::new(RESPv2Type::String(String::from(""))));
-                    } else if num == "-1" {
-                        array.push(Box::new(RESPv2Type::Null));
-                    } else {
-                        match Self::parse_string(iterator.next().unwrap()) {
-                            Ok(data) => array.push(Box::new(data)),
-                            Err(e) => {
-                                error = true;
-                                error_type = e;
-                            }
-                        }
-                    }
-                }
-                '*' => {
-                    if num == "0" {
-                        array.push(Box::new(RESPv2Type::Array(vec![])));
-                    } else if num == "-1" {
-                        array.push(Box::new(RESPv2Type::Null));
-                    } else {
-                        match Self::parse_array(iterator) {
-                            Ok(data) => array.push(Box::new(data)),
-                            Err(e) => {
-                                error = true;
-                                error_type = e;
-                            }
-                        }
-                    }
-                }
-                _ => {
-                    error = true;
-                    error_type = RESPv2Error::InvalidCommand;
-                }
-            }
+        if buffer.len() < end + 2 {
+            return Err(RESPv2Error::Incomplete);
+        }
+
+        if &buffer[end..end + 2] != b"\r\n" {
+            return Err(RESPv2Error::InvalidData);
+        }
+
+        Ok((Self::parse_string(&buffer[position..end]), end + 2))
+    }
+
+    fn parse_array(
+        buffer: &[u8],
+        line: &[u8],
+        mut position: usize,
+        depth: usize,
+    ) -> Result<(RESPv2Type, usize), RESPv2Error> {
+        let length = Self::parse_length(line)?;
+
+        if length == -1 {
+            return Ok((RESPv2Type::Null, position));
         }
 
-        if error {
-            Err(error_type)
-        } else {
-            Ok(RESPv2Type::Array(array))
+        let mut array: Vec<Box<RESPv2Type>> = Vec::with_capacity(length.min(1024) as usize);
+
+        for _ in 0..length {
+            let (value, next) = Self::parse_value(buffer, position, depth + 1)?;
+
+            array.push(Box::new(value));
+            position = next;
         }
+
+        Ok((RESPv2Type::Array(array), position))
     }
 }
@@ -1,13 +1,14 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
 
 #[derive(PartialEq, Eq, Debug)]
 pub enum RESPv2Type {
     Array(Vec<Box<RESPv2Type>>),
-    Number(u64),
-    String(String),
-    Bulk(String),
+    Number(i64),
+    String(Vec<u8>),
+    Bulk(Vec<u8>),
     Error(String),
     Null,
+    NullArray,
 }
 
 pub enum RESPv2Error {
@@ -15,21 +16,23 @@ pub enum RESPv2Error {
     InvalidData,
     InvalidLength,
     InvalidType,
+    Incomplete,
 }
 
 impl Debug for RESPv2Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.to_string())
+        write!(f, "{}", self)
     }
 }
 
-impl ToString for RESPv2Error {
-    fn to_string(&self) -> String {
+impl Display for RESPv2Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::InvalidCommand => String::from("InvalidCommand"),
-            Self::InvalidData => String::from("InvalidData"),
-            Self::InvalidLength => String::from("InvalidLength"),
-            Self::InvalidType => String::from("InvalidType"),
+            Self::InvalidCommand => write!(f, "InvalidCommand"),
+            Self::InvalidData => write!(f, "InvalidData"),
+            Self::InvalidLength => write!(f, "InvalidLength"),
+            Self::InvalidType => write!(f, "InvalidType"),
+            Self::Incomplete => write!(f, "Incomplete"),
         }
     }
 }
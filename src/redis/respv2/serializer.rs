@@ -1,114 +1,137 @@
 use super::RESPv2Type;
 
 pub trait Serialize {
-    fn serialize_to_respv2(&self) -> String;
+    fn serialize_to_respv2(&self) -> Vec<u8>;
 }
 
-impl Serialize for u64 {
-    fn serialize_to_respv2(&self) -> String {
-        format!(":{}\r\n", self)
+impl Serialize for i64 {
+    fn serialize_to_respv2(&self) -> Vec<u8> {
+        format!(":{}\r\n", self).into_bytes()
     }
 }
 
-impl Serialize for &u64 {
-    fn serialize_to_respv2(&self) -> String {
-        format!(":{}\r\n", self)
+impl Serialize for &i64 {
+    fn serialize_to_respv2(&self) -> Vec<u8> {
+        format!(":{}\r\n", self).into_bytes()
     }
 }
 
 impl Serialize for &str {
-    fn serialize_to_respv2(&self) -> String {
-        format!("+{}\r\n", self)
+    fn serialize_to_respv2(&self) -> Vec<u8> {
+        format!("+{}\r\n", self).into_bytes()
     }
 }
 
 impl Serialize for String {
-    fn serialize_to_respv2(&self) -> String {
-        format!("+{}\r\n", self)
+    fn serialize_to_respv2(&self) -> Vec<u8> {
+        format!("+{}\r\n", self).into_bytes()
     }
 }
 
 impl Serialize for &String {
-    fn serialize_to_respv2(&self) -> String {
-        format!("+{}\r\n", self)
+    fn serialize_to_respv2(&self) -> Vec<u8> {
+        format!("+{}\r\n", self).into_bytes()
+    }
+}
+
+impl Serialize for &[u8] {
+    fn serialize_to_respv2(&self) -> Vec<u8> {
+        [b"+", *self, b"\r\n"].concat()
+    }
+}
+
+impl Serialize for Vec<u8> {
+    fn serialize_to_respv2(&self) -> Vec<u8> {
+        self.as_slice().serialize_to_respv2()
     }
 }
 
 pub trait SerializeError {
-    fn serialize_error_to_respv2(&self) -> String;
+    fn serialize_error_to_respv2(&self) -> Vec<u8>;
 }
 
 impl SerializeError for &str {
-    fn serialize_error_to_respv2(&self) -> String {
-        format!("-{}\r\n", self)
+    fn serialize_error_to_respv2(&self) -> Vec<u8> {
+        format!("-{}\r\n", self).into_bytes()
     }
 }
 
 impl SerializeError for String {
-    fn serialize_error_to_respv2(&self) -> String {
-        format!("-{}\r\n", self)
+    fn serialize_error_to_respv2(&self) -> Vec<u8> {
+        format!("-{}\r\n", self).into_bytes()
     }
 }
 
 impl SerializeError for &String {
-    fn serialize_error_to_respv2(&self) -> String {
-        format!("-{}\r\n", self)
+    fn serialize_error_to_respv2(&self) -> Vec<u8> {
+        format!("-{}\r\n", self).into_bytes()
     }
 }
 
 pub trait SerializeBulk {
-    fn serialize_bulk_to_respv2(&self) -> String;
+    fn serialize_bulk_to_respv2(&self) -> Vec<u8>;
 }
 
 impl SerializeBulk for &str {
-    fn serialize_bulk_to_respv2(&self) -> String {
-        format!("${}\r\n{}\r\n", self.len(), self)
+    fn serialize_bulk_to_respv2(&self) -> Vec<u8> {
+        self.as_bytes().serialize_bulk_to_respv2()
     }
 }
 
 impl SerializeBulk for String {
-    fn serialize_bulk_to_respv2(&self) -> String {
-        format!("${}\r\n{}\r\n", self.len(), self)
+    fn serialize_bulk_to_respv2(&self) -> Vec<u8> {
+        self.as_bytes().serialize_bulk_to_respv2()
     }
 }
 
 impl SerializeBulk for &String {
-    fn serialize_bulk_to_respv2(&self) -> String {
-        format!("${}\r\n{}\r\n", self.len(), self)
+    fn serialize_bulk_to_respv2(&self) -> Vec<u8> {
+        self.as_bytes().serialize_bulk_to_respv2()
     }
 }
 
-impl SerializeBulk for u64 {
-    fn serialize_bulk_to_respv2(&self) -> String {
-        format!("${}\r\n{}\r\n", self.to_string().len(), self)
+impl SerializeBulk for &[u8] {
+    fn serialize_bulk_to_respv2(&self) -> Vec<u8> {
+        let mut output = format!("${}\r\n", self.len()).into_bytes();
+
+        output.extend_from_slice(self);
+        output.extend_from_slice(b"\r\n");
+        output
+    }
+}
+
+impl SerializeBulk for Vec<u8> {
+    fn serialize_bulk_to_respv2(&self) -> Vec<u8> {
+        self.as_slice().serialize_bulk_to_respv2()
+    }
+}
+
+impl SerializeBulk for i64 {
+    fn serialize_bulk_to_respv2(&self) -> Vec<u8> {
+        self.to_string().serialize_bulk_to_respv2()
     }
 }
 
 impl Serialize for Vec<Box<RESPv2Type>> {
-    fn serialize_to_respv2(&self) -> String {
-        if self.len() == 0 {
-            return String::from("*0\r\n");
-        } else if self.len() == 1 {
-            return format!("*1\r\n{}", self[0].serialize_to_respv2());
-        } else {
-            return format!(
-                "*{}\r\n{}",
-                self.len(),
-                self.iter()
-                    .map(|x| x.serialize_to_respv2())
-                    .collect::<String>()
-            );
+    fn serialize_to_respv2(&self) -> Vec<u8> {
+        let mut output = format!("*{}\r\n", self.len()).into_bytes();
+
+        for item in self {
+            output.extend(item.serialize_to_respv2());
         }
+
+        output
     }
 }
 
 impl Serialize for RESPv2Type {
-    fn serialize_to_respv2(&self) -> String {
+    fn serialize_to_respv2(&self) -> Vec<u8> {
         match self {
             RESPv2Type::Number(num) => num.serialize_to_respv2(),
             RESPv2Type::String(string) => string.serialize_to_respv2(),
             RESPv2Type::Error(error) => error.serialize_error_to_respv2(),
-            RESPv2Type::Null => String::from("$-1\r\n"),
+            RESPv2Type::Null => b"$-1\r\n".to_vec(),
+            RESPv2Type::NullArray => b"*-1\r\n".to_vec(),
             RESPv2Type::Bulk(bulk) => bulk.serialize_bulk_to_respv2(),
             RESPv2Type::Array(array) => array.serialize_to_respv2(),
         }
@@ -1,11 +1,13 @@
-use crate::redis::respv2::{Parser, RESPv2Parser, RESPv2Type, Serialize, SerializeBulk, SerializeError};
+use crate::redis::respv2::{
+    Parser, RESPv2Decoder, RESPv2Parser, RESPv2Type, Serialize, SerializeBulk, SerializeError,
+};
 
 #[test]
 fn respv2_parser_string() {
     let data = String::from("+OK\r\n");
     let result = data.try_parse_to_respv2().unwrap();
 
-    assert_eq!(result, RESPv2Type::String(String::from("OK")));
+    assert_eq!(result, RESPv2Type::String(b"OK".to_vec()));
 }
 
 #[test]
@@ -21,10 +23,7 @@ fn respv2_parser_error() {
     let data = String::from("-ERR unknown command\r\n");
     let result = RESPv2Parser::parse(data).unwrap();
 
-    assert_eq!(
-        result,
-        RESPv2Type::String(String::from("ERR unknown command"))
-    );
+    assert_eq!(result, RESPv2Type::String(b"ERR unknown command".to_vec()));
 }
 
 #[test]
@@ -32,7 +31,7 @@ fn respv2_parser_bulk_string() {
     let data = String::from("$6\r\nfoobar\r\n");
     let result = RESPv2Parser::parse(data).unwrap();
 
-    assert_eq!(result, RESPv2Type::String(String::from("foobar")));
+    assert_eq!(result, RESPv2Type::String(b"foobar".to_vec()));
 }
 
 #[test]
@@ -48,7 +47,7 @@ fn respv2_parser_empty_bulk_string() {
     let data = String::from("$0\r\n\r\n");
     let result = RESPv2Parser::parse(data).unwrap();
 
-    assert_eq!(result, RESPv2Type::String(String::from("")));
+    assert_eq!(result, RESPv2Type::String(b"".to_vec()));
 }
 
 #[test]
@@ -59,8 +58,8 @@ fn respv2_parser_array() {
     assert_eq!(
         result,
         RESPv2Type::Array(vec![
-            Box::new(RESPv2Type::String(String::from("foo"))),
-            Box::new(RESPv2Type::String(String::from("bar"))),
+            Box::new(RESPv2Type::String(b"foo".to_vec())),
+            Box::new(RESPv2Type::String(b"bar".to_vec())),
         ])
     );
 }
@@ -89,9 +88,23 @@ fn respv2_parser_invalid_data() {
     assert!(result.is_err() && result.unwrap_err().to_string() == "InvalidData");
 }
 
+#[test]
+fn respv2_parser_rejects_deep_nesting() {
+    let nested = "*1\r\n".repeat(32) + "$1\r\na\r\n";
+    let too_deep = "*1\r\n".repeat(200_000);
+    let mut decoder = RESPv2Decoder::new();
+
+    assert!(RESPv2Parser::parse(&nested).is_ok());
+    assert!(RESPv2Parser::parse(format!("*1\r\n{}", nested)).is_err());
+    assert!(RESPv2Parser::parse_frame(too_deep.as_bytes()).is_err());
+
+    decoder.feed(too_deep.as_bytes());
+    assert!(decoder.next_frame().is_err());
+}
+
 #[test]
 fn respv2_parser_invalid_command() {
-    let data = String::from("!\r\n");
+    let data = String::from("*1\r\n!\r\n");
     let result = RESPv2Parser::parse(data);
 
     assert!(result.is_err() && result.unwrap_err().to_string() == "InvalidCommand");
@@ -102,7 +115,7 @@ fn respv2_serializer_string() {
     let data = String::from("OK");
     let result = data.serialize_to_respv2();
 
-    assert_eq!(result, "+OK\r\n");
+    assert_eq!(result, b"+OK\r\n");
 }
 
 #[test]
@@ -110,7 +123,7 @@ fn respv2_serializer_integer() {
     let data = 1000;
     let result = data.serialize_to_respv2();
 
-    assert_eq!(result, ":1000\r\n");
+    assert_eq!(result, b":1000\r\n");
 }
 
 #[test]
@@ -118,7 +131,7 @@ fn respv2_serializer_error() {
     let data = String::from("ERR unknown command");
     let result = data.serialize_error_to_respv2();
 
-    assert_eq!(result, "-ERR unknown command\r\n");
+    assert_eq!(result, b"-ERR unknown command\r\n");
 }
 
 #[test]
@@ -126,7 +139,7 @@ fn respv2_serializer_bulk_string() {
     let data = String::from("foobar");
     let result = data.serialize_bulk_to_respv2();
 
-    assert_eq!(result, "$6\r\nfoobar\r\n");
+    assert_eq!(result, b"$6\r\nfoobar\r\n");
 }
 
 #[test]
@@ -134,24 +147,256 @@ fn respv2_serializer_null_bulk_string() {
     let data = String::from("");
     let result = data.serialize_bulk_to_respv2();
 
-    assert_eq!(result, "$0\r\n\r\n");
+    assert_eq!(result, b"$0\r\n\r\n");
 }
 
 #[test]
 fn respv2_serializer_array() {
     let data = vec![
-        Box::new(RESPv2Type::Bulk(String::from("foo"))),
-        Box::new(RESPv2Type::Bulk(String::from("bar"))),
+        Box::new(RESPv2Type::Bulk(b"foo".to_vec())),
+        Box::new(RESPv2Type::Bulk(b"bar".to_vec())),
     ];
     let result = data.serialize_to_respv2();
 
-    assert_eq!(result, "*2\r\n$3\r\nfoo\r\n$3\r\nbar\r\n");
+    assert_eq!(result, b"*2\r\n$3\r\nfoo\r\n$3\r\nbar\r\n");
 }
 
 #[test]
 fn respv2_serializer_null_array() {
-    let data = vec![];
+    let data: Vec<Box<RESPv2Type>> = vec![];
     let result = data.serialize_to_respv2();
 
-    assert_eq!(result, "*0\r\n");
+    assert_eq!(result, b"*0\r\n");
+}
+
+#[test]
+fn respv2_parser_binary_bulk_string() {
+    let data = b"$8\r\nfoo\r\n\xff\0b\r\n".to_vec();
+    let result = RESPv2Parser::parse(data).unwrap();
+
+    assert_eq!(result, RESPv2Type::String(b"foo\r\n\xff\0b".to_vec()));
+}
+
+#[test]
+fn respv2_parser_array_with_binary_bulk_strings() {
+    let data = b"*3\r\n$3\r\nSET\r\n$1\r\n\n\r\n$4\r\n\r\n\r\n\r\n".to_vec();
+    let result = RESPv2Parser::parse(data).unwrap();
+
+    assert_eq!(
+        result,
+        RESPv2Type::Array(vec![
+            Box::new(RESPv2Type::String(b"SET".to_vec())),
+            Box::new(RESPv2Type::String(b"\n".to_vec())),
+            Box::new(RESPv2Type::String(b"\r\n\r\n".to_vec())),
+        ])
+    );
+}
+
+#[test]
+fn respv2_parser_short_bulk_string() {
+    let data = String::from("$6\r\nfoo\r\n");
+    let result = RESPv2Parser::parse(data);
+
+    assert!(result.is_err() && result.unwrap_err().to_string() == "InvalidLength");
+}
+
+#[test]
+fn respv2_serializer_binary_bulk_string() {
+    let data = b"\xff\r\n".to_vec();
+    let result = data.serialize_bulk_to_respv2();
+
+    assert_eq!(result, b"$3\r\n\xff\r\n\r\n");
+}
+
+#[test]
+fn respv2_decoder_partial_frame() {
+    let mut decoder = RESPv2Decoder::new();
+
+    decoder.feed(b"*2\r\n$3\r\nGET\r\n$5\r\nhe");
+    assert_eq!(decoder.next_frame().unwrap(), None);
+
+    decoder.feed(b"llo\r\n");
+    assert_eq!(
+        decoder.next_frame().unwrap(),
+        Some(RESPv2Type::Array(vec![
+            Box::new(RESPv2Type::String(b"GET".to_vec())),
+            Box::new(RESPv2Type::String(b"hello".to_vec())),
+        ]))
+    );
+    assert!(decoder.is_empty());
+}
+
+#[test]
+fn respv2_decoder_multiple_frames() {
+    let mut decoder = RESPv2Decoder::new();
+
+    decoder.feed(b"*1\r\n$4\r\nPING\r\n*2\r\n$4\r\nECHO\r\n$2\r\nhi\r\n*1\r\n$4\r\nPI");
+
+    assert_eq!(
+        decoder.next_frame().unwrap(),
+        Some(RESPv2Type::Array(vec![Box::new(RESPv2Type::String(
+            b"PING".to_vec()
+        ))]))
+    );
+    assert_eq!(
+        decoder.next_frame().unwrap(),
+        Some(RESPv2Type::Array(vec![
+            Box::new(RESPv2Type::String(b"ECHO".to_vec())),
+            Box::new(RESPv2Type::String(b"hi".to_vec())),
+        ]))
+    );
+    assert_eq!(decoder.next_frame().unwrap(), None);
+    assert!(!decoder.is_empty());
+}
+
+#[test]
+fn respv2_decoder_large_bulk_string() {
+    let mut decoder = RESPv2Decoder::new();
+    let value = vec![b'x'; 10_000];
+
+    decoder.feed(b"$10000\r\n");
+    for chunk in value.chunks(1000) {
+        assert_eq!(decoder.next_frame().unwrap(), None);
+        decoder.feed(chunk);
+    }
+    decoder.feed(b"\r\n");
+
+    assert_eq!(
+        decoder.next_frame().unwrap(),
+        Some(RESPv2Type::String(value))
+    );
+}
+
+#[test]
+fn respv2_decoder_byte_at_a_time() {
+    let mut decoder = RESPv2Decoder::new();
+    let data = b"*3\r\n$3\r\nSET\r\n*2\r\n:1\r\n$-1\r\n+ok\r\n*0\r\n*1\r\n$4\r\nPING\r\n";
+    let mut frames = vec![];
+
+    for byte in data {
+        decoder.feed(&[*byte]);
+
+        while let Some(frame) = decoder.next_frame().unwrap() {
+            frames.push(frame);
+        }
+    }
+
+    assert_eq!(
+        frames,
+        vec![
+            RESPv2Type::Array(vec![
+                Box::new(RESPv2Type::String(b"SET".to_vec())),
+                Box::new(RESPv2Type::Array(vec![
+                    Box::new(RESPv2Type::Number(1)),
+                    Box::new(RESPv2Type::Null),
+                ])),
+                Box::new(RESPv2Type::String(b"ok".to_vec())),
+            ]),
+            RESPv2Type::Array(vec![Box::new(RESPv2Type::String(b"PING".to_vec()))]),
+        ]
+    );
+    assert!(decoder.is_empty());
+}
+
+#[test]
+fn respv2_decoder_many_arguments() {
+    let mut decoder = RESPv2Decoder::new();
+    let count = 100_000;
+
+    decoder.feed(format!("*{count}\r\n").as_bytes());
+
+    for _ in 1..count {
+        decoder.feed(b"$1\r\nx\r\n");
+        assert_eq!(decoder.next_frame().unwrap(), None);
+        assert!(!decoder.is_empty());
+    }
+
+    decoder.feed(b"$1\r\nx\r\n*1\r\n$4\r\nPING\r\n");
+
+    match decoder.next_frame().unwrap() {
+        Some(RESPv2Type::Array(array)) => assert_eq!(array.len(), count),
+        frame => panic!("unexpected frame {frame:?}"),
+    }
+    assert!(decoder.next_frame().unwrap().is_some());
+}
+
+#[test]
+fn respv2_decoder_invalid_frame() {
+    let mut decoder = RESPv2Decoder::new();
+
+    decoder.feed(b"$abc\r\n");
+
+    assert!(decoder.next_frame().is_err());
+}
+
+#[test]
+fn respv2_parser_inline_command() {
+    let data = String::from("SET foo bar\r\n");
+    let result = RESPv2Parser::parse(data).unwrap();
+
+    assert_eq!(
+        result,
+        RESPv2Type::Array(vec![
+            Box::new(RESPv2Type::String(b"SET".to_vec())),
+            Box::new(RESPv2Type::String(b"foo".to_vec())),
+            Box::new(RESPv2Type::String(b"bar".to_vec())),
+        ])
+    );
+}
+
+#[test]
+fn respv2_parser_inline_command_with_quotes() {
+    let data = String::from("set \"hello world\" 'it\\'s' \"\\x41\\n\"\n");
+    let result = RESPv2Parser::parse(data).unwrap();
+
+    assert_eq!(
+        result,
+        RESPv2Type::Array(vec![
+            Box::new(RESPv2Type::String(b"set".to_vec())),
+            Box::new(RESPv2Type::String(b"hello world".to_vec())),
+            Box::new(RESPv2Type::String(b"it's".to_vec())),
+            Box::new(RESPv2Type::String(b"A\n".to_vec())),
+        ])
+    );
+}
+
+#[test]
+fn respv2_parser_inline_unbalanced_quotes() {
+    let data = String::from("SET \"foo bar\r\n");
+    let result = RESPv2Parser::parse(data);
+
+    assert!(result.is_err() && result.unwrap_err().to_string() == "InvalidData");
+
+    let data = String::from("SET \"foo\"bar\r\n");
+    let result = RESPv2Parser::parse(data);
+
+    assert!(result.is_err() && result.unwrap_err().to_string() == "InvalidData");
+}
+
+#[test]
+fn respv2_decoder_inline_commands() {
+    let mut decoder = RESPv2Decoder::new();
+
+    decoder.feed(b"\r\nPING\r\n\nECHO hi\n*1\r\n$4\r\nPING\r\nGET ");
+
+    assert_eq!(
+        decoder.next_frame().unwrap(),
+        Some(RESPv2Type::Array(vec![Box::new(RESPv2Type::String(
+            b"PING".to_vec()
+        ))]))
+    );
+    assert_eq!(
+        decoder.next_frame().unwrap(),
+        Some(RESPv2Type::Array(vec![
+            Box::new(RESPv2Type::String(b"ECHO".to_vec())),
+            Box::new(RESPv2Type::String(b"hi".to_vec())),
+        ]))
+    );
+    assert_eq!(
+        decoder.next_frame().unwrap(),
+        Some(RESPv2Type::Array(vec![Box::new(RESPv2Type::String(
+            b"PING".to_vec()
+        ))]))
+    );
+    assert_eq!(decoder.next_frame().unwrap(), None);
 }
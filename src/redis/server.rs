@@ -1,65 +1,242 @@
 use super::{
-    cmd::{cmd_echo, cmd_get, cmd_set},
-    db::MemoryDatabase, respv2::{Parser, RESPv2Type, Serialize},
+    aof,
+    blocking::BlockingRegistry,
+    client::Client,
+    cmd::transaction::unwatch_all,
+    command::{lookup_command, unknown_command, CommandContext},
+    config::Config,
+    db::MemoryDatabase,
+    persistence::Persistence,
+    pubsub::PubSubHub,
+    reply::{Protocol, Reply},
+    respv2::RESPv2Type,
 };
 
 use std::{
+    fs::{self, OpenOptions},
     io::{Error, ErrorKind},
     sync::Arc,
 };
 use tokio::sync::Mutex;
 
-pub struct Redis;
+pub struct Redis<D: MemoryDatabase> {
+    pub db: Arc<Mutex<D>>,
+    pub blocking: BlockingRegistry,
+    pub pubsub: PubSubHub,
+    pub persistence: Persistence,
+}
+
+const SUBSCRIBED_MODE_COMMANDS: &[&str] = &[
+    "subscribe",
+    "psubscribe",
+    "ssubscribe",
+    "unsubscribe",
+    "punsubscribe",
+    "sunsubscribe",
+    "ping",
+];
 
-type PeekableBoxes<'a> = std::iter::Peekable<std::slice::Iter<'a, Box<RESPv2Type>>>;
+const TRANSACTION_COMMANDS: &[&str] = &["multi", "exec", "discard", "watch"];
+
+impl<D: MemoryDatabase> Redis<D> {
+    pub fn new(db: D) -> Self {
+        Self::with_config(db, Config::default())
+    }
 
-impl Redis {
-    pub async fn handle(
-        buffer: String,
-        db: Arc<Mutex<impl MemoryDatabase>>,
-    ) -> Result<String, Error> {
-        if buffer.is_empty() {
-            return Err(Error::new(ErrorKind::InvalidData, "Empty buffer received."));
+    pub fn with_config(db: D, config: Config) -> Self {
+        let persistence = Persistence::new(config, db.dirty());
+
+        Self {
+            db: Arc::new(Mutex::new(db)),
+            blocking: BlockingRegistry::new(),
+            pubsub: PubSubHub::new(),
+            persistence,
         }
+    }
 
-        let parse_result = buffer.try_parse_to_respv2();
+    pub async fn handle_pipeline(&self, frames: Vec<RESPv2Type>, client: &mut Client) -> Vec<u8> {
+        let mut response = Vec::new();
 
-        if parse_result.is_err() {
-            return Err(Error::new(
-                ErrorKind::InvalidData,
-                parse_result.unwrap_err().to_string(),
-            ));
+        for frame in frames {
+            while let Some(message) = client.try_next_message() {
+                response.extend(message.serialize(client.protocol));
+            }
+
+            let reply = match self.handle(frame, client).await {
+                Ok(reply) => reply,
+                Err(e) => Reply::from_error(e),
+            };
+
+            response.extend(reply.serialize(client.protocol));
+
+            while let Some(message) = client.try_next_message() {
+                response.extend(message.serialize(client.protocol));
+            }
+        }
+
+        response
+    }
+
+    pub async fn disconnect(&self, client: &mut Client) {
+        self.pubsub.disconnect(client);
+        unwatch_all(client, &mut *self.db.lock().await);
+    }
+
+    pub async fn load_aof(&self) -> Result<usize, Error> {
+        let path = self.persistence.config().aof_path();
+        let data = match fs::read(&path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(0),
+            Err(err) => return Err(err),
+        };
+
+        let (commands, valid_len) = aof::parse(&data)?;
+
+        if valid_len < data.len() {
+            eprintln!(
+                "!!! Warning: short read while loading the AOF file {}, truncating it to {} bytes",
+                path.display(),
+                valid_len
+            );
+            OpenOptions::new()
+                .write(true)
+                .open(&path)?
+                .set_len(valid_len as u64)?;
         }
 
-        if let RESPv2Type::Array(vec) = parse_result.unwrap() {
-            let mut itr = vec.iter().peekable();
+        let mut db = self.db.lock().await;
+        let mut client = Client::new();
 
-            while let Some(type_box) = itr.next() {
-                if let RESPv2Type::String(data) = type_box.as_ref() {
-                    return Self::command_handler(data, &mut itr, db).await;
+        for args in &commands {
+            let command = lookup_command(&args[0]).ok_or_else(|| unknown_command(args))?;
+
+            command.check_arity(args.len())?;
+
+            if let Some(transaction) = client.transaction.as_mut() {
+                if !TRANSACTION_COMMANDS.contains(&command.name) {
+                    transaction.commands.push(args.clone());
+                    continue;
                 }
             }
+
+            let mut ctx = CommandContext {
+                args,
+                db: &mut *db,
+                client: &mut client,
+                pubsub: &self.pubsub,
+                persistence: &self.persistence,
+                ready_keys: vec![],
+                block: None,
+                propagate: None,
+            };
+
+            if let Err(err) = (command.handler)(&mut ctx) {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Error replaying '{}' from the AOF: {}",
+                        String::from_utf8_lossy(&args[0]),
+                        err.to_string().trim_start_matches('-')
+                    ),
+                ));
+            }
         }
 
-        Err(Error::new(
-            ErrorKind::InvalidData,
-            "Invalid RESPv2 data received.",
-        ))
+        self.persistence.state().dirty_at_save = db.dirty();
+
+        Ok(commands.len())
+    }
+
+    pub async fn handle(&self, frame: RESPv2Type, client: &mut Client) -> Result<Reply, Error> {
+        let args = Self::frame_to_args(frame)?;
+
+        self.command_handler(args, client).await
+    }
+
+    fn frame_to_args(frame: RESPv2Type) -> Result<Vec<Vec<u8>>, Error> {
+        let invalid = || Error::new(ErrorKind::InvalidData, "Invalid RESPv2 data received.");
+
+        match frame {
+            RESPv2Type::Array(vec) if !vec.is_empty() => vec
+                .into_iter()
+                .map(|arg| match *arg {
+                    RESPv2Type::String(arg) | RESPv2Type::Bulk(arg) => Ok(arg),
+                    _ => Err(invalid()),
+                })
+                .collect(),
+            _ => Err(invalid()),
+        }
     }
 
     async fn command_handler(
-        data: &String,
-        itr: &mut PeekableBoxes<'_>,
-        db: Arc<Mutex<impl MemoryDatabase>>,
-    ) -> Result<String, Error> {
-        match data.to_lowercase().as_str() {
-            "ping" => {
-                return Ok("PONG".serialize_to_respv2());
+        &self,
+        args: Vec<Vec<u8>>,
+        client: &mut Client,
+    ) -> Result<Reply, Error> {
+        let Some(command) = lookup_command(&args[0]) else {
+            client.abort_transaction();
+            return Err(unknown_command(&args));
+        };
+
+        if let Err(err) = command.check_arity(args.len()) {
+            client.abort_transaction();
+            return Err(err);
+        }
+
+        if client.protocol == Protocol::RESP2
+            && client.subscription_count() > 0
+            && !SUBSCRIBED_MODE_COMMANDS.contains(&command.name)
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Can't execute '{}': only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE / PING / QUIT / RESET are allowed in this context",
+                    command.name
+                ),
+            ));
+        }
+
+        if let Some(transaction) = client.transaction.as_mut() {
+            if !TRANSACTION_COMMANDS.contains(&command.name) {
+                transaction.commands.push(args);
+                return Ok(Reply::Simple("QUEUED".to_string()));
             }
-            "echo" => cmd_echo(itr.next()),
-            "set" => cmd_set(itr.next(), itr.next(), &db).await,
-            "get" => cmd_get(itr.next(), &db).await,
-            _ => return Err(Error::new(ErrorKind::InvalidData, "Invalid command.")),
         }
+
+        let mut db = self.db.lock().await;
+        let dirty = db.dirty();
+        let mut ctx = CommandContext {
+            args: &args,
+            db: &mut *db,
+            client,
+            pubsub: &self.pubsub,
+            persistence: &self.persistence,
+            ready_keys: vec![],
+            block: None,
+            propagate: None,
+        };
+
+        let reply = (command.handler)(&mut ctx);
+        let mut propagated = match reply.is_ok() && ctx.db.dirty() != dirty {
+            true => ctx.propagated(command),
+            false => vec![],
+        };
+        let (ready_keys, block) = (ctx.ready_keys, ctx.block);
+
+        propagated.extend(self.blocking.serve(ready_keys, &mut *db));
+        self.persistence.append(&propagated);
+
+        let Some(request) = block else {
+            return reply;
+        };
+
+        let (id, receiver) = self.blocking.register(&request);
+
+        drop(db);
+
+        Ok(self
+            .blocking
+            .wait(id, receiver, request.timeout, &client.disconnected)
+            .await)
     }
 }
@@ -0,0 +1,1945 @@
+use crate::{
+    mem_db::MemDB,
+    redis::{
+        aof,
+        client::Client,
+        command::{lookup_command, COMMAND_TABLE},
+        config::{parse_save_rules, AppendFsync, Config},
+        db::{self, MemoryDatabase},
+        expire::{active_expire_cycle, ACTIVE_EXPIRE_CYCLE_ACCEPTABLE_STALE},
+        pubsub::glob_match,
+        rdb,
+        reply::Protocol,
+        respv2::{RESPv2Decoder, RESPv2Type},
+        server::Redis,
+        types::{set::SET_MAX_INTSET_ENTRIES, ScoreBound, Value, ZSet},
+    },
+};
+use std::{collections::VecDeque, sync::Arc, time::Duration};
+use tokio::task::JoinHandle;
+
+async fn execute(redis: &Redis<MemDB>, client: &mut Client, command: &str) -> Vec<u8> {
+    let frame = RESPv2Type::Array(
+        command
+            .split_whitespace()
+            .map(|arg| Box::new(RESPv2Type::String(arg.as_bytes().to_vec())))
+            .collect(),
+    );
+
+    redis.handle_pipeline(vec![frame], client).await
+}
+
+#[tokio::test]
+async fn server_pipeline_replies_in_order() {
+    let redis = Redis::new(MemDB::new());
+    let mut decoder = RESPv2Decoder::new();
+    let mut frames = vec![];
+
+    decoder.feed(b"*3\r\n$3\r\nSET\r\n$1\r\nk\r\n$1\r\nv\r\n*1\r\n$4\r\nNOPE\r\n");
+    decoder.feed(b"*2\r\n$3\r\nGET\r\n$1\r\nk\r\n*1\r\n$4\r\nPING\r\n");
+
+    while let Some(frame) = decoder.next_frame().unwrap() {
+        frames.push(frame);
+    }
+
+    let response = redis.handle_pipeline(frames, &mut Client::new()).await;
+
+    assert_eq!(
+        response,
+        b"+OK\r\n-ERR unknown command 'NOPE', with args beginning with: \r\n$1\r\nv\r\n+PONG\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_hello_switches_protocol() {
+    let redis = Redis::new(MemDB::new());
+    let mut client = Client::new();
+    let mut decoder = RESPv2Decoder::new();
+
+    decoder.feed(b"*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n");
+    let frame = decoder.next_frame().unwrap().unwrap();
+
+    let response = redis.handle_pipeline(vec![frame], &mut client).await;
+
+    assert_eq!(client.protocol, Protocol::RESP3);
+    assert!(response.starts_with(b"%7\r\n$6\r\nserver\r\n$5\r\nredis\r\n"));
+
+    decoder.feed(b"*2\r\n$5\r\nHELLO\r\n$1\r\n4\r\n");
+    let frame = decoder.next_frame().unwrap().unwrap();
+
+    let response = redis.handle_pipeline(vec![frame], &mut client).await;
+
+    assert_eq!(response, b"-NOPROTO unsupported protocol version\r\n");
+    assert_eq!(client.protocol, Protocol::RESP3);
+
+    let client = &mut Client::new();
+
+    for version in ["1", "0", "-3"] {
+        assert_eq!(
+            execute(&redis, client, &format!("HELLO {version}")).await,
+            b"-NOPROTO unsupported protocol version\r\n"
+        );
+    }
+    assert_eq!(client.protocol, Protocol::RESP2);
+}
+
+#[tokio::test]
+async fn server_set_with_expiry() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(execute(&redis, client, "SET k v PX 50").await, b"+OK\r\n");
+    assert_eq!(execute(&redis, client, "GET k").await, b"$1\r\nv\r\n");
+    assert_eq!(execute(&redis, client, "PTTL k").await[0], b':');
+
+    tokio::time::sleep(Duration::from_millis(80)).await;
+
+    assert_eq!(execute(&redis, client, "GET k").await, b"$-1\r\n");
+    assert_eq!(execute(&redis, client, "TTL k").await, b":-2\r\n");
+    assert_eq!(
+        execute(&redis, client, "SET k v EX 0").await,
+        b"-ERR invalid expire time in 'set' command\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "SET k v EX 10 PX 100").await,
+        b"-ERR syntax error\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_expire_ttl_persist() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(execute(&redis, client, "EXPIRE k 100").await, b":0\r\n");
+    execute(&redis, client, "SET k v").await;
+    assert_eq!(execute(&redis, client, "TTL k").await, b":-1\r\n");
+    assert_eq!(execute(&redis, client, "EXPIRE k 100 XX").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "EXPIRE k 100").await, b":1\r\n");
+    assert_eq!(execute(&redis, client, "TTL k").await, b":100\r\n");
+    assert_eq!(execute(&redis, client, "EXPIRE k 50 GT").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "EXPIRE k 50 LT").await, b":1\r\n");
+    assert_eq!(execute(&redis, client, "PERSIST k").await, b":1\r\n");
+    assert_eq!(execute(&redis, client, "PERSIST k").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "EXPIRETIME k").await, b":-1\r\n");
+    assert_eq!(execute(&redis, client, "PEXPIREAT k 1000").await, b":1\r\n");
+    assert_eq!(execute(&redis, client, "GET k").await, b"$-1\r\n");
+}
+
+#[tokio::test]
+async fn server_active_expire_cycle_evicts_unread_keys() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    for i in 0..200 {
+        execute(&redis, client, &format!("SET short{} v PX 10", i)).await;
+    }
+    for i in 0..10 {
+        execute(&redis, client, &format!("SET long{} v EX 100", i)).await;
+    }
+    execute(&redis, client, "SET forever v").await;
+
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    active_expire_cycle(&redis.db, Duration::from_secs(1)).await;
+
+    let mut db = redis.db.lock().await;
+
+    assert!(db.len() <= 11 + 200 * ACTIVE_EXPIRE_CYCLE_ACCEPTABLE_STALE / 100);
+    assert!(db.expire_stats().expired_keys > 0);
+    assert_eq!(db.expire_stats().active_expire_cycles, 1);
+}
+
+#[tokio::test]
+async fn server_set_conditional_options() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(execute(&redis, client, "SET lock a XX").await, b"$-1\r\n");
+    assert_eq!(
+        execute(&redis, client, "SET lock a NX PX 30000").await,
+        b"+OK\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "SET lock b NX PX 30000").await,
+        b"$-1\r\n"
+    );
+    assert_eq!(execute(&redis, client, "GET lock").await, b"$1\r\na\r\n");
+    assert_eq!(
+        execute(&redis, client, "SET lock c XX GET KEEPTTL").await,
+        b"$1\r\na\r\n"
+    );
+    assert_ne!(execute(&redis, client, "TTL lock").await, b":-1\r\n");
+    assert_eq!(
+        execute(&redis, client, "SET lock d GET").await,
+        b"$1\r\nc\r\n"
+    );
+    assert_eq!(execute(&redis, client, "TTL lock").await, b":-1\r\n");
+    assert_eq!(
+        execute(&redis, client, "SET new e NX GET").await,
+        b"$-1\r\n"
+    );
+    assert_eq!(execute(&redis, client, "GET new").await, b"$1\r\ne\r\n");
+}
+
+#[tokio::test]
+async fn server_set_conflicting_options() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    for command in [
+        "SET k v NX XX",
+        "SET k v XX NX",
+        "SET k v EX 10 KEEPTTL",
+        "SET k v KEEPTTL PX 10",
+        "SET k v EX",
+        "SET k v FOO",
+    ] {
+        assert_eq!(
+            execute(&redis, client, command).await,
+            b"-ERR syntax error\r\n"
+        );
+    }
+
+    assert_eq!(execute(&redis, client, "GET k").await, b"$-1\r\n");
+}
+
+#[tokio::test]
+async fn server_typed_values() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    redis
+        .db
+        .lock()
+        .await
+        .set(b"list", Value::List(VecDeque::from([b"a".to_vec()])), None)
+        .unwrap();
+    execute(&redis, client, "SET string v").await;
+
+    assert_eq!(execute(&redis, client, "TYPE list").await, b"+list\r\n");
+    assert_eq!(execute(&redis, client, "TYPE string").await, b"+string\r\n");
+    assert_eq!(execute(&redis, client, "TYPE missing").await, b"+none\r\n");
+    assert_eq!(
+        execute(&redis, client, "GET list").await,
+        b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "SET list v GET").await,
+        b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "EXISTS list string missing").await,
+        b":2\r\n"
+    );
+    assert_eq!(execute(&redis, client, "SET list v").await, b"+OK\r\n");
+    assert_eq!(
+        execute(&redis, client, "DEL list string missing").await,
+        b":2\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_command_table_arity_and_introspection() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(
+        execute(&redis, client, "GET").await,
+        b"-ERR wrong number of arguments for 'get' command\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "GET a b").await,
+        b"-ERR wrong number of arguments for 'get' command\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "SET k").await,
+        b"-ERR wrong number of arguments for 'set' command\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "FOO bar").await,
+        b"-ERR unknown command 'FOO', with args beginning with: 'bar' \r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "COMMAND COUNT").await,
+        format!(":{}\r\n", COMMAND_TABLE.len()).as_bytes()
+    );
+    assert_eq!(
+        execute(&redis, client, "COMMAND INFO get nope").await,
+        b"*2\r\n*10\r\n$3\r\nget\r\n:2\r\n*2\r\n+readonly\r\n+fast\r\n:1\r\n:1\r\n:1\r\n*3\r\n+@read\r\n+@string\r\n+@fast\r\n*0\r\n*0\r\n*0\r\n*-1\r\n"
+    );
+    assert!(execute(&redis, client, "COMMAND DOCS set")
+        .await
+        .starts_with(b"*2\r\n$3\r\nset\r\n*6\r\n$7\r\nsummary\r\n"));
+}
+
+#[tokio::test]
+async fn server_list_push_pop_range() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(execute(&redis, client, "RPUSH l a b c").await, b":3\r\n");
+    assert_eq!(execute(&redis, client, "LPUSH l z").await, b":4\r\n");
+    assert_eq!(
+        execute(&redis, client, "LRANGE l 0 -1").await,
+        b"*4\r\n$1\r\nz\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nc\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "LRANGE l -100 1").await,
+        b"*2\r\n$1\r\nz\r\n$1\r\na\r\n"
+    );
+    assert_eq!(execute(&redis, client, "LRANGE l 5 10").await, b"*0\r\n");
+    assert_eq!(execute(&redis, client, "LINDEX l -1").await, b"$1\r\nc\r\n");
+    assert_eq!(execute(&redis, client, "LINDEX l 4").await, b"$-1\r\n");
+    assert_eq!(
+        execute(&redis, client, "LSET l 4 x").await,
+        b"-ERR index out of range\r\n"
+    );
+    assert_eq!(execute(&redis, client, "LPOP l").await, b"$1\r\nz\r\n");
+    assert_eq!(
+        execute(&redis, client, "RPOP l 2").await,
+        b"*2\r\n$1\r\nc\r\n$1\r\nb\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "RPOP l 5").await,
+        b"*1\r\n$1\r\na\r\n"
+    );
+    assert_eq!(execute(&redis, client, "EXISTS l").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "LPOP l 1").await, b"*-1\r\n");
+    assert_eq!(execute(&redis, client, "LPUSHX l a").await, b":0\r\n");
+}
+
+#[tokio::test]
+async fn server_list_edit_commands() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "RPUSH l a b a c a").await;
+
+    assert_eq!(execute(&redis, client, "LPOS l a").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "LPOS l a RANK -1").await, b":4\r\n");
+    assert_eq!(
+        execute(&redis, client, "LPOS l a COUNT 0").await,
+        b"*3\r\n:0\r\n:2\r\n:4\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "LPOS l a RANK 2 COUNT 1 MAXLEN 3").await,
+        b"*1\r\n:2\r\n"
+    );
+    assert_eq!(execute(&redis, client, "LREM l -2 a").await, b":2\r\n");
+    assert_eq!(
+        execute(&redis, client, "LINSERT l BEFORE c x").await,
+        b":4\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "LINSERT l AFTER q x").await,
+        b":-1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "LRANGE l 0 -1").await,
+        b"*4\r\n$1\r\na\r\n$1\r\nb\r\n$1\r\nx\r\n$1\r\nc\r\n"
+    );
+    assert_eq!(execute(&redis, client, "LTRIM l 1 -2").await, b"+OK\r\n");
+    assert_eq!(execute(&redis, client, "LLEN l").await, b":2\r\n");
+    assert_eq!(
+        execute(&redis, client, "LMOVE l other LEFT RIGHT").await,
+        b"$1\r\nb\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "RPOPLPUSH l other").await,
+        b"$1\r\nx\r\n"
+    );
+    assert_eq!(execute(&redis, client, "EXISTS l").await, b":0\r\n");
+    assert_eq!(
+        execute(&redis, client, "LRANGE other 0 -1").await,
+        b"*2\r\n$1\r\nx\r\n$1\r\nb\r\n"
+    );
+    execute(&redis, client, "SET s v").await;
+    assert_eq!(
+        execute(&redis, client, "LMOVE other s LEFT LEFT").await,
+        b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+    );
+    assert_eq!(execute(&redis, client, "LLEN other").await, b":2\r\n");
+}
+
+async fn spawn_blocked(redis: &Arc<Redis<MemDB>>, command: &'static str) -> JoinHandle<Vec<u8>> {
+    let blocked = redis.blocking.blocked_clients();
+    let handle = tokio::spawn({
+        let redis = Arc::clone(redis);
+        async move { execute(&redis, &mut Client::new(), command).await }
+    });
+
+    while redis.blocking.blocked_clients() == blocked {
+        tokio::task::yield_now().await;
+    }
+
+    handle
+}
+
+#[tokio::test]
+async fn server_blocking_pop_and_timeout() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "RPUSH b x").await;
+
+    assert_eq!(
+        execute(&redis, client, "BLPOP a b 0").await,
+        b"*2\r\n$1\r\nb\r\n$1\r\nx\r\n"
+    );
+    assert_eq!(execute(&redis, client, "BRPOP a b 0.05").await, b"*-1\r\n");
+    assert_eq!(
+        execute(&redis, client, "BLMOVE a b LEFT LEFT 0.01").await,
+        b"*-1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "BLPOP a -1").await,
+        b"-ERR timeout is negative\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "BLPOP a x").await,
+        b"-ERR timeout is not a float or out of range\r\n"
+    );
+    assert_eq!(redis.blocking.blocked_clients(), 0);
+}
+
+#[tokio::test]
+async fn server_blocking_wakes_in_fifo_order() {
+    let redis = Arc::new(Redis::new(MemDB::new()));
+    let client = &mut Client::new();
+
+    let first = spawn_blocked(&redis, "BLPOP q other 0").await;
+    let second = spawn_blocked(&redis, "BRPOP q 0").await;
+
+    assert_eq!(execute(&redis, client, "RPUSH q a b c").await, b":3\r\n");
+    assert_eq!(first.await.unwrap(), b"*2\r\n$1\r\nq\r\n$1\r\na\r\n");
+    assert_eq!(second.await.unwrap(), b"*2\r\n$1\r\nq\r\n$1\r\nc\r\n");
+    assert_eq!(
+        execute(&redis, client, "LRANGE q 0 -1").await,
+        b"*1\r\n$1\r\nb\r\n"
+    );
+    assert_eq!(redis.blocking.blocked_clients(), 0);
+}
+
+#[tokio::test]
+async fn server_blocking_client_disconnect() {
+    let redis = Arc::new(Redis::new(MemDB::new()));
+    let client = &mut Client::new();
+    let mut blocked = Client::new();
+    let disconnected = Arc::clone(&blocked.disconnected);
+
+    let handle = tokio::spawn({
+        let redis = Arc::clone(&redis);
+        async move { execute(&redis, &mut blocked, "BLPOP q 0").await }
+    });
+
+    while redis.blocking.blocked_clients() == 0 {
+        tokio::task::yield_now().await;
+    }
+
+    disconnected.notify_one();
+    handle.await.unwrap();
+
+    assert_eq!(redis.blocking.blocked_clients(), 0);
+    assert_eq!(execute(&redis, client, "RPUSH q x").await, b":1\r\n");
+    assert_eq!(
+        execute(&redis, client, "LRANGE q 0 -1").await,
+        b"*1\r\n$1\r\nx\r\n"
+    );
+
+    let aborted = spawn_blocked(&redis, "BLPOP other 0").await;
+
+    aborted.abort();
+    assert!(aborted.await.unwrap_err().is_cancelled());
+    assert_eq!(redis.blocking.blocked_clients(), 0);
+    assert_eq!(execute(&redis, client, "RPUSH other y").await, b":1\r\n");
+    assert_eq!(execute(&redis, client, "LLEN other").await, b":1\r\n");
+}
+
+#[tokio::test]
+async fn server_blocking_move_chains_to_waiters() {
+    let redis = Arc::new(Redis::new(MemDB::new()));
+    let client = &mut Client::new();
+
+    let mover = spawn_blocked(&redis, "BLMOVE src dst RIGHT LEFT 0").await;
+    let popper = spawn_blocked(&redis, "BLMPOP 0 2 nope dst LEFT COUNT 5").await;
+
+    execute(&redis, client, "LPUSH src job").await;
+
+    assert_eq!(mover.await.unwrap(), b"$3\r\njob\r\n");
+    assert_eq!(
+        popper.await.unwrap(),
+        b"*2\r\n$3\r\ndst\r\n*1\r\n$3\r\njob\r\n"
+    );
+    assert_eq!(execute(&redis, client, "EXISTS src dst").await, b":0\r\n");
+}
+
+#[tokio::test]
+async fn server_lmpop() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "RPUSH b 1 2 3").await;
+
+    assert_eq!(
+        execute(&redis, client, "LMPOP 2 a b RIGHT COUNT 2").await,
+        b"*2\r\n$1\r\nb\r\n*2\r\n$1\r\n3\r\n$1\r\n2\r\n"
+    );
+    assert_eq!(execute(&redis, client, "LMPOP 1 a LEFT").await, b"*-1\r\n");
+    assert_eq!(
+        execute(&redis, client, "LMPOP 0 a LEFT").await,
+        b"-ERR numkeys should be greater than 0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "LMPOP 3 a LEFT").await,
+        b"-ERR Number of keys can't be greater than number of args\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "LMPOP 1 b LEFT COUNT 0").await,
+        b"-ERR count should be greater than 0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "LMPOP 1 b UP").await,
+        b"-ERR syntax error\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_hash_commands() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(execute(&redis, client, "HSET h a 1 b 2").await, b":2\r\n");
+    assert_eq!(execute(&redis, client, "HSET h a 3 c 4").await, b":1\r\n");
+    assert_eq!(
+        execute(&redis, client, "HSET h a").await,
+        b"-ERR wrong number of arguments for 'hset' command\r\n"
+    );
+    assert_eq!(execute(&redis, client, "HSETNX h a 9").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "HGET h a").await, b"$1\r\n3\r\n");
+    assert_eq!(
+        execute(&redis, client, "HMGET h a nope").await,
+        b"*2\r\n$1\r\n3\r\n$-1\r\n"
+    );
+    assert_eq!(execute(&redis, client, "HLEN h").await, b":3\r\n");
+    assert_eq!(execute(&redis, client, "HEXISTS h b").await, b":1\r\n");
+    assert_eq!(execute(&redis, client, "HSTRLEN h b").await, b":1\r\n");
+    assert_eq!(execute(&redis, client, "HINCRBY h a 10").await, b":13\r\n");
+    assert_eq!(
+        execute(&redis, client, "HINCRBYFLOAT h f 1.5").await,
+        b"$3\r\n1.5\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "HINCRBY h f 1").await,
+        b"-ERR hash value is not an integer\r\n"
+    );
+    assert_eq!(execute(&redis, client, "HDEL h a b c f x").await, b":4\r\n");
+    assert_eq!(execute(&redis, client, "EXISTS h").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "HRANDFIELD h").await, b"$-1\r\n");
+    assert_eq!(execute(&redis, client, "HRANDFIELD h 3").await, b"*0\r\n");
+    assert_eq!(
+        execute(&redis, client, "HINCRBYFLOAT h f inf").await,
+        b"-ERR increment would produce NaN or Infinity\r\n"
+    );
+    assert_eq!(execute(&redis, client, "EXISTS h").await, b":0\r\n");
+}
+
+#[tokio::test]
+async fn server_hash_replies_per_protocol() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "HSET h f v").await;
+
+    assert_eq!(
+        execute(&redis, client, "HGETALL h").await,
+        b"*2\r\n$1\r\nf\r\n$1\r\nv\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "HRANDFIELD h -2 WITHVALUES").await,
+        b"*4\r\n$1\r\nf\r\n$1\r\nv\r\n$1\r\nf\r\n$1\r\nv\r\n"
+    );
+
+    execute(&redis, client, "HELLO 3").await;
+
+    assert_eq!(
+        execute(&redis, client, "HGETALL h").await,
+        b"%1\r\n$1\r\nf\r\n$1\r\nv\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "HRANDFIELD h 5 WITHVALUES").await,
+        b"*1\r\n*2\r\n$1\r\nf\r\n$1\r\nv\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_hrandfield_count_limits() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "HSET h f v").await;
+
+    assert_eq!(
+        execute(&redis, client, "HRANDFIELD h -9223372036854775808").await,
+        b"-ERR value is out of range\r\n"
+    );
+    assert_eq!(
+        execute(
+            &redis,
+            client,
+            "HRANDFIELD h -9223372036854775807 WITHVALUES"
+        )
+        .await,
+        b"-ERR value is out of range\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "HRANDFIELD h 9223372036854775807").await,
+        b"*1\r\n$1\r\nf\r\n"
+    );
+
+    let reply = execute(&redis, client, "HRANDFIELD h -100000").await;
+
+    assert_eq!(reply[..9], *b"*100000\r\n");
+    assert_eq!(reply.len(), 9 + 100000 * 7);
+}
+
+#[tokio::test]
+async fn server_set_commands() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(execute(&redis, client, "SADD a 3 1 2 2").await, b":3\r\n");
+    assert_eq!(execute(&redis, client, "SADD b 2 3 x").await, b":3\r\n");
+    assert_eq!(execute(&redis, client, "SCARD a").await, b":3\r\n");
+    assert_eq!(
+        execute(&redis, client, "SMEMBERS a").await,
+        b"*3\r\n$1\r\n1\r\n$1\r\n2\r\n$1\r\n3\r\n"
+    );
+    assert_eq!(execute(&redis, client, "SISMEMBER b x").await, b":1\r\n");
+    assert_eq!(
+        execute(&redis, client, "SMISMEMBER a 1 x").await,
+        b"*2\r\n:1\r\n:0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "SINTERSTORE i a b").await,
+        b":2\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "SUNIONSTORE u a b").await,
+        b":4\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "SDIFF a b").await,
+        b"*1\r\n$1\r\n1\r\n"
+    );
+    assert_eq!(execute(&redis, client, "SDIFFSTORE d a a").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "EXISTS d").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "SINTER a missing").await, b"*0\r\n");
+    assert_eq!(
+        execute(&redis, client, "SINTERCARD 2 a b LIMIT 1").await,
+        b":1\r\n"
+    );
+    assert_eq!(execute(&redis, client, "SINTERCARD 2 a b").await, b":2\r\n");
+    assert_eq!(
+        execute(&redis, client, "SINTERCARD 3 a b").await,
+        b"-ERR Number of keys can't be greater than number of args\r\n"
+    );
+    assert_eq!(execute(&redis, client, "SMOVE b a x").await, b":1\r\n");
+    assert_eq!(execute(&redis, client, "SREM a 1 2 3 x").await, b":4\r\n");
+    assert_eq!(execute(&redis, client, "EXISTS a").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "SPOP b 5").await[..4], *b"*2\r\n");
+    assert_eq!(execute(&redis, client, "EXISTS b").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "SPOP b").await, b"$-1\r\n");
+    assert_eq!(
+        execute(&redis, client, "SRANDMEMBER u -3").await[..4],
+        *b"*3\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "SPOP u -1").await,
+        b"-ERR value is out of range, must be positive\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_set_random_sampling() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+    let distinct = |reply: Vec<u8>| {
+        let mut members: Vec<Vec<u8>> = reply
+            .split(|byte| *byte == b'\n')
+            .filter(|line| line.starts_with(b"m"))
+            .map(|line| line.to_vec())
+            .collect();
+        let length = members.len();
+
+        members.sort();
+        members.dedup();
+        (length, members.len())
+    };
+
+    execute(&redis, client, "SADD s m1 m2 m3 m4 m5 m6 m7 m8").await;
+
+    assert_eq!(
+        execute(&redis, client, "SRANDMEMBER s -9223372036854775808").await,
+        b"-ERR value is out of range\r\n"
+    );
+    assert_eq!(
+        distinct(execute(&redis, client, "SRANDMEMBER s 5").await),
+        (5, 5)
+    );
+    assert_eq!(
+        distinct(execute(&redis, client, "SRANDMEMBER s 9223372036854775807").await),
+        (8, 8)
+    );
+    assert_eq!(
+        distinct(execute(&redis, client, "SRANDMEMBER s -100000").await),
+        (100000, 8)
+    );
+    assert_eq!(execute(&redis, client, "SRANDMEMBER s 0").await, b"*0\r\n");
+    assert_eq!(execute(&redis, client, "SRANDMEMBER x -5").await, b"*0\r\n");
+    assert_eq!(execute(&redis, client, "SRANDMEMBER x").await, b"$-1\r\n");
+    assert_eq!(distinct(execute(&redis, client, "SPOP s 3").await), (3, 3));
+    assert_eq!(execute(&redis, client, "SCARD s").await, b":5\r\n");
+    assert_eq!(
+        distinct(execute(&redis, client, "SPOP s 9223372036854775807").await),
+        (5, 5)
+    );
+    assert_eq!(execute(&redis, client, "EXISTS s").await, b":0\r\n");
+}
+
+#[tokio::test]
+async fn server_set_intset_encoding() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+    let encoding = |redis: &Redis<MemDB>, key: &'static [u8]| {
+        let db = Arc::clone(&redis.db);
+
+        async move {
+            match db.lock().await.get_set(key) {
+                Ok(Some(set)) => set.encoding(),
+                _ => "none",
+            }
+        }
+    };
+
+    execute(&redis, client, "SADD ints 1 -5 300").await;
+    execute(&redis, client, "SADD padded 01").await;
+
+    assert_eq!(encoding(&redis, b"ints").await, "intset");
+    assert_eq!(encoding(&redis, b"padded").await, "hashtable");
+
+    execute(&redis, client, "SADD ints x").await;
+
+    assert_eq!(encoding(&redis, b"ints").await, "hashtable");
+    assert_eq!(
+        execute(&redis, client, "SISMEMBER ints -5").await,
+        b":1\r\n"
+    );
+
+    for i in 0..SET_MAX_INTSET_ENTRIES {
+        execute(&redis, client, &format!("SADD big {}", i)).await;
+    }
+
+    assert_eq!(encoding(&redis, b"big").await, "intset");
+
+    execute(&redis, client, "SADD big -1").await;
+
+    assert_eq!(encoding(&redis, b"big").await, "hashtable");
+    assert_eq!(execute(&redis, client, "SCARD big").await, b":513\r\n");
+}
+
+#[tokio::test]
+async fn server_zset_commands() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(
+        execute(&redis, client, "ZADD z 1 a 2 b 3 c 3 d").await,
+        b":4\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZADD z NX 9 a 4 e").await,
+        b":1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZADD z XX CH 5 a 1 x").await,
+        b":1\r\n"
+    );
+    assert_eq!(execute(&redis, client, "ZADD z GT CH 1 a").await, b":0\r\n");
+    assert_eq!(
+        execute(&redis, client, "ZADD z LT INCR -1 a").await,
+        b"$1\r\n4\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZADD z GT INCR -1 a").await,
+        b"$-1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZADD z NX XX 1 a").await,
+        b"-ERR XX and NX options at the same time are not compatible\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZADD z GT LT 1 a").await,
+        b"-ERR GT, LT, and/or NX options at the same time are not compatible\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZADD z INCR 1 a 2 b").await,
+        b"-ERR INCR option supports a single increment-element pair\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZINCRBY z 0.5 b").await,
+        b"$3\r\n2.5\r\n"
+    );
+    assert_eq!(execute(&redis, client, "ZSCORE z nope").await, b"$-1\r\n");
+    assert_eq!(execute(&redis, client, "ZCARD z").await, b":5\r\n");
+    assert_eq!(execute(&redis, client, "ZRANK z b").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "ZREVRANK z b").await, b":4\r\n");
+    assert_eq!(
+        execute(&redis, client, "ZRANK z e WITHSCORE").await,
+        b"*2\r\n:4\r\n$1\r\n4\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZCOUNT z (2.5 +inf").await,
+        b":4\r\n"
+    );
+    assert_eq!(execute(&redis, client, "ZREM z a e nope").await, b":2\r\n");
+    assert_eq!(
+        execute(&redis, client, "ZRANGE z 0 -1 WITHSCORES").await,
+        b"*6\r\n$1\r\nb\r\n$3\r\n2.5\r\n$1\r\nc\r\n$1\r\n3\r\n$1\r\nd\r\n$1\r\n3\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZRANGE z 0 0 REV").await,
+        b"*1\r\n$1\r\nd\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZRANGE z 0 -1 LIMIT 0 1").await,
+        b"-ERR syntax error, LIMIT is only supported in combination with either BYSCORE or BYLEX\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZRANGE z a b BYSCORE").await,
+        b"-ERR min or max is not a float\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_zset_ranges_and_pops() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "ZADD s 1 one 2 two 3 three 4 four").await;
+    execute(&redis, client, "ZADD l 0 a 0 b 0 c 0 d").await;
+
+    assert_eq!(
+        execute(&redis, client, "ZRANGE s (1 3 BYSCORE").await,
+        b"*2\r\n$3\r\ntwo\r\n$5\r\nthree\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZRANGE s +inf -inf BYSCORE REV LIMIT 1 2").await,
+        b"*2\r\n$5\r\nthree\r\n$3\r\ntwo\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZRANGEBYSCORE s -inf (2 WITHSCORES").await,
+        b"*2\r\n$3\r\none\r\n$1\r\n1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZREVRANGEBYSCORE s 3 2").await,
+        b"*2\r\n$5\r\nthree\r\n$3\r\ntwo\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZREVRANGE s 0 1").await,
+        b"*2\r\n$4\r\nfour\r\n$5\r\nthree\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZRANGEBYLEX l [b (d").await,
+        b"*2\r\n$1\r\nb\r\n$1\r\nc\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZRANGE l + - BYLEX REV LIMIT 0 1").await,
+        b"*1\r\n$1\r\nd\r\n"
+    );
+    assert_eq!(execute(&redis, client, "ZLEXCOUNT l - +").await, b":4\r\n");
+    assert_eq!(
+        execute(&redis, client, "ZRANGEBYLEX l b c").await,
+        b"-ERR min or max not valid string range item\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZPOPMIN s").await,
+        b"*2\r\n$3\r\none\r\n$1\r\n1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZPOPMAX s 2").await,
+        b"*4\r\n$4\r\nfour\r\n$1\r\n4\r\n$5\r\nthree\r\n$1\r\n3\r\n"
+    );
+
+    execute(&redis, client, "HELLO 3").await;
+
+    assert_eq!(
+        execute(&redis, client, "ZPOPMAX s 5").await,
+        b"*1\r\n*2\r\n$3\r\ntwo\r\n,2\r\n"
+    );
+    assert_eq!(execute(&redis, client, "EXISTS s").await, b":0\r\n");
+}
+
+#[tokio::test]
+async fn server_zset_score_formatting() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(
+        &redis,
+        client,
+        "ZADD f 0.1 a 1e20 b 1.5e-5 c -2.5 d 123456789012345678 e 3 g",
+    )
+    .await;
+
+    for (member, score) in [
+        ("a", "0.10000000000000001"),
+        ("b", "1e+20"),
+        ("c", "1.5e-05"),
+        ("d", "-2.5"),
+        ("e", "1.2345678901234568e+17"),
+        ("g", "3"),
+    ] {
+        assert_eq!(
+            execute(&redis, client, &format!("ZSCORE f {member}")).await,
+            format!("${}\r\n{score}\r\n", score.len()).as_bytes()
+        );
+    }
+
+    assert_eq!(
+        execute(&redis, client, "ZINCRBY f 0.2 a").await,
+        b"$19\r\n0.30000000000000004\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_zset_store_operations() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "ZADD a 1 x 2 y").await;
+    execute(&redis, client, "ZADD b 10 y 20 z").await;
+    execute(&redis, client, "SADD c y z").await;
+
+    assert_eq!(
+        execute(&redis, client, "ZUNIONSTORE u 2 a b WEIGHTS 2 1").await,
+        b":3\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZRANGE u 0 -1 WITHSCORES").await,
+        b"*6\r\n$1\r\nx\r\n$1\r\n2\r\n$1\r\ny\r\n$2\r\n14\r\n$1\r\nz\r\n$2\r\n20\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZINTERSTORE i 3 a b c AGGREGATE MAX").await,
+        b":1\r\n"
+    );
+    assert_eq!(execute(&redis, client, "ZSCORE i y").await, b"$2\r\n10\r\n");
+    assert_eq!(
+        execute(&redis, client, "ZINTERSTORE i 2 a missing").await,
+        b":0\r\n"
+    );
+    assert_eq!(execute(&redis, client, "EXISTS i").await, b":0\r\n");
+    assert_eq!(
+        execute(&redis, client, "ZUNIONSTORE u 0 a").await,
+        b"-ERR at least 1 input key is needed for 'zunionstore' command\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "ZUNIONSTORE u 1 a WEIGHTS x").await,
+        b"-ERR weight value is not a float\r\n"
+    );
+
+    let args: Vec<Vec<u8>> = "ZINTERSTORE i 2 a b WEIGHTS 2 1"
+        .split_whitespace()
+        .map(|arg| arg.as_bytes().to_vec())
+        .collect();
+
+    assert_eq!(
+        lookup_command(b"zinterstore").unwrap().keys(&args),
+        vec![&b"i"[..], b"a", b"b"]
+    );
+    assert!(execute(&redis, client, "COMMAND INFO zunionstore")
+        .await
+        .starts_with(b"*1\r\n*10\r\n$11\r\nzunionstore\r\n:-4\r\n*3\r\n+write\r\n+denyoom\r\n+movablekeys\r\n:1\r\n:1\r\n:1\r\n"));
+}
+
+#[tokio::test]
+async fn server_stream_add_and_ids() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(
+        execute(&redis, client, "XADD s 5-1 a 1").await,
+        b"$3\r\n5-1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XADD s 5-* b 2").await,
+        b"$3\r\n5-2\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XADD s 6-* c 3").await,
+        b"$3\r\n6-0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XADD s 6-0 d 4").await,
+        b"-ERR The ID specified in XADD is equal or smaller than the target stream top item\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XADD s 4-* d 4").await,
+        b"-ERR The ID specified in XADD is equal or smaller than the target stream top item\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XADD t 0-0 a 1").await,
+        b"-ERR The ID specified in XADD must be greater than 0-0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XADD t 1-x a 1").await,
+        b"-ERR Invalid stream ID specified as stream command argument\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XADD t * a").await,
+        b"-ERR wrong number of arguments for 'xadd' command\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XADD t NOMKSTREAM * a 1").await,
+        b"$-1\r\n"
+    );
+    assert_eq!(execute(&redis, client, "EXISTS t").await, b":0\r\n");
+    assert_eq!(execute(&redis, client, "XLEN s").await, b":3\r\n");
+    assert_eq!(execute(&redis, client, "XLEN t").await, b":0\r\n");
+
+    let auto = execute(&redis, client, "XADD s * e 5").await;
+    assert!(auto.ends_with(b"-0\r\n"));
+
+    assert_eq!(execute(&redis, client, "SET k v").await, b"+OK\r\n");
+    assert_eq!(
+        execute(&redis, client, "XADD k * a 1").await,
+        b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_stream_ranges_and_trimming() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    for i in 1..=5 {
+        execute(&redis, client, &format!("XADD s {}-0 f {}", i, i)).await;
+    }
+
+    assert_eq!(
+        execute(&redis, client, "XRANGE s 2 (4-0").await,
+        b"*2\r\n*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nf\r\n$1\r\n2\r\n*2\r\n$3\r\n3-0\r\n*2\r\n$1\r\nf\r\n$1\r\n3\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREVRANGE s + - COUNT 1").await,
+        b"*1\r\n*2\r\n$3\r\n5-0\r\n*2\r\n$1\r\nf\r\n$1\r\n5\r\n"
+    );
+    assert_eq!(execute(&redis, client, "XRANGE s 4 2").await, b"*0\r\n");
+    assert_eq!(
+        execute(&redis, client, "XRANGE s - + LIMIT 1").await,
+        b"-ERR syntax error\r\n"
+    );
+    assert_eq!(execute(&redis, client, "XDEL s 2-0 9-0").await, b":1\r\n");
+    assert_eq!(execute(&redis, client, "XTRIM s MINID 4").await, b":2\r\n");
+    assert_eq!(execute(&redis, client, "XLEN s").await, b":2\r\n");
+    assert_eq!(
+        execute(&redis, client, "XTRIM s MAXLEN 0 LIMIT 1").await,
+        b"-ERR syntax error, LIMIT cannot be used without the special ~ option\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XTRIM s MAXLEN -1").await,
+        b"-ERR The MAXLEN argument must be >= 0.\r\n"
+    );
+
+    for i in 10..260 {
+        execute(&redis, client, &format!("XADD s {}-0 f {}", i, i)).await;
+    }
+
+    assert_eq!(
+        execute(&redis, client, "XTRIM s MAXLEN ~ 10").await,
+        b":200\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XTRIM s MAXLEN ~ 10 LIMIT 20").await,
+        b":0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XADD s MAXLEN = 3 300-0 f x").await,
+        b"$5\r\n300-0\r\n"
+    );
+    assert_eq!(execute(&redis, client, "XLEN s").await, b":3\r\n");
+}
+
+#[tokio::test]
+async fn server_stream_read() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "XADD a 1-0 f 1").await;
+    execute(&redis, client, "XADD a 2-0 f 2").await;
+    execute(&redis, client, "XADD b 1-0 g 1").await;
+
+    assert_eq!(
+        execute(&redis, client, "XREAD COUNT 1 STREAMS a b 0 1").await,
+        b"*1\r\n*2\r\n$1\r\na\r\n*1\r\n*2\r\n$3\r\n1-0\r\n*2\r\n$1\r\nf\r\n$1\r\n1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREAD STREAMS a b $ $").await,
+        b"*-1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREAD BLOCK 10 STREAMS a missing 2-0 $").await,
+        b"*-1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREAD STREAMS a b 0").await,
+        b"-ERR Unbalanced 'xread' list of streams: for each stream key an ID or '$' must be specified.\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREAD COUNT 1 a 0").await,
+        b"-ERR syntax error\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREAD COUNT -5 STREAMS a 0").await,
+        b"-ERR value is out of range\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREAD BLOCK -1 STREAMS a 0").await,
+        b"-ERR timeout is negative\r\n"
+    );
+
+    execute(&redis, client, "HELLO 3").await;
+
+    assert_eq!(
+        execute(&redis, client, "XREAD STREAMS a b 1 1").await,
+        b"%1\r\n$1\r\na\r\n*1\r\n*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nf\r\n$1\r\n2\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_stream_read_blocks_until_xadd() {
+    let redis = Arc::new(Redis::new(MemDB::new()));
+    let client = &mut Client::new();
+
+    execute(&redis, client, "XADD a 1-0 f 1").await;
+
+    let tail = spawn_blocked(&redis, "XREAD BLOCK 0 STREAMS a b $ $").await;
+    let counted = spawn_blocked(&redis, "XREAD COUNT 1 BLOCK 0 STREAMS b 0").await;
+
+    execute(&redis, client, "XADD a 1-1 skip 1").await;
+    assert_eq!(
+        tail.await.unwrap(),
+        b"*1\r\n*2\r\n$1\r\na\r\n*1\r\n*2\r\n$3\r\n1-1\r\n*2\r\n$4\r\nskip\r\n$1\r\n1\r\n"
+    );
+    assert_eq!(redis.blocking.blocked_clients(), 1);
+
+    execute(&redis, client, "RPUSH list x").await;
+    assert_eq!(redis.blocking.blocked_clients(), 1);
+
+    execute(&redis, client, "XADD b 5-0 g 1").await;
+    assert_eq!(
+        counted.await.unwrap(),
+        b"*1\r\n*2\r\n$1\r\nb\r\n*1\r\n*2\r\n$3\r\n5-0\r\n*2\r\n$1\r\ng\r\n$1\r\n1\r\n"
+    );
+    assert_eq!(redis.blocking.blocked_clients(), 0);
+}
+
+#[tokio::test]
+async fn server_stream_consumer_groups() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    assert_eq!(
+        execute(&redis, client, "XGROUP CREATE s g $").await,
+        b"-ERR The XGROUP subcommand requires the key to exist. Note that for CREATE you may want to use the MKSTREAM option to create an empty stream automatically.\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XGROUP CREATE s g $ MKSTREAM").await,
+        b"+OK\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XGROUP CREATE s g 0").await,
+        b"-BUSYGROUP Consumer Group name already exists\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREADGROUP GROUP nope c STREAMS s >").await,
+        b"-NOGROUP No such key 's' or consumer group 'nope' in XREADGROUP with GROUP option\r\n"
+    );
+
+    for i in 1..=3 {
+        execute(&redis, client, &format!("XADD s {}-0 f {}", i, i)).await;
+    }
+
+    assert_eq!(
+        execute(&redis, client, "XREADGROUP GROUP g alice COUNT 2 STREAMS s >").await,
+        b"*1\r\n*2\r\n$1\r\ns\r\n*2\r\n*2\r\n$3\r\n1-0\r\n*2\r\n$1\r\nf\r\n$1\r\n1\r\n*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nf\r\n$1\r\n2\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREADGROUP GROUP g bob NOACK STREAMS s >").await,
+        b"*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n3-0\r\n*2\r\n$1\r\nf\r\n$1\r\n3\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XREADGROUP GROUP g bob STREAMS s >").await,
+        b"*-1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XPENDING s g").await,
+        b"*4\r\n:2\r\n$3\r\n1-0\r\n$3\r\n2-0\r\n*1\r\n*2\r\n$5\r\nalice\r\n$1\r\n2\r\n"
+    );
+
+    execute(&redis, client, "XDEL s 1-0").await;
+
+    assert_eq!(
+        execute(&redis, client, "XREADGROUP GROUP g alice STREAMS s 0").await,
+        b"*1\r\n*2\r\n$1\r\ns\r\n*2\r\n*2\r\n$3\r\n1-0\r\n*-1\r\n*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nf\r\n$1\r\n2\r\n"
+    );
+
+    let pending = execute(&redis, client, "XPENDING s g - + 10 alice").await;
+    assert!(pending.starts_with(b"*2\r\n*4\r\n$3\r\n1-0\r\n$5\r\nalice\r\n:"));
+    assert!(pending.ends_with(b"\r\n:2\r\n"));
+
+    assert_eq!(
+        execute(&redis, client, "XCLAIM s g bob 0 2-0 3-0 JUSTID").await,
+        b"*1\r\n$3\r\n2-0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XCLAIM s g bob 0 3-0 FORCE RETRYCOUNT 7").await,
+        b"*1\r\n*2\r\n$3\r\n3-0\r\n*2\r\n$1\r\nf\r\n$1\r\n3\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XCLAIM s g bob 3600000 3-0").await,
+        b"*0\r\n"
+    );
+
+    let pending = execute(&redis, client, "XPENDING s g IDLE 0 (1-0 + 10 bob").await;
+    let second = b"\r\n:2\r\n*4\r\n$3\r\n3-0\r\n$3\r\nbob\r\n:";
+
+    assert!(pending.starts_with(b"*2\r\n*4\r\n$3\r\n2-0\r\n$3\r\nbob\r\n:"));
+    assert!(pending.windows(second.len()).any(|window| window == second));
+    assert!(pending.ends_with(b"\r\n:7\r\n"));
+
+    assert_eq!(
+        execute(&redis, client, "XAUTOCLAIM s g carol 0 0 COUNT 1 JUSTID").await,
+        b"*3\r\n$3\r\n3-0\r\n*1\r\n$3\r\n2-0\r\n*1\r\n$3\r\n1-0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XACK s g 2-0 3-0 9-0").await,
+        b":2\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XPENDING s g").await,
+        b"*4\r\n:0\r\n$-1\r\n$-1\r\n*-1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XGROUP CREATECONSUMER s g dave").await,
+        b":1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XGROUP DELCONSUMER s g alice").await,
+        b":0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XINFO GROUPS s").await,
+        b"*1\r\n*12\r\n$4\r\nname\r\n$1\r\ng\r\n$9\r\nconsumers\r\n:3\r\n$7\r\npending\r\n:0\r\n$17\r\nlast-delivered-id\r\n$3\r\n3-0\r\n$12\r\nentries-read\r\n:3\r\n$3\r\nlag\r\n:0\r\n"
+    );
+
+    let consumers = execute(&redis, client, "XINFO CONSUMERS s g").await;
+    assert!(consumers.starts_with(b"*3\r\n*8\r\n$4\r\nname\r\n$3\r\nbob\r\n"));
+    assert!(consumers.ends_with(b"$8\r\ninactive\r\n:-1\r\n"));
+
+    assert_eq!(
+        execute(&redis, client, "XGROUP SETID s g 0 ENTRIESREAD 0").await,
+        b"+OK\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XGROUP SETID s nope 0").await,
+        b"-NOGROUP No such consumer group 'nope' for key name 's'\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XINFO STREAM missing").await,
+        b"-ERR no such key\r\n"
+    );
+
+    let full = execute(&redis, client, "XINFO STREAM s FULL COUNT 1").await;
+    assert!(full.starts_with(b"*18\r\n"));
+    let groups: &[u8] = b"$7\r\nentries\r\n*1\r\n*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nf\r\n$1\r\n2\r\n$6\r\ngroups\r\n*1\r\n*14\r\n$4\r\nname\r\n$1\r\ng\r\n$17\r\nlast-delivered-id\r\n$3\r\n0-0\r\n$12\r\nentries-read\r\n:0\r\n$3\r\nlag\r\n:2\r\n$9\r\npel-count\r\n:0\r\n$7\r\npending\r\n*0\r\n$9\r\nconsumers\r\n*3\r\n";
+    assert!(full.windows(groups.len()).any(|window| window == groups));
+
+    assert_eq!(
+        execute(&redis, client, "XGROUP DESTROY s g").await,
+        b":1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "XINFO STREAM s").await,
+        b"*20\r\n$6\r\nlength\r\n:2\r\n$15\r\nradix-tree-keys\r\n:1\r\n$16\r\nradix-tree-nodes\r\n:1\r\n$17\r\nlast-generated-id\r\n$3\r\n3-0\r\n$20\r\nmax-deleted-entry-id\r\n$3\r\n1-0\r\n$13\r\nentries-added\r\n:3\r\n$23\r\nrecorded-first-entry-id\r\n$3\r\n2-0\r\n$6\r\ngroups\r\n:0\r\n$11\r\nfirst-entry\r\n*2\r\n$3\r\n2-0\r\n*2\r\n$1\r\nf\r\n$1\r\n2\r\n$10\r\nlast-entry\r\n*2\r\n$3\r\n3-0\r\n*2\r\n$1\r\nf\r\n$1\r\n3\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_stream_read_group_blocks_until_xadd() {
+    let redis = Arc::new(Redis::new(MemDB::new()));
+    let client = &mut Client::new();
+
+    execute(&redis, client, "XGROUP CREATE s g $ MKSTREAM").await;
+
+    let first = spawn_blocked(&redis, "XREADGROUP GROUP g a BLOCK 0 STREAMS s >").await;
+    let second = spawn_blocked(&redis, "XREADGROUP GROUP g b BLOCK 0 STREAMS s >").await;
+
+    execute(&redis, client, "XADD s 1-0 f 1").await;
+    assert_eq!(
+        first.await.unwrap(),
+        b"*1\r\n*2\r\n$1\r\ns\r\n*1\r\n*2\r\n$3\r\n1-0\r\n*2\r\n$1\r\nf\r\n$1\r\n1\r\n"
+    );
+    assert_eq!(redis.blocking.blocked_clients(), 1);
+
+    execute(&redis, client, "XGROUP DESTROY s g").await;
+    assert_eq!(
+        second.await.unwrap(),
+        b"-NOGROUP the consumer group this client was blocked on no longer exists\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_pubsub_delivery() {
+    let redis = Redis::new(MemDB::new());
+    let subscriber = &mut Client::new();
+    let publisher = &mut Client::new();
+
+    assert_eq!(
+        execute(&redis, subscriber, "SUBSCRIBE news sport").await,
+        b"*3\r\n$9\r\nsubscribe\r\n$4\r\nnews\r\n:1\r\n*3\r\n$9\r\nsubscribe\r\n$5\r\nsport\r\n:2\r\n"
+    );
+    assert_eq!(
+        execute(&redis, subscriber, "PSUBSCRIBE n[a-f]w?").await,
+        b"*3\r\n$10\r\npsubscribe\r\n$8\r\nn[a-f]w?\r\n:3\r\n"
+    );
+    assert_eq!(
+        execute(&redis, subscriber, "GET k").await,
+        b"-ERR Can't execute 'get': only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE / PING / QUIT / RESET are allowed in this context\r\n"
+    );
+    assert_eq!(
+        execute(&redis, subscriber, "PING").await,
+        b"*2\r\n$4\r\npong\r\n$0\r\n\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "PUBLISH news hello").await,
+        b":2\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "PUBLISH other hello").await,
+        b":0\r\n"
+    );
+    assert_eq!(
+        subscriber
+            .next_message()
+            .await
+            .unwrap()
+            .serialize(Protocol::RESP2),
+        b"*3\r\n$7\r\nmessage\r\n$4\r\nnews\r\n$5\r\nhello\r\n"
+    );
+    assert_eq!(
+        subscriber
+            .next_message()
+            .await
+            .unwrap()
+            .serialize(Protocol::RESP2),
+        b"*4\r\n$8\r\npmessage\r\n$8\r\nn[a-f]w?\r\n$4\r\nnews\r\n$5\r\nhello\r\n"
+    );
+    assert!(subscriber.try_next_message().is_none());
+
+    assert_eq!(
+        execute(&redis, publisher, "PUBSUB CHANNELS").await,
+        b"*2\r\n$4\r\nnews\r\n$5\r\nsport\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "PUBSUB CHANNELS s*").await,
+        b"*1\r\n$5\r\nsport\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "PUBSUB NUMSUB news nope").await,
+        b"*4\r\n$4\r\nnews\r\n:1\r\n$4\r\nnope\r\n:0\r\n"
+    );
+    assert_eq!(execute(&redis, publisher, "PUBSUB NUMPAT").await, b":1\r\n");
+
+    execute(&redis, publisher, "PUBLISH sport goal").await;
+
+    assert_eq!(
+        execute(&redis, subscriber, "UNSUBSCRIBE").await,
+        b"*3\r\n$7\r\nmessage\r\n$5\r\nsport\r\n$4\r\ngoal\r\n*3\r\n$11\r\nunsubscribe\r\n$4\r\nnews\r\n:2\r\n*3\r\n$11\r\nunsubscribe\r\n$5\r\nsport\r\n:1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, subscriber, "PUNSUBSCRIBE").await,
+        b"*3\r\n$12\r\npunsubscribe\r\n$8\r\nn[a-f]w?\r\n:0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, subscriber, "UNSUBSCRIBE").await,
+        b"*3\r\n$11\r\nunsubscribe\r\n$-1\r\n:0\r\n"
+    );
+    assert_eq!(execute(&redis, subscriber, "GET k").await, b"$-1\r\n");
+    assert_eq!(execute(&redis, publisher, "PUBSUB NUMPAT").await, b":0\r\n");
+}
+
+#[tokio::test]
+async fn server_pubsub_resp3_and_disconnect() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "HELLO 3").await;
+
+    assert_eq!(
+        execute(&redis, client, "SUBSCRIBE a").await,
+        b">3\r\n$9\r\nsubscribe\r\n$1\r\na\r\n:1\r\n"
+    );
+    assert_eq!(execute(&redis, client, "PING").await, b"+PONG\r\n");
+    assert_eq!(
+        execute(&redis, client, "PUBLISH a hi").await,
+        b":1\r\n>3\r\n$7\r\nmessage\r\n$1\r\na\r\n$2\r\nhi\r\n"
+    );
+
+    redis.disconnect(client).await;
+
+    assert_eq!(
+        execute(&redis, &mut Client::new(), "PUBSUB NUMSUB a").await,
+        b"*2\r\n$1\r\na\r\n:0\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_sharded_pubsub_namespace() {
+    let redis = Redis::new(MemDB::new());
+    let subscriber = &mut Client::new();
+    let publisher = &mut Client::new();
+
+    assert_eq!(
+        execute(&redis, subscriber, "SUBSCRIBE orders").await,
+        b"*3\r\n$9\r\nsubscribe\r\n$6\r\norders\r\n:1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, subscriber, "SSUBSCRIBE orders").await,
+        b"*3\r\n$10\r\nssubscribe\r\n$6\r\norders\r\n:1\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "SPUBLISH orders one").await,
+        b":1\r\n"
+    );
+    assert_eq!(
+        subscriber
+            .try_next_message()
+            .unwrap()
+            .serialize(Protocol::RESP2),
+        b"*3\r\n$8\r\nsmessage\r\n$6\r\norders\r\n$3\r\none\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "PUBLISH orders two").await,
+        b":1\r\n"
+    );
+    assert_eq!(
+        subscriber
+            .try_next_message()
+            .unwrap()
+            .serialize(Protocol::RESP2),
+        b"*3\r\n$7\r\nmessage\r\n$6\r\norders\r\n$3\r\ntwo\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "PUBSUB SHARDCHANNELS o*").await,
+        b"*1\r\n$6\r\norders\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "PUBSUB SHARDNUMSUB orders x").await,
+        b"*4\r\n$6\r\norders\r\n:1\r\n$1\r\nx\r\n:0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, subscriber, "UNSUBSCRIBE orders").await,
+        b"*3\r\n$11\r\nunsubscribe\r\n$6\r\norders\r\n:0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, subscriber, "GET k").await,
+        b"-ERR Can't execute 'get': only (P|S)SUBSCRIBE / (P|S)UNSUBSCRIBE / PING / QUIT / RESET are allowed in this context\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "PUBSUB CHANNELS").await,
+        b"*0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, subscriber, "SUNSUBSCRIBE").await,
+        b"*3\r\n$12\r\nsunsubscribe\r\n$6\r\norders\r\n:0\r\n"
+    );
+    assert_eq!(
+        execute(&redis, publisher, "SPUBLISH orders three").await,
+        b":0\r\n"
+    );
+    assert_eq!(execute(&redis, subscriber, "GET k").await, b"$-1\r\n");
+}
+
+#[tokio::test]
+async fn server_transaction_exec_and_discard() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+    let other = &mut Client::new();
+
+    assert_eq!(
+        execute(&redis, client, "EXEC").await,
+        b"-ERR EXEC without MULTI\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "DISCARD").await,
+        b"-ERR DISCARD without MULTI\r\n"
+    );
+
+    assert_eq!(execute(&redis, client, "MULTI").await, b"+OK\r\n");
+    assert_eq!(
+        execute(&redis, client, "MULTI").await,
+        b"-ERR MULTI calls can not be nested\r\n"
+    );
+    assert_eq!(execute(&redis, client, "SET k v").await, b"+QUEUED\r\n");
+    assert_eq!(execute(&redis, client, "LPUSH k x").await, b"+QUEUED\r\n");
+    assert_eq!(execute(&redis, client, "BLPOP l 0").await, b"+QUEUED\r\n");
+    assert_eq!(execute(&redis, client, "GET k").await, b"+QUEUED\r\n");
+    assert_eq!(execute(&redis, other, "GET k").await, b"$-1\r\n");
+    assert_eq!(
+        execute(&redis, client, "EXEC").await,
+        b"*4\r\n+OK\r\n-WRONGTYPE Operation against a key holding the wrong kind of value\r\n*-1\r\n$1\r\nv\r\n"
+    );
+    assert_eq!(redis.blocking.blocked_clients(), 0);
+
+    execute(&redis, client, "MULTI").await;
+    execute(&redis, client, "SET k w").await;
+    assert_eq!(execute(&redis, client, "DISCARD").await, b"+OK\r\n");
+    assert_eq!(execute(&redis, client, "GET k").await, b"$1\r\nv\r\n");
+    assert_eq!(
+        execute(&redis, client, "EXEC").await,
+        b"-ERR EXEC without MULTI\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_transaction_aborts_on_queue_errors() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "MULTI").await;
+    execute(&redis, client, "SET k v").await;
+    assert_eq!(
+        execute(&redis, client, "NOPE x").await,
+        b"-ERR unknown command 'NOPE', with args beginning with: 'x' \r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "EXEC").await,
+        b"-EXECABORT Transaction discarded because of previous errors.\r\n"
+    );
+    assert_eq!(execute(&redis, client, "GET k").await, b"$-1\r\n");
+
+    execute(&redis, client, "MULTI").await;
+    assert_eq!(
+        execute(&redis, client, "GET").await,
+        b"-ERR wrong number of arguments for 'get' command\r\n"
+    );
+    execute(&redis, client, "SET k v").await;
+    assert_eq!(
+        execute(&redis, client, "EXEC").await,
+        b"-EXECABORT Transaction discarded because of previous errors.\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "EXEC").await,
+        b"-ERR EXEC without MULTI\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_transaction_watch() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+    let other = &mut Client::new();
+
+    execute(&redis, client, "SET k 1").await;
+    assert_eq!(execute(&redis, client, "WATCH k missing").await, b"+OK\r\n");
+    execute(&redis, client, "MULTI").await;
+    assert_eq!(
+        execute(&redis, client, "WATCH k").await,
+        b"-ERR WATCH inside MULTI is not allowed\r\n"
+    );
+    execute(&redis, client, "SET k 2").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*1\r\n+OK\r\n");
+
+    execute(&redis, client, "WATCH k missing").await;
+    execute(&redis, other, "SET missing x").await;
+    execute(&redis, client, "MULTI").await;
+    execute(&redis, client, "SET k 3").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*-1\r\n");
+    assert_eq!(execute(&redis, client, "GET k").await, b"$1\r\n2\r\n");
+
+    execute(&redis, client, "WATCH k").await;
+    execute(&redis, other, "GET k").await;
+    execute(&redis, client, "UNWATCH").await;
+    execute(&redis, other, "DEL k").await;
+    execute(&redis, client, "MULTI").await;
+    execute(&redis, client, "SET k 4").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*1\r\n+OK\r\n");
+
+    execute(&redis, client, "SET w x").await;
+    execute(&redis, client, "RPUSH l a").await;
+    execute(&redis, client, "WATCH w l").await;
+    assert_eq!(
+        execute(&redis, other, "LPUSH w oops").await,
+        b"-WRONGTYPE Operation against a key holding the wrong kind of value\r\n"
+    );
+    assert_eq!(
+        execute(&redis, other, "LSET l 5 oops").await,
+        b"-ERR index out of range\r\n"
+    );
+    execute(&redis, client, "MULTI").await;
+    execute(&redis, client, "SET w y").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*1\r\n+OK\r\n");
+
+    execute(&redis, client, "WATCH k").await;
+    assert_eq!(execute(&redis, other, "FLUSHDB").await, b"+OK\r\n");
+    execute(&redis, client, "MULTI").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*-1\r\n");
+    assert_eq!(execute(&redis, client, "GET k").await, b"$-1\r\n");
+
+    execute(&redis, client, "SET k 5 PX 50").await;
+    execute(&redis, client, "WATCH k").await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    execute(&redis, client, "MULTI").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*-1\r\n");
+
+    execute(&redis, client, "WATCH k").await;
+    execute(&redis, client, "MULTI").await;
+    execute(&redis, client, "DISCARD").await;
+    execute(&redis, other, "SET k 6").await;
+    execute(&redis, client, "MULTI").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*0\r\n");
+    assert_eq!(
+        execute(&redis, client, "FLUSHDB LAZY").await,
+        b"-ERR syntax error\r\n"
+    );
+}
+
+#[tokio::test]
+async fn server_transaction_watch_ignores_noop_writes() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+    let other = &mut Client::new();
+
+    execute(&redis, client, "HSET h f v").await;
+    execute(&redis, client, "SADD s m").await;
+    execute(&redis, client, "RPUSH l a b").await;
+    execute(&redis, client, "ZADD z 1 m").await;
+    execute(&redis, client, "XADD x 1-1 f v").await;
+    execute(&redis, client, "XGROUP CREATE x g 0").await;
+    execute(&redis, client, "WATCH h s l z x").await;
+
+    for (command, reply) in [
+        ("HDEL h missing", &b":0\r\n"[..]),
+        ("HSETNX h f w", b":0\r\n"),
+        ("SREM s missing", b":0\r\n"),
+        ("LREM l 0 missing", b":0\r\n"),
+        ("LINSERT l BEFORE missing c", b":-1\r\n"),
+        ("LTRIM l 0 -1", b"+OK\r\n"),
+        ("ZPOPMIN z 0", b"*0\r\n"),
+        ("ZREM z missing", b":0\r\n"),
+        ("ZADD z NX 2 m", b":0\r\n"),
+        ("XDEL x 9-9", b":0\r\n"),
+        ("XTRIM x MAXLEN 10", b":0\r\n"),
+        ("XACK x g 1-1", b":0\r\n"),
+    ] {
+        assert_eq!(execute(&redis, other, command).await, reply, "{command}");
+    }
+
+    execute(&redis, client, "MULTI").await;
+    execute(&redis, client, "PING").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*1\r\n+PONG\r\n");
+
+    execute(&redis, client, "WATCH x").await;
+    execute(&redis, other, "XGROUP CREATECONSUMER x g alice").await;
+    execute(&redis, client, "MULTI").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*-1\r\n");
+
+    execute(&redis, client, "WATCH x").await;
+    assert_eq!(
+        execute(&redis, other, "XGROUP CREATECONSUMER x g alice").await,
+        b":0\r\n"
+    );
+    execute(&redis, client, "MULTI").await;
+    assert_eq!(execute(&redis, client, "EXEC").await, b"*0\r\n");
+}
+
+#[tokio::test]
+async fn server_snapshot_is_isolated_from_writes() {
+    let redis = Redis::new(MemDB::new());
+    let client = &mut Client::new();
+
+    execute(&redis, client, "RPUSH l a").await;
+
+    let snapshot = redis.db.lock().await.snapshot();
+
+    execute(&redis, client, "RPUSH l b").await;
+
+    assert_eq!(*snapshot[0].1, Value::List(VecDeque::from([b"a".to_vec()])));
+    assert_eq!(execute(&redis, client, "LLEN l").await, b":2\r\n");
+}
+
+#[tokio::test]
+async fn server_save_and_bgsave() {
+    let dir = std::env::temp_dir().join(format!("redis-save-{}", std::process::id()));
+    let config = Config {
+        dir: dir.to_string_lossy().into_owned(),
+        dbfilename: "test.rdb".to_string(),
+        save: parse_save_rules("1 2").unwrap(),
+        ..Config::default()
+    };
+    let path = config.rdb_path();
+    let redis = Redis::with_config(MemDB::new(), config);
+    let client = &mut Client::new();
+
+    std::fs::create_dir_all(&dir).unwrap();
+    redis.persistence.state().last_save -= 10;
+
+    execute(&redis, client, "SET k v").await;
+    assert!(!redis.persistence.save_due(redis.db.lock().await.dirty()));
+
+    execute(&redis, client, "RPUSH l a b").await;
+    assert!(redis.persistence.save_due(redis.db.lock().await.dirty()));
+
+    assert_eq!(execute(&redis, client, "SAVE").await, b"+OK\r\n");
+    assert!(!redis.persistence.save_due(redis.db.lock().await.dirty()));
+
+    let lastsave = execute(&redis, client, "LASTSAVE").await;
+    let now = db::unix_time_ms() / 1000;
+    assert!([format!(":{}\r\n", now), format!(":{}\r\n", now - 1)]
+        .iter()
+        .any(|expected| expected.as_bytes() == lastsave));
+
+    let mut loaded = MemDB::new();
+    assert_eq!(rdb::load(&path, &mut loaded).unwrap(), 2);
+    assert_eq!(loaded.get(b"k"), Some(&Value::String(b"v".to_vec())));
+
+    execute(&redis, client, "SET k w").await;
+    assert_eq!(
+        execute(&redis, client, "BGSAVE").await,
+        b"+Background saving started\r\n"
+    );
+    assert_eq!(
+        execute(&redis, client, "BGSAVE NOW").await,
+        b"-ERR syntax error\r\n"
+    );
+
+    while redis.persistence.state().bgsave_in_progress {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    assert!(redis.persistence.state().last_bgsave_ok);
+    rdb::load(&path, &mut loaded).unwrap();
+    assert_eq!(loaded.get(b"k"), Some(&Value::String(b"w".to_vec())));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+async fn sorted_snapshot(redis: &Redis<MemDB>) -> db::Snapshot {
+    let mut snapshot = redis.db.lock().await.snapshot();
+
+    snapshot.retain(|(_, value, _)| !matches!(**value, Value::Stream(_)));
+    snapshot.sort_by(|a, b| a.0.cmp(&b.0));
+    snapshot
+}
+
+#[tokio::test]
+async fn server_append_only_file() {
+    let dir = std::env::temp_dir().join(format!("redis-aof-{}", std::process::id()));
+    let config = Config {
+        dir: dir.to_string_lossy().into_owned(),
+        appendonly: true,
+        appendfsync: AppendFsync::Always,
+        ..Config::default()
+    };
+    let path = config.aof_path();
+    let redis = Arc::new(Redis::with_config(MemDB::new(), config.clone()));
+    let client = &mut Client::new();
+
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    redis
+        .persistence
+        .open_aof(&mut *redis.db.lock().await)
+        .unwrap();
+
+    execute(&redis, client, "SET k v EX 100").await;
+    execute(&redis, client, "SET k w NX").await;
+    execute(&redis, client, "SADD s a b c").await;
+    execute(&redis, client, "SPOP s").await;
+    execute(&redis, client, "GET k").await;
+    execute(&redis, client, "MULTI").await;
+    execute(&redis, client, "RPUSH l a b c").await;
+    execute(&redis, client, "LPOP l").await;
+    execute(&redis, client, "EXEC").await;
+    execute(&redis, client, "XADD x * f v").await;
+    execute(&redis, client, "XGROUP CREATE x g 0").await;
+    execute(&redis, client, "XREADGROUP GROUP g c STREAMS x >").await;
+    execute(&redis, client, "XREADGROUP GROUP g c STREAMS x 0").await;
+
+    let blocked = spawn_blocked(&redis, "BLPOP q 0").await;
+    execute(&redis, client, "RPUSH q 1 2").await;
+    blocked.await.unwrap();
+
+    let blocked = spawn_blocked(&redis, "XREADGROUP GROUP g d BLOCK 0 STREAMS x >").await;
+    execute(&redis, client, "XADD x * f w").await;
+    blocked.await.unwrap();
+
+    let log = String::from_utf8(std::fs::read(&path).unwrap()).unwrap();
+    assert!(log.contains("PXAT") && !log.contains("EX\r\n"));
+    assert!(log.contains("SREM") && !log.contains("SPOP"));
+    assert!(log.contains("MULTI") && log.contains("EXEC"));
+    assert!(!log.contains("GET") && !log.contains("BLPOP") && !log.contains("\n*\r"));
+    assert!(log.contains("XCLAIM") && !log.contains("XREADGROUP"));
+
+    tokio::time::sleep(Duration::from_millis(5)).await;
+
+    let replayed = Redis::with_config(MemDB::new(), config.clone());
+    assert_eq!(replayed.load_aof().await.unwrap(), 19);
+    assert_eq!(
+        sorted_snapshot(&replayed).await,
+        sorted_snapshot(&redis).await
+    );
+
+    let pending = |db: &mut MemDB| {
+        let group = db.get_stream(b"x").unwrap().unwrap().group(b"g").unwrap();
+
+        (group.last_id, group.entries_read, group.pending.clone())
+    };
+    assert_eq!(
+        pending(&mut *replayed.db.lock().await),
+        pending(&mut *redis.db.lock().await)
+    );
+
+    for command in ["XRANGE x - +", "XPENDING x g"] {
+        assert_eq!(
+            execute(&replayed, client, command).await,
+            execute(&redis, client, command).await
+        );
+    }
+
+    let len = std::fs::metadata(&path).unwrap().len();
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    std::io::Write::write_all(&mut file, b"*1\r\n$5\r\nMULTI\r\n*2\r\n$3\r\nDEL\r\n$1").unwrap();
+
+    let truncated = Redis::with_config(MemDB::new(), config.clone());
+    assert_eq!(truncated.load_aof().await.unwrap(), 19);
+    assert_eq!(std::fs::metadata(&path).unwrap().len(), len);
+    assert!(aof::parse(b"+OK\r\n").is_err());
+
+    for _ in 0..10 {
+        execute(&redis, client, "SET k v").await;
+    }
+
+    let len = std::fs::metadata(&path).unwrap().len();
+
+    assert_eq!(
+        execute(&redis, client, "BGREWRITEAOF").await,
+        b"+Background append only file rewriting started\r\n"
+    );
+
+    while redis.persistence.state().aof_rewrite_in_progress {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    assert!(redis.persistence.state().aof_last_rewrite_ok);
+    assert!(std::fs::metadata(&path).unwrap().len() < len);
+    execute(&redis, client, "SET after rewrite").await;
+
+    let rewritten = Redis::with_config(MemDB::new(), config.clone());
+    rewritten.load_aof().await.unwrap();
+    assert_eq!(
+        sorted_snapshot(&rewritten).await,
+        sorted_snapshot(&redis).await
+    );
+    assert_eq!(
+        execute(&rewritten, client, "XINFO GROUPS x").await,
+        execute(&redis, client, "XINFO GROUPS x").await
+    );
+
+    let mut file = std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .unwrap();
+    std::io::Write::write_all(&mut file, b"*3\r\n$5\r\nLPUSH\r\n$1\r\nk\r\n$1\r\nx\r\n").unwrap();
+
+    let failed = Redis::with_config(MemDB::new(), config);
+    assert_eq!(
+        failed.load_aof().await.unwrap_err().to_string(),
+        "Error replaying 'LPUSH' from the AOF: WRONGTYPE Operation against a key holding the wrong kind of value"
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn pubsub_glob_patterns() {
+    assert!(glob_match(b"*", b""));
+    assert!(glob_match(b"news.*", b"news.sport"));
+    assert!(glob_match(b"h?llo", b"hello"));
+    assert!(!glob_match(b"h?llo", b"hllo"));
+    assert!(glob_match(b"h[ae]llo", b"hallo"));
+    assert!(!glob_match(b"h[^e]llo", b"hello"));
+    assert!(glob_match(b"h[a-b]llo", b"hbllo"));
+    assert!(glob_match(b"h\\*llo", b"h*llo"));
+    assert!(!glob_match(b"h\\*llo", b"hello"));
+    assert!(glob_match(b"a*b*c", b"axxbyyc"));
+    assert!(!glob_match(b"a*b*c", b"axxbyy"));
+    assert!(glob_match(b"*[xb]c", b"axxbc"));
+    assert!(glob_match(b"a\\", b"a\\"));
+    assert!(glob_match(b"h[ae", b"ha"));
+    assert!(!glob_match(b"*?", b""));
+
+    let string = [b'a'; 100];
+    let pattern = b"a*".repeat(50);
+
+    assert!(!glob_match(&[pattern.as_slice(), b"b"].concat(), &string));
+}
+
+#[test]
+fn skiplist_ranks_match_sorted_order() {
+    let mut zset = ZSet::new();
+    let mut seed = 7u64;
+
+    for i in 0..500u64 {
+        seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+        zset.insert(
+            format!("m{}", i % 300).as_bytes(),
+            (seed >> 33) as f64 % 50.0,
+        );
+
+        if i % 7 == 0 {
+            zset.remove(format!("m{}", (i * 13) % 300).as_bytes());
+        }
+    }
+
+    let mut expected: Vec<(f64, Vec<u8>)> = (0..300)
+        .filter_map(|i| {
+            let member = format!("m{}", i).into_bytes();
+            zset.score(&member).map(|score| (score, member))
+        })
+        .collect();
+    expected.sort_by(|a, b| a.0.total_cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+
+    let actual: Vec<(f64, Vec<u8>)> = zset
+        .iter()
+        .map(|(member, score)| (score, member.to_vec()))
+        .collect();
+
+    assert_eq!(actual, expected);
+
+    for (rank, (_, member)) in expected.iter().enumerate() {
+        assert_eq!(zset.rank(member, false), Some(rank));
+        assert_eq!(zset.iter_from(rank, false).next().unwrap().0, member);
+    }
+
+    let range = zset.score_range(&ScoreBound::Exclusive(10.0), &ScoreBound::Inclusive(20.0));
+    let count = expected
+        .iter()
+        .filter(|(score, _)| *score > 10.0 && *score <= 20.0)
+        .count();
+
+    assert_eq!(range.len(), count);
+}
@@ -0,0 +1,179 @@
+use std::{collections::HashMap, sync::Arc};
+
+pub const SET_MAX_INTSET_ENTRIES: usize = 512;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Set {
+    IntSet(Vec<i64>),
+    HashTable(Members),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Members {
+    entries: Vec<Arc<[u8]>>,
+    positions: HashMap<Arc<[u8]>, usize>,
+}
+
+impl Members {
+    fn contains(&self, member: &[u8]) -> bool {
+        self.positions.contains_key(member)
+    }
+
+    fn insert(&mut self, member: &[u8]) -> bool {
+        if self.contains(member) {
+            return false;
+        }
+
+        let member: Arc<[u8]> = Arc::from(member);
+
+        self.positions
+            .insert(Arc::clone(&member), self.entries.len());
+        self.entries.push(member);
+        true
+    }
+
+    fn remove(&mut self, member: &[u8]) -> bool {
+        let Some(position) = self.positions.remove(member) else {
+            return false;
+        };
+
+        self.entries.swap_remove(position);
+
+        if let Some(moved) = self.entries.get(position) {
+            self.positions.insert(Arc::clone(moved), position);
+        }
+
+        true
+    }
+}
+
+impl PartialEq for Members {
+    fn eq(&self, other: &Self) -> bool {
+        self.entries.len() == other.entries.len()
+            && self.entries.iter().all(|member| other.contains(member))
+    }
+}
+
+impl Default for Set {
+    fn default() -> Self {
+        Set::IntSet(Vec::new())
+    }
+}
+
+impl Set {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn encoding(&self) -> &'static str {
+        match self {
+            Set::IntSet(_) => "intset",
+            Set::HashTable(_) => "hashtable",
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            Set::IntSet(ints) => ints.len(),
+            Set::HashTable(members) => members.entries.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn contains(&self, member: &[u8]) -> bool {
+        match self {
+            Set::IntSet(ints) => {
+                as_integer(member).is_some_and(|int| ints.binary_search(&int).is_ok())
+            }
+            Set::HashTable(members) => members.contains(member),
+        }
+    }
+
+    pub fn insert(&mut self, member: &[u8]) -> bool {
+        if let Set::IntSet(ints) = self {
+            match as_integer(member) {
+                Some(int) => match ints.binary_search(&int) {
+                    Ok(_) => return false,
+                    Err(_) if ints.len() >= SET_MAX_INTSET_ENTRIES => self.convert(),
+                    Err(position) => {
+                        ints.insert(position, int);
+                        return true;
+                    }
+                },
+                None => self.convert(),
+            }
+        }
+
+        match self {
+            Set::HashTable(members) => members.insert(member),
+            Set::IntSet(_) => unreachable!("set was converted to a hash table"),
+        }
+    }
+
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        match self {
+            Set::IntSet(ints) => match as_integer(member).map(|int| ints.binary_search(&int)) {
+                Some(Ok(position)) => {
+                    ints.remove(position);
+                    true
+                }
+                _ => false,
+            },
+            Set::HashTable(members) => members.remove(member),
+        }
+    }
+
+    pub fn members(&self) -> Vec<Vec<u8>> {
+        match self {
+            Set::IntSet(ints) => ints
+                .iter()
+                .map(|int| int.to_string().into_bytes())
+                .collect(),
+            Set::HashTable(members) => members
+                .entries
+                .iter()
+                .map(|member| member.to_vec())
+                .collect(),
+        }
+    }
+
+    pub fn member_at(&self, index: usize) -> Option<Vec<u8>> {
+        match self {
+            Set::IntSet(ints) => ints.get(index).map(|int| int.to_string().into_bytes()),
+            Set::HashTable(members) => members.entries.get(index).map(|member| member.to_vec()),
+        }
+    }
+
+    fn convert(&mut self) {
+        if let Set::IntSet(_) = self {
+            let mut members = Members::default();
+
+            for member in self.members() {
+                members.insert(&member);
+            }
+
+            *self = Set::HashTable(members);
+        }
+    }
+}
+
+impl FromIterator<Vec<u8>> for Set {
+    fn from_iter<T: IntoIterator<Item = Vec<u8>>>(iter: T) -> Self {
+        let mut set = Set::new();
+
+        for member in iter {
+            set.insert(&member);
+        }
+
+        set
+    }
+}
+
+fn as_integer(member: &[u8]) -> Option<i64> {
+    let int = std::str::from_utf8(member).ok()?.parse::<i64>().ok()?;
+
+    (int.to_string().as_bytes() == member).then_some(int)
+}
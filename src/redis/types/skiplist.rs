@@ -0,0 +1,302 @@
+use std::cmp::Ordering;
+
+pub const SKIPLIST_MAX_LEVEL: usize = 32;
+const SKIPLIST_P: u64 = u64::MAX / 4;
+const HEADER: usize = 0;
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Link {
+    forward: Option<usize>,
+    span: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+    member: Vec<u8>,
+    score: f64,
+    backward: Option<usize>,
+    levels: Vec<Link>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SkipList {
+    nodes: Vec<Option<Node>>,
+    free: Vec<usize>,
+    length: usize,
+    level: usize,
+    rng: u64,
+}
+
+impl Default for SkipList {
+    fn default() -> Self {
+        let header = Node {
+            member: vec![],
+            score: 0.0,
+            backward: None,
+            levels: vec![Link::default(); SKIPLIST_MAX_LEVEL],
+        };
+
+        Self {
+            nodes: vec![Some(header)],
+            free: vec![],
+            length: 0,
+            level: 1,
+            rng: 0x2545_f491_4f6c_dd1d,
+        }
+    }
+}
+
+impl SkipList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+
+    pub fn insert(&mut self, score: f64, member: Vec<u8>) {
+        let mut update = [HEADER; SKIPLIST_MAX_LEVEL];
+        let mut rank = [0; SKIPLIST_MAX_LEVEL];
+        let mut x = HEADER;
+
+        for i in (0..self.level).rev() {
+            rank[i] = if i == self.level - 1 { 0 } else { rank[i + 1] };
+
+            while let Some(next) = self.link(x, i).forward {
+                if compare(self.node(next), score, &member) != Ordering::Less {
+                    break;
+                }
+
+                rank[i] += self.link(x, i).span;
+                x = next;
+            }
+
+            update[i] = x;
+        }
+
+        let level = self.random_level();
+
+        if level > self.level {
+            for i in self.level..level {
+                rank[i] = 0;
+                update[i] = HEADER;
+                self.link_mut(HEADER, i).span = self.length;
+            }
+
+            self.level = level;
+        }
+
+        let id = self.allocate(Node {
+            member,
+            score,
+            backward: (update[0] != HEADER).then_some(update[0]),
+            levels: vec![Link::default(); level],
+        });
+
+        for i in 0..level {
+            let previous = *self.link(update[i], i);
+
+            *self.link_mut(id, i) = Link {
+                forward: previous.forward,
+                span: previous.span - (rank[0] - rank[i]),
+            };
+            *self.link_mut(update[i], i) = Link {
+                forward: Some(id),
+                span: rank[0] - rank[i] + 1,
+            };
+        }
+
+        for (i, node) in update.iter().enumerate().take(self.level).skip(level) {
+            self.link_mut(*node, i).span += 1;
+        }
+
+        if let Some(next) = self.link(id, 0).forward {
+            self.node_mut(next).backward = Some(id);
+        }
+
+        self.length += 1;
+    }
+
+    pub fn remove(&mut self, score: f64, member: &[u8]) -> bool {
+        let mut update = [HEADER; SKIPLIST_MAX_LEVEL];
+        let mut x = HEADER;
+
+        for (i, slot) in update.iter_mut().enumerate().take(self.level).rev() {
+            while let Some(next) = self.link(x, i).forward {
+                if compare(self.node(next), score, member) != Ordering::Less {
+                    break;
+                }
+
+                x = next;
+            }
+
+            *slot = x;
+        }
+
+        let Some(id) = self.link(x, 0).forward else {
+            return false;
+        };
+
+        if compare(self.node(id), score, member) != Ordering::Equal {
+            return false;
+        }
+
+        for (i, node) in update.iter().enumerate().take(self.level) {
+            if self.link(*node, i).forward != Some(id) {
+                self.link_mut(*node, i).span -= 1;
+                continue;
+            }
+
+            let removed = *self.link(id, i);
+            let link = self.link_mut(*node, i);
+
+            link.span = link.span + removed.span - 1;
+            link.forward = removed.forward;
+        }
+
+        let backward = self.node(id).backward;
+
+        if let Some(next) = self.link(id, 0).forward {
+            self.node_mut(next).backward = backward;
+        }
+
+        while self.level > 1 && self.link(HEADER, self.level - 1).forward.is_none() {
+            self.level -= 1;
+        }
+
+        self.nodes[id] = None;
+        self.free.push(id);
+        self.length -= 1;
+
+        true
+    }
+
+    pub fn count_while(&self, predicate: impl Fn(f64, &[u8]) -> bool) -> usize {
+        let mut rank = 0;
+        let mut x = HEADER;
+
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.link(x, i).forward {
+                let node = self.node(next);
+
+                if !predicate(node.score, &node.member) {
+                    break;
+                }
+
+                rank += self.link(x, i).span;
+                x = next;
+            }
+        }
+
+        rank
+    }
+
+    pub fn iter_from(&self, rank: usize, reverse: bool) -> Iter<'_> {
+        Iter {
+            list: self,
+            current: self.get_by_rank(rank),
+            reverse,
+        }
+    }
+
+    fn get_by_rank(&self, rank: usize) -> Option<usize> {
+        let target = rank + 1;
+        let mut traversed = 0;
+        let mut x = HEADER;
+
+        for i in (0..self.level).rev() {
+            while let Some(next) = self.link(x, i).forward {
+                if traversed + self.link(x, i).span > target {
+                    break;
+                }
+
+                traversed += self.link(x, i).span;
+                x = next;
+            }
+
+            if traversed == target {
+                return Some(x);
+            }
+        }
+
+        None
+    }
+
+    fn random_level(&mut self) -> usize {
+        let mut level = 1;
+
+        while level < SKIPLIST_MAX_LEVEL && self.next_random() < SKIPLIST_P {
+            level += 1;
+        }
+
+        level
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    fn allocate(&mut self, node: Node) -> usize {
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = Some(node);
+                id
+            }
+            None => {
+                self.nodes.push(Some(node));
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn node(&self, id: usize) -> &Node {
+        self.nodes[id].as_ref().expect("skiplist node is live")
+    }
+
+    fn node_mut(&mut self, id: usize) -> &mut Node {
+        self.nodes[id].as_mut().expect("skiplist node is live")
+    }
+
+    fn link(&self, id: usize, level: usize) -> &Link {
+        &self.node(id).levels[level]
+    }
+
+    fn link_mut(&mut self, id: usize, level: usize) -> &mut Link {
+        &mut self.node_mut(id).levels[level]
+    }
+}
+
+pub struct Iter<'a> {
+    list: &'a SkipList,
+    current: Option<usize>,
+    reverse: bool,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a [u8], f64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.list.node(self.current?);
+
+        self.current = match self.reverse {
+            true => node.backward,
+            false => node.levels[0].forward,
+        };
+
+        Some((&node.member, node.score))
+    }
+}
+
+fn compare(node: &Node, score: f64, member: &[u8]) -> Ordering {
+    node.score
+        .total_cmp(&score)
+        .then_with(|| node.member.as_slice().cmp(member))
+}
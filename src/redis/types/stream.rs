@@ -0,0 +1,456 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fmt::Display,
+    ops::RangeInclusive,
+};
+
+pub const STREAM_NODE_MAX_ENTRIES: usize = 100;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy, Default)]
+pub struct StreamId {
+    pub ms: u64,
+    pub seq: u64,
+}
+
+impl StreamId {
+    pub const MIN: StreamId = StreamId { ms: 0, seq: 0 };
+    pub const MAX: StreamId = StreamId {
+        ms: u64::MAX,
+        seq: u64::MAX,
+    };
+
+    pub fn new(ms: u64, seq: u64) -> Self {
+        Self { ms, seq }
+    }
+
+    pub fn next(&self) -> Option<StreamId> {
+        match (self.seq.checked_add(1), self.ms.checked_add(1)) {
+            (Some(seq), _) => Some(StreamId::new(self.ms, seq)),
+            (None, Some(ms)) => Some(StreamId::new(ms, 0)),
+            (None, None) => None,
+        }
+    }
+
+    pub fn prev(&self) -> Option<StreamId> {
+        match (self.seq.checked_sub(1), self.ms.checked_sub(1)) {
+            (Some(seq), _) => Some(StreamId::new(self.ms, seq)),
+            (None, Some(ms)) => Some(StreamId::new(ms, u64::MAX)),
+            (None, None) => None,
+        }
+    }
+}
+
+impl Display for StreamId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.ms, self.seq)
+    }
+}
+
+pub type StreamFields = Vec<(Vec<u8>, Vec<u8>)>;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TrimStrategy {
+    MaxLen(usize),
+    MinId(StreamId),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StreamTrim {
+    pub strategy: TrimStrategy,
+    pub approximate: bool,
+    pub limit: usize,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct PendingEntry {
+    pub consumer: Vec<u8>,
+    pub delivery_time: u64,
+    pub delivery_count: u64,
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct Consumer {
+    pub seen_time: u64,
+    pub active_time: Option<u64>,
+    pub pending: BTreeSet<StreamId>,
+}
+
+impl Consumer {
+    fn new(now: u64) -> Self {
+        Self {
+            seen_time: now,
+            active_time: None,
+            pending: BTreeSet::new(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub struct ConsumerGroup {
+    pub last_id: StreamId,
+    pub entries_read: Option<u64>,
+    pub pending: BTreeMap<StreamId, PendingEntry>,
+    pub consumers: BTreeMap<Vec<u8>, Consumer>,
+}
+
+impl ConsumerGroup {
+    pub fn new(last_id: StreamId, entries_read: Option<u64>) -> Self {
+        Self {
+            last_id,
+            entries_read,
+            pending: BTreeMap::new(),
+            consumers: BTreeMap::new(),
+        }
+    }
+
+    pub fn create_consumer(&mut self, name: &[u8], now: u64) -> bool {
+        if self.consumers.contains_key(name) {
+            return false;
+        }
+
+        self.consumers.insert(name.to_vec(), Consumer::new(now));
+
+        true
+    }
+
+    pub fn touch_consumer(&mut self, name: &[u8], now: u64) -> &mut Consumer {
+        let consumer = self
+            .consumers
+            .entry(name.to_vec())
+            .or_insert_with(|| Consumer::new(now));
+
+        consumer.seen_time = now;
+        consumer
+    }
+
+    pub fn delete_consumer(&mut self, name: &[u8]) -> Option<usize> {
+        let consumer = self.consumers.remove(name)?;
+
+        for id in &consumer.pending {
+            self.pending.remove(id);
+        }
+
+        Some(consumer.pending.len())
+    }
+
+    pub fn assign(&mut self, id: StreamId, entry: PendingEntry, now: u64) {
+        let consumer = entry.consumer.clone();
+
+        if let Some(previous) = self.pending.insert(id, entry) {
+            if let Some(owner) = self.consumers.get_mut(&previous.consumer) {
+                owner.pending.remove(&id);
+            }
+        }
+
+        let owner = self.touch_consumer(&consumer, now);
+
+        owner.active_time = Some(now);
+        owner.pending.insert(id);
+    }
+
+    pub fn ack(&mut self, id: &StreamId) -> bool {
+        let Some(entry) = self.pending.remove(id) else {
+            return false;
+        };
+
+        if let Some(owner) = self.consumers.get_mut(&entry.consumer) {
+            owner.pending.remove(id);
+        }
+
+        true
+    }
+}
+
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct Stream {
+    entries: BTreeMap<StreamId, StreamFields>,
+    last_id: StreamId,
+    max_deleted_id: StreamId,
+    entries_added: u64,
+    groups: BTreeMap<Vec<u8>, ConsumerGroup>,
+}
+
+impl Stream {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn last_id(&self) -> StreamId {
+        self.last_id
+    }
+
+    pub fn max_deleted_id(&self) -> StreamId {
+        self.max_deleted_id
+    }
+
+    pub fn entries_added(&self) -> u64 {
+        self.entries_added
+    }
+
+    pub fn set_id(
+        &mut self,
+        last_id: StreamId,
+        entries_added: Option<u64>,
+        max_deleted_id: Option<StreamId>,
+    ) {
+        self.last_id = last_id;
+        self.entries_added = entries_added.unwrap_or(self.entries_added);
+        self.max_deleted_id = max_deleted_id.unwrap_or(self.max_deleted_id);
+    }
+
+    pub fn first_entry(&self) -> Option<(&StreamId, &StreamFields)> {
+        self.entries.first_key_value()
+    }
+
+    pub fn last_entry(&self) -> Option<(&StreamId, &StreamFields)> {
+        self.entries.last_key_value()
+    }
+
+    pub fn get(&self, id: &StreamId) -> Option<&StreamFields> {
+        self.entries.get(id)
+    }
+
+    pub fn auto_id(&self, now: u64) -> Option<StreamId> {
+        match now > self.last_id.ms {
+            true => Some(StreamId::new(now, 0)),
+            false => self.last_id.next(),
+        }
+    }
+
+    pub fn auto_seq(&self, ms: u64) -> Option<StreamId> {
+        match ms.cmp(&self.last_id.ms) {
+            Ordering::Greater => Some(StreamId::new(ms, 0)),
+            Ordering::Equal => self
+                .last_id
+                .seq
+                .checked_add(1)
+                .map(|seq| StreamId::new(ms, seq)),
+            Ordering::Less => None,
+        }
+    }
+
+    pub fn add(&mut self, id: StreamId, fields: StreamFields) -> bool {
+        if id <= self.last_id {
+            return false;
+        }
+
+        self.entries.insert(id, fields);
+        self.last_id = id;
+        self.entries_added += 1;
+
+        true
+    }
+
+    pub fn delete(&mut self, id: &StreamId) -> bool {
+        if self.entries.remove(id).is_none() {
+            return false;
+        }
+
+        self.max_deleted_id = self.max_deleted_id.max(*id);
+
+        true
+    }
+
+    pub fn range(
+        &self,
+        range: RangeInclusive<StreamId>,
+    ) -> impl DoubleEndedIterator<Item = (&StreamId, &StreamFields)> {
+        let range = (range.start() <= range.end()).then(|| self.entries.range(range));
+
+        range.into_iter().flatten()
+    }
+
+    pub fn trim(&mut self, trim: &StreamTrim) -> usize {
+        let mut excess = match trim.strategy {
+            TrimStrategy::MaxLen(max_len) => self.len().saturating_sub(max_len),
+            TrimStrategy::MinId(min_id) => self.entries.range(..min_id).count(),
+        };
+
+        if trim.approximate {
+            excess -= excess % STREAM_NODE_MAX_ENTRIES;
+        }
+
+        if trim.limit > 0 {
+            excess = excess.min(trim.limit);
+        }
+
+        for _ in 0..excess {
+            if let Some((id, _)) = self.entries.pop_first() {
+                self.max_deleted_id = self.max_deleted_id.max(id);
+            }
+        }
+
+        excess
+    }
+
+    pub fn groups(&self) -> impl Iterator<Item = (&Vec<u8>, &ConsumerGroup)> {
+        self.groups.iter()
+    }
+
+    pub fn group(&self, name: &[u8]) -> Option<&ConsumerGroup> {
+        self.groups.get(name)
+    }
+
+    pub fn group_mut(&mut self, name: &[u8]) -> Option<&mut ConsumerGroup> {
+        self.groups.get_mut(name)
+    }
+
+    pub fn create_group(&mut self, name: &[u8], group: ConsumerGroup) -> bool {
+        if self.groups.contains_key(name) {
+            return false;
+        }
+
+        self.groups.insert(name.to_vec(), group);
+
+        true
+    }
+
+    pub fn destroy_group(&mut self, name: &[u8]) -> bool {
+        self.groups.remove(name).is_some()
+    }
+
+    pub fn read_group(
+        &mut self,
+        name: &[u8],
+        consumer: &[u8],
+        count: Option<usize>,
+        noack: bool,
+        now: u64,
+    ) -> Vec<(StreamId, StreamFields)> {
+        let Some(start) = self.groups.get(name).and_then(|group| group.last_id.next()) else {
+            return vec![];
+        };
+
+        let entries: Vec<(StreamId, StreamFields)> = self
+            .entries
+            .range(start..)
+            .take(count.unwrap_or(usize::MAX))
+            .map(|(id, fields)| (*id, fields.clone()))
+            .collect();
+
+        for (id, _) in &entries {
+            let entries_read = match self.groups[name].entries_read {
+                Some(read) if !self.has_tombstones(*id) => Some(read + 1),
+                _ => self.estimate_entries_read(*id),
+            };
+            let group = self.groups.get_mut(name).expect("group exists");
+
+            group.last_id = *id;
+            group.entries_read = entries_read;
+
+            if !noack {
+                let entry = PendingEntry {
+                    consumer: consumer.to_vec(),
+                    delivery_time: now,
+                    delivery_count: 1,
+                };
+
+                group.assign(*id, entry, now);
+            }
+        }
+
+        self.groups
+            .get_mut(name)
+            .expect("group exists")
+            .touch_consumer(consumer, now);
+
+        entries
+    }
+
+    pub fn read_history(
+        &mut self,
+        name: &[u8],
+        consumer: &[u8],
+        after: StreamId,
+        count: Option<usize>,
+        now: u64,
+    ) -> Vec<(StreamId, Option<StreamFields>)> {
+        let Some(group) = self.groups.get_mut(name) else {
+            return vec![];
+        };
+
+        let pending = &group.touch_consumer(consumer, now).pending;
+        let ids: Vec<StreamId> = match after.next() {
+            Some(start) => pending
+                .range(start..)
+                .take(count.unwrap_or(usize::MAX))
+                .copied()
+                .collect(),
+            None => vec![],
+        };
+
+        ids.into_iter()
+            .map(|id| {
+                let fields = self.entries.get(&id).cloned();
+
+                if let Some(entry) = group.pending.get_mut(&id).filter(|_| fields.is_some()) {
+                    entry.delivery_time = now;
+                    entry.delivery_count += 1;
+                }
+
+                (id, fields)
+            })
+            .collect()
+    }
+
+    pub fn estimate_entries_read(&self, id: StreamId) -> Option<u64> {
+        if self.entries_added == 0 {
+            return Some(0);
+        }
+
+        if self.is_empty() && id <= self.last_id {
+            return Some(self.entries_added);
+        }
+
+        match id.cmp(&self.last_id) {
+            Ordering::Equal => return Some(self.entries_added),
+            Ordering::Greater => return None,
+            Ordering::Less => {}
+        }
+
+        let first_id = self.first_entry().map_or(StreamId::MIN, |(id, _)| *id);
+
+        if self.max_deleted_id == StreamId::MIN || self.max_deleted_id < first_id {
+            match id.cmp(&first_id) {
+                Ordering::Less => return Some(self.entries_added - self.len() as u64),
+                Ordering::Equal => return Some(self.entries_added - self.len() as u64 + 1),
+                Ordering::Greater => {}
+            }
+        }
+
+        None
+    }
+
+    pub fn lag(&self, group: &ConsumerGroup) -> Option<u64> {
+        if self.entries_added == 0 {
+            return Some(0);
+        }
+
+        let first_id = self.first_entry().map_or(StreamId::MIN, |(id, _)| *id);
+        let entries_read = match group.entries_read {
+            Some(read) if !self.has_tombstones(group.last_id) && group.last_id >= first_id => {
+                Some(read)
+            }
+            _ => self.estimate_entries_read(group.last_id),
+        };
+
+        entries_read.map(|read| self.entries_added.saturating_sub(read))
+    }
+
+    fn has_tombstones(&self, start: StreamId) -> bool {
+        if self.is_empty() || self.max_deleted_id == StreamId::MIN {
+            return false;
+        }
+
+        self.max_deleted_id >= start
+    }
+}
@@ -0,0 +1,29 @@
+use std::collections::{HashMap, VecDeque};
+
+use super::{Set, Stream, ZSet};
+
+pub type List = VecDeque<Vec<u8>>;
+pub type Hash = HashMap<Vec<u8>, Vec<u8>>;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum Value {
+    String(Vec<u8>),
+    List(List),
+    Hash(Hash),
+    Set(Set),
+    ZSet(ZSet),
+    Stream(Stream),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::String(_) => "string",
+            Value::List(_) => "list",
+            Value::Hash(_) => "hash",
+            Value::Set(_) => "set",
+            Value::ZSet(_) => "zset",
+            Value::Stream(_) => "stream",
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::{collections::HashMap, ops::Range};
+
+use super::skiplist::{Iter, SkipList};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ScoreBound {
+    Inclusive(f64),
+    Exclusive(f64),
+}
+
+impl ScoreBound {
+    fn is_below_min(&self, score: f64) -> bool {
+        match self {
+            ScoreBound::Inclusive(min) => score < *min,
+            ScoreBound::Exclusive(min) => score <= *min,
+        }
+    }
+
+    fn is_within_max(&self, score: f64) -> bool {
+        match self {
+            ScoreBound::Inclusive(max) => score <= *max,
+            ScoreBound::Exclusive(max) => score < *max,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum LexBound {
+    Min,
+    Max,
+    Inclusive(Vec<u8>),
+    Exclusive(Vec<u8>),
+}
+
+impl LexBound {
+    fn is_below_min(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(min) => member < min.as_slice(),
+            LexBound::Exclusive(min) => member <= min.as_slice(),
+        }
+    }
+
+    fn is_within_max(&self, member: &[u8]) -> bool {
+        match self {
+            LexBound::Min => false,
+            LexBound::Max => true,
+            LexBound::Inclusive(max) => member <= max.as_slice(),
+            LexBound::Exclusive(max) => member < max.as_slice(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ZSet {
+    scores: HashMap<Vec<u8>, f64>,
+    index: SkipList,
+}
+
+impl PartialEq for ZSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.scores == other.scores
+    }
+}
+
+impl ZSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scores.is_empty()
+    }
+
+    pub fn score(&self, member: &[u8]) -> Option<f64> {
+        self.scores.get(member).copied()
+    }
+
+    pub fn insert(&mut self, member: &[u8], score: f64) -> bool {
+        let score = score + 0.0;
+
+        match self.scores.insert(member.to_vec(), score) {
+            Some(current) if current == score => false,
+            Some(current) => {
+                self.index.remove(current, member);
+                self.index.insert(score, member.to_vec());
+                false
+            }
+            None => {
+                self.index.insert(score, member.to_vec());
+                true
+            }
+        }
+    }
+
+    pub fn remove(&mut self, member: &[u8]) -> bool {
+        match self.scores.remove(member) {
+            Some(score) => self.index.remove(score, member),
+            None => false,
+        }
+    }
+
+    pub fn rank(&self, member: &[u8], reverse: bool) -> Option<usize> {
+        let score = self.score(member)?;
+        let rank = self
+            .index
+            .count_while(|current, other| current < score || (current == score && other < member));
+
+        match reverse {
+            true => Some(self.len() - 1 - rank),
+            false => Some(rank),
+        }
+    }
+
+    pub fn score_range(&self, min: &ScoreBound, max: &ScoreBound) -> Range<usize> {
+        let start = self.index.count_while(|score, _| min.is_below_min(score));
+        let end = self.index.count_while(|score, _| max.is_within_max(score));
+
+        start..end.max(start)
+    }
+
+    pub fn lex_range(&self, min: &LexBound, max: &LexBound) -> Range<usize> {
+        let start = self.index.count_while(|_, member| min.is_below_min(member));
+        let end = self
+            .index
+            .count_while(|_, member| max.is_within_max(member));
+
+        start..end.max(start)
+    }
+
+    pub fn iter_from(&self, rank: usize, reverse: bool) -> Iter<'_> {
+        self.index.iter_from(rank, reverse)
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        self.index.iter_from(0, false)
+    }
+}